@@ -0,0 +1,112 @@
+//! Generates:
+//! * `linked_days.rs`, which references every `dayXY` (or `yYYYY-dayXY`) dependency so that the
+//!   crates are linked and their registered solutions are found
+//! * `embedded_inputs.rs`, which with the `embed-inputs` feature is a lookup of every
+//!   `dayXY/input.txt` and `YYYY/dayXY/input.txt` in the workspace embedded with `include_str!`
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Whether a package is a day, eg. `day01` or `y2024-day17`
+fn is_day_package(name: &str) -> bool {
+    let day = match name.strip_prefix('y') {
+        Some(rest) => match rest.split_once("-day") {
+            Some((year, day)) if is_number(year) => day,
+            _ => return false,
+        },
+        None => match name.strip_prefix("day") {
+            Some(day) => day,
+            None => return false,
+        },
+    };
+
+    is_number(day)
+}
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let out_dir = Path::new(&out_dir);
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+
+    write_linked_days(out_dir);
+    write_embedded_inputs(out_dir, root);
+}
+
+/// Unused crates aren't linked, which would drop their `inventory` registrations
+fn write_linked_days(out_dir: &Path) {
+    println!("cargo:rerun-if-changed=Cargo.toml");
+
+    let manifest = fs::read_to_string("Cargo.toml").unwrap();
+    let source: String = manifest
+        .lines()
+        .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim()))
+        .filter(|name| is_day_package(name))
+        .map(|name| format!("use {} as _;\n", name.replace('-', "_")))
+        .collect();
+
+    fs::write(out_dir.join("linked_days.rs"), source).unwrap();
+}
+
+fn write_embedded_inputs(out_dir: &Path, root: &Path) {
+    // New days come with a change to Cargo.toml, which is already watched. Watching the whole
+    // root would include target/, and rebuild on every build.
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let mut arms = String::new();
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        // Days of the main year are at the root, other years in a directory per year
+        let mut year_dirs = vec![(None, root.to_path_buf())];
+        year_dirs.extend(subdirs(root).filter_map(|path| {
+            let year: u32 = path.file_name()?.to_str()?.parse().ok()?;
+            Some((Some(year), path))
+        }));
+
+        let mut days: Vec<(Option<u32>, u32, String)> = Vec::new();
+        for (year, dir) in year_dirs {
+            days.extend(subdirs(&dir).filter_map(|path| {
+                let day = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_prefix("day")?
+                    .parse()
+                    .ok()?;
+                // Watching the day's directory notices when its input is first downloaded
+                println!("cargo:rerun-if-changed={}", path.display());
+                let input = path.join("input.txt");
+
+                input
+                    .exists()
+                    .then(|| (year, day, input.display().to_string()))
+            }));
+        }
+        days.sort();
+
+        for (year, day, input) in days {
+            let year = year.map_or("crate::MAIN_YEAR".to_string(), |y| y.to_string());
+            println!("cargo:rerun-if-changed={}", input);
+            arms.push_str(&format!(
+                "        ({}, {}) => Some(include_str!({:?})),\n",
+                year, day, input
+            ));
+        }
+    }
+
+    let source = format!(
+        "#[allow(clippy::match_single_binding)]\npub fn embedded_input(year: u32, day: u32) -> Option<&'static str> {{\n    match (year, day) {{\n{}        _ => None,\n    }}\n}}\n",
+        arms
+    );
+    fs::write(out_dir.join("embedded_inputs.rs"), source).unwrap();
+}
+
+fn subdirs(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.is_dir())
+}
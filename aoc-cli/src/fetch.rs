@@ -0,0 +1,33 @@
+use aoc::config::config;
+use std::process::Command;
+
+/// The session token from the config (see [`aoc::config`]), which `aoc.sh` saves it to
+pub fn session_token() -> Option<String> {
+    config().session.clone()
+}
+
+/// Fetch a page from adventofcode.com using curl, authenticated with the session token if
+/// there is one. Returns the body, or an error message for failed requests.
+pub fn fetch(path: &str) -> Result<String, String> {
+    let url = format!("https://adventofcode.com/{}", path.trim_start_matches('/'));
+
+    let mut command = Command::new("curl");
+    command.args(["--silent", "--show-error", "--fail", &url]);
+    if let Some(token) = session_token() {
+        command.args(["--header", &format!("Cookie: session={}", token)]);
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to fetch {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| format!("Invalid response from {}: {}", url, e))
+}
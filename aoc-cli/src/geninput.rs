@@ -0,0 +1,66 @@
+use crate::parse_year;
+use aoc::config::config;
+use aoc::geninput::generator;
+use aoc::rng::Rng;
+use aoc::runner::{run_part, solutions};
+use std::time::Instant;
+
+/// Generate a random input for a day with its registered generator, either printing it or
+/// running both parts on it to see how the solution scales.
+pub fn main(mut args: impl Iterator<Item = String>) {
+    // Usage: aoc-cli geninput [--year <year>] --day <day> --size <size> [--seed <seed>] [--run]
+    let mut year = config().year;
+    let mut day = None;
+    let mut size = None;
+    let mut seed = 0;
+    let mut run = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("No value provided for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--year" => year = parse_year(&value()),
+            "--day" => day = Some(value().parse::<u32>().expect("Invalid day")),
+            "--size" => size = Some(value().parse::<usize>().expect("Invalid size")),
+            "--seed" => seed = value().parse::<u64>().expect("Invalid seed"),
+            "--run" => run = true,
+            _ => panic!("Unrecognized argument {}", arg),
+        }
+    }
+
+    let day = day.expect("No day provided, use --day <day>");
+    let size = size.expect("No size provided, use --size <size>");
+    let generate = generator(year, day)
+        .unwrap_or_else(|| panic!("Day {} of {} doesn't have an input generator", day, year))
+        .generate;
+
+    let start = Instant::now();
+    let input = generate(size, &mut Rng::new(seed));
+
+    if !run {
+        print!("{}", input);
+        return;
+    }
+
+    println!(
+        "Generated {} bytes in {:.3} ms",
+        input.len(),
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+
+    let solution = solutions()
+        .into_iter()
+        .find(|s| s.year == year && s.day == day)
+        .unwrap_or_else(|| panic!("Day {} of {} isn't registered with aoc-cli", day, year));
+
+    for part in [1, 2] {
+        let result = run_part(day, part, &input, solution.part(part)).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        });
+        println!("  Part {}: {} ({})", part, result.answer, result.summary());
+    }
+}
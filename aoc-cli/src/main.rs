@@ -0,0 +1,76 @@
+mod fetch;
+mod geninput;
+mod profile;
+mod puzzle;
+mod run;
+mod trace;
+mod wait;
+mod watch;
+
+use aoc::runner::MAIN_YEAR;
+use std::path::{Path, PathBuf};
+
+include!(concat!(env!("OUT_DIR"), "/linked_days.rs"));
+
+/// Root of the workspace this binary was built from
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// Where downloaded inputs and puzzle pages are kept, `inputs/` in the workspace unless
+/// configured otherwise
+fn input_dir() -> PathBuf {
+    aoc::config::config()
+        .input_dir
+        .clone()
+        .unwrap_or_else(|| workspace_root().join("inputs"))
+}
+
+/// Cargo's target directory for the workspace
+fn target_dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| workspace_root().join("target"))
+}
+
+/// `base` for [`MAIN_YEAR`], or a directory for the year under it for other years
+fn year_dir(base: &Path, year: u32) -> PathBuf {
+    if year == MAIN_YEAR {
+        base.to_path_buf()
+    } else {
+        base.join(year.to_string())
+    }
+}
+
+/// Directory of a day's crate, eg. `day01` or `2024/day17`
+fn day_dir(year: u32, day: u32) -> PathBuf {
+    year_dir(&workspace_root(), year).join(format!("day{:02}", day))
+}
+
+/// Downloaded copy of a day's input, eg. `inputs/day01.txt` or `inputs/2024/day17.txt`
+fn cached_input(year: u32, day: u32) -> PathBuf {
+    year_dir(&input_dir(), year).join(format!("day{:02}.txt", day))
+}
+
+fn parse_year(arg: &str) -> u32 {
+    arg.parse()
+        .ok()
+        .filter(|year| *year >= 2015)
+        .unwrap_or_else(|| panic!("Invalid year {}", arg))
+}
+
+fn main() {
+    // Usage: aoc-cli [run|watch|puzzle|wait|profile|geninput|trace] [options]
+    let mut args = std::env::args().skip(1).peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("watch") => watch::main(args.skip(1)),
+        Some("puzzle") => puzzle::main(args.skip(1)),
+        Some("wait") => wait::main(args.skip(1)),
+        Some("profile") => profile::main(args.skip(1)),
+        Some("geninput") => geninput::main(args.skip(1)),
+        Some("trace") => trace::main(args.skip(1)),
+        Some("run") => run::main(args.skip(1)),
+        _ => run::main(args),
+    }
+}
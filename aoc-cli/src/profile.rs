@@ -0,0 +1,101 @@
+use crate::parse_year;
+use crate::run::input_for;
+use aoc::config::config;
+use aoc::runner::{package_name, solutions};
+
+/// Keep re-running the solution until at least this long has been sampled, so that fast
+/// solutions still produce a useful flamegraph
+#[cfg(feature = "profile")]
+const MIN_SAMPLE_TIME: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Profile one part of a day with a sampling profiler, writing a flamegraph SVG to
+/// `target/profiles/`.
+pub fn main(mut args: impl Iterator<Item = String>) {
+    // Usage: aoc-cli profile [--year <year>] --day <day> [--part <part>] [--frequency <hz>]
+    let mut year = config().year;
+    let mut day = None;
+    let mut part = 1;
+    let mut frequency = 1000;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("No value provided for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--year" => year = parse_year(&value()),
+            "--day" => day = Some(value().parse::<u32>().expect("Invalid day")),
+            "--part" => part = value().parse::<u32>().expect("Invalid part"),
+            "--frequency" => frequency = value().parse::<i32>().expect("Invalid frequency"),
+            _ => panic!("Unrecognized argument {}", arg),
+        }
+    }
+
+    let day = day.expect("No day provided, use --day <day>");
+    let solution = solutions()
+        .into_iter()
+        .find(|s| s.year == year && s.day == day)
+        .unwrap_or_else(|| panic!("Day {} of {} isn't registered with aoc-cli", day, year))
+        .part(part);
+
+    let input = input_for(year, day);
+    let name = format!("{}-part{}", package_name(year, day), part);
+
+    if let Err(e) = solution(&input) {
+        eprintln!("error: day {} part {}: {}", day, part, e);
+        std::process::exit(1);
+    }
+
+    profile(&name, frequency, || {
+        let _ = solution(&input);
+    });
+}
+
+#[cfg(feature = "profile")]
+fn profile(name: &str, frequency: i32, mut run: impl FnMut()) {
+    use std::time::Instant;
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(frequency)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .expect("Failed to start the profiler");
+
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < MIN_SAMPLE_TIME {
+        run();
+        runs += 1;
+    }
+    let elapsed = start.elapsed();
+
+    let report = guard
+        .report()
+        .build()
+        .expect("Failed to build the profile report");
+
+    let dir = crate::target_dir().join("profiles");
+    std::fs::create_dir_all(&dir).expect("Failed to create profiles directory");
+    let path = dir.join(format!("{}.svg", name));
+    let file = std::fs::File::create(&path)
+        .unwrap_or_else(|e| panic!("Failed to create {}: {}", path.display(), e));
+    report
+        .flamegraph(file)
+        .expect("Failed to write the flamegraph");
+
+    println!(
+        "Profiled {} run(s) in {:.3} ms, flamegraph written to {}",
+        runs,
+        elapsed.as_secs_f64() * 1000.0,
+        path.display()
+    );
+}
+
+#[cfg(not(feature = "profile"))]
+fn profile(_name: &str, _frequency: i32, _run: impl FnMut()) {
+    eprintln!(
+        "Profiling needs the profile feature: cargo run --release -p aoc-cli --features profile -- profile ..."
+    );
+    std::process::exit(1);
+}
@@ -0,0 +1,260 @@
+use crate::fetch::fetch;
+use crate::{cached_input, day_dir, parse_year};
+use aoc::config::config;
+use std::fs;
+
+/// Decode the handful of HTML entities that show up in puzzle descriptions
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Split HTML into tags (including the angle brackets) and the text between them
+fn tokens(html: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let end = if rest.starts_with('<') {
+            rest.find('>').map_or(rest.len(), |i| i + 1)
+        } else {
+            rest.find('<').unwrap_or(rest.len())
+        };
+
+        tokens.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+
+    tokens
+}
+
+/// The tag name of a token, and whether it's a closing tag. None for text.
+fn tag_name(token: &str) -> Option<(&str, bool)> {
+    let inner = token.strip_prefix('<')?.strip_suffix('>')?;
+    let (inner, closing) = match inner.strip_prefix('/') {
+        Some(inner) => (inner, true),
+        None => (inner, false),
+    };
+
+    let name = inner.split(|c: char| c.is_whitespace()).next()?;
+    Some((name, closing))
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// The contents of every `<article>`, which hold the description of each part
+fn articles(html: &str) -> Vec<&str> {
+    html.split("<article")
+        .skip(1)
+        .filter_map(|a| {
+            let start = a.find('>')? + 1;
+            let end = a.find("</article>")?;
+            Some(&a[start..end])
+        })
+        .collect()
+}
+
+/// Convert the puzzle description HTML into markdown. Only handles the small set of tags
+/// used on puzzle pages; anything else is dropped and its text kept.
+pub fn html_to_markdown(html: &str) -> String {
+    let mut md = String::new();
+    let mut in_pre = false;
+    let mut link = None;
+
+    for article in articles(html) {
+        for token in tokens(article) {
+            let Some((name, closing)) = tag_name(token) else {
+                // Newlines between block elements are just formatting
+                if in_pre || !token.trim().is_empty() || !token.contains('\n') {
+                    md.push_str(&decode_entities(token));
+                }
+                continue;
+            };
+
+            match (name, closing) {
+                ("h2", false) => md.push_str("## "),
+                ("h2", true) | ("p", true) | ("ul", true) => md.push_str("\n\n"),
+                ("li", false) => md.push_str("- "),
+                ("li", true) => md.push('\n'),
+                ("pre", false) => {
+                    in_pre = true;
+                    md.push_str("```\n");
+                }
+                ("pre", true) => {
+                    in_pre = false;
+                    if !md.ends_with('\n') {
+                        md.push('\n');
+                    }
+                    md.push_str("```\n\n");
+                }
+                ("code", _) if !in_pre => md.push('`'),
+                ("em", _) if !in_pre => md.push('*'),
+                ("a", false) => {
+                    link = attribute(token, "href").map(str::to_string);
+                    md.push('[');
+                }
+                ("a", true) => {
+                    md.push_str(&format!("]({})", link.take().unwrap_or_default()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    md.trim_end().to_string() + "\n"
+}
+
+/// The text of every `<pre><code>` block, in order
+pub fn code_blocks(html: &str) -> Vec<String> {
+    html.split("<pre><code>")
+        .skip(1)
+        .filter_map(|block| block.split_once("</code></pre>"))
+        .map(|(code, _)| {
+            // Strip any highlighting tags, eg. <em>
+            let text: String = tokens(code)
+                .into_iter()
+                .filter(|t| tag_name(t).is_none())
+                .collect();
+            decode_entities(&text)
+        })
+        .collect()
+}
+
+/// Download a day's puzzle description into `dayXY/puzzle.md`, and extract the example
+/// into `example.txt` if there isn't one already
+pub fn main(mut args: impl Iterator<Item = String>) {
+    // Usage: aoc-cli puzzle [--year <year>] --day <day> [--refresh] [--example-block <n>]
+    //                       [--no-example] [--force]
+    let mut year = config().year;
+    let mut day = None;
+    let mut refresh = false;
+    let mut example_block = 1;
+    let mut write_example = true;
+    let mut force = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("No value provided for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--year" => year = parse_year(&value()),
+            "--day" => day = Some(value().parse::<u32>().expect("Invalid day")),
+            "--refresh" => refresh = true,
+            "--example-block" => {
+                // Blocks are numbered from 1
+                example_block = value()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .expect("Invalid example block")
+            }
+            "--no-example" => write_example = false,
+            "--force" => force = true,
+            _ => panic!("Unrecognized argument {}", arg),
+        }
+    }
+
+    let day = day.expect("No day provided");
+    let dir = day_dir(year, day);
+    if !dir.exists() {
+        panic!(
+            "{} does not exist, create it first with ./aoc.sh new {} {}",
+            dir.display(),
+            day,
+            year
+        );
+    }
+
+    // Cache the page, since part 2 only shows up once part 1 is solved use --refresh to update
+    let cache = cached_input(year, day).with_extension("html");
+    let html = match fs::read_to_string(&cache) {
+        Ok(html) if !refresh => html,
+        _ => {
+            println!("Fetching puzzle for {} day {}", year, day);
+            let html = fetch(&format!("{}/day/{}", year, day)).unwrap_or_else(|e| panic!("{}", e));
+            fs::create_dir_all(cache.parent().unwrap()).expect("Failed to create inputs directory");
+            fs::write(&cache, &html).expect("Failed to cache puzzle");
+            html
+        }
+    };
+
+    let puzzle = dir.join("puzzle.md");
+    fs::write(&puzzle, html_to_markdown(&html)).expect("Failed to write puzzle.md");
+    println!("Wrote {}", puzzle.display());
+
+    if !write_example {
+        return;
+    }
+
+    let example = dir.join("example.txt");
+    let existing = fs::read_to_string(&example).unwrap_or_default();
+    if !existing.trim().is_empty() && !force {
+        println!("{} already has contents, skipping", example.display());
+        return;
+    }
+
+    match code_blocks(&html).get(example_block - 1) {
+        Some(block) => {
+            fs::write(&example, block).expect("Failed to write example.txt");
+            println!(
+                "Wrote code block {} to {}",
+                example_block,
+                example.display()
+            );
+        }
+        None => println!(
+            "No code block {} found, example.txt not written",
+            example_block
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Secret Entrance ---</h2><p>The dial starts at <code>50</code>, <em>see</em> <a href="/2025/about">here</a>.</p>
+<pre><code>L68
+<em>L30</em>
+R48 &lt; 5
+</code></pre>
+<ul><li>One</li><li>Two</li></ul>
+</article>
+<p>Not part of the puzzle</p>
+</main>"#;
+
+    #[test]
+    fn test_html_to_markdown() {
+        let expected = "\
+## --- Day 1: Secret Entrance ---
+
+The dial starts at `50`, *see* [here](/2025/about).
+
+```
+L68
+L30
+R48 < 5
+```
+
+- One
+- Two
+";
+
+        assert_eq!(html_to_markdown(PAGE), expected);
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        assert_eq!(code_blocks(PAGE), vec!["L68\nL30\nR48 < 5\n"]);
+    }
+}
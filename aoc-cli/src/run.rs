@@ -0,0 +1,276 @@
+use crate::{cached_input, day_dir, parse_year};
+use aoc::answers::{self, diff, AnswerStore};
+use aoc::config::config;
+use aoc::memory::{self, MemoryUsage};
+use aoc::runner::{
+    parse_time_limit, solutions, solve_with_limit, Answer, OutputFormat, RunResult, Solved,
+    TimedOut,
+};
+use aoc::timing::{self, Phase};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+/// Get the real input for a day, either embedded in the binary or read from the workspace,
+/// falling back to the downloaded copy in the input directory
+pub fn input_for(year: u32, day: u32) -> String {
+    if let Some(input) = embedded_input(year, day) {
+        return input.to_string();
+    }
+
+    let mut path = day_dir(year, day).join("input.txt");
+    if !path.exists() {
+        path = cached_input(year, day);
+    }
+
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e))
+}
+
+type PartFn = fn(&str) -> aoc::Result<Answer>;
+
+/// What solving one part produced, along with the phases it timed
+type Outcome = Result<(Solved<aoc::Result<Answer>>, Vec<Phase>), TimedOut>;
+
+fn solve_part(input: &str, solution: PartFn, time_limit: Option<Duration>) -> Outcome {
+    solve_with_limit(input, solution, time_limit).map(|solved| (solved, timing::take_report()))
+}
+
+/// Solve every task using up to `jobs` threads at once, returning the outcomes in order.
+///
+/// With a `memory_limit` in bytes, no new task is started while the process's RSS is at or over
+/// it, unless nothing else is running to bring it back down.
+fn solve_parallel(
+    tasks: &[(&str, PartFn)],
+    time_limit: Option<Duration>,
+    jobs: usize,
+    memory_limit: Option<u64>,
+) -> Vec<Outcome> {
+    let next = AtomicUsize::new(0);
+    // Held while deciding whether to start a task, so idle workers can't all see that nothing
+    // is running and start at once
+    let running = Mutex::new(0usize);
+    let outcomes: Vec<Mutex<Option<Outcome>>> = tasks.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(tasks.len()) {
+            // Match the main thread's stack size, so deeply recursive solutions behave the same
+            thread::Builder::new()
+                .stack_size(8 * 1024 * 1024)
+                .spawn_scoped(scope, || loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&(input, solution)) = tasks.get(i) else {
+                        break;
+                    };
+                    loop {
+                        let mut count = running.lock().unwrap();
+                        let wait = memory_limit.is_some_and(|limit| {
+                            *count > 0 && memory::current_rss().is_some_and(|rss| rss >= limit)
+                        });
+                        if !wait {
+                            *count += 1;
+                            break;
+                        }
+                        drop(count);
+                        thread::sleep(Duration::from_millis(10));
+                    }
+
+                    let outcome = solve_part(input, solution, time_limit);
+                    *running.lock().unwrap() -= 1;
+                    *outcomes[i].lock().unwrap() = Some(outcome);
+                })
+                .expect("Failed to spawn worker thread");
+        }
+    });
+
+    outcomes
+        .into_iter()
+        .map(|outcome| outcome.into_inner().unwrap().expect("every task is solved"))
+        .collect()
+}
+
+/// Run registered days against their real inputs, printing answers and timings.
+///
+/// Answers are checked against the ones from previous runs, exiting with an error if any have
+/// changed. Use `--update` to accept the new answers.
+///
+/// With `--jobs N`, up to N parts are solved at once on separate threads. Timings are less
+/// reliable when parts compete for cores, and memory usage isn't reported, since it can only be
+/// measured for the whole process. `--memory-limit <MiB>` holds off on starting more parts while
+/// the process uses at least that much (on Linux). It can't stop a part that's already running
+/// from going over, and only applies along with `--jobs`.
+pub fn main(mut args: impl Iterator<Item = String>) {
+    // Usage: aoc-cli run [--year <year>] [--day <day>] [--part <part>] [--output text|json]
+    //                    [--time-limit <secs>] [--jobs <n> [--memory-limit <MiB>]] [--update]
+    let mut year = config().year;
+    let mut day = None;
+    let mut part = None;
+    let mut output = OutputFormat::Text;
+    let mut time_limit = None;
+    let mut jobs = 1;
+    let mut memory_limit = None;
+    let mut update = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("No value provided for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--year" => year = parse_year(&value()),
+            "--day" => day = Some(value().parse::<u32>().expect("Invalid day")),
+            "--part" => part = Some(value().parse::<u32>().expect("Invalid part")),
+            "--output" => {
+                let value = value();
+                output = OutputFormat::from_arg(&value)
+                    .unwrap_or_else(|e| panic!("Invalid output format {}, {}", value, e));
+            }
+            "--time-limit" => {
+                let value = value();
+                let limit = parse_time_limit(&value)
+                    .unwrap_or_else(|e| panic!("Invalid time limit {}, {}", value, e));
+                time_limit = Some(limit);
+            }
+            "--jobs" => {
+                jobs = value()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .expect("Invalid number of jobs")
+            }
+            "--memory-limit" => {
+                let bytes = value()
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .and_then(|mib| mib.checked_mul(1024 * 1024))
+                    .expect("Invalid memory limit");
+                memory_limit = Some(bytes);
+            }
+            "--update" => update = true,
+            _ => panic!("Unrecognized argument {}", arg),
+        }
+    }
+
+    if memory_limit.is_some() && jobs == 1 {
+        panic!("--memory-limit only applies when solving in parallel with --jobs");
+    }
+
+    let mut answers = AnswerStore::load(answers::default_path());
+    let mut changed = 0;
+
+    let days: Vec<_> = solutions()
+        .into_iter()
+        .filter(|s| s.year == year && day.is_none_or(|d| d == s.day))
+        .map(|solution| (solution, input_for(year, solution.day)))
+        .collect();
+    let parts: Vec<u32> = [1, 2]
+        .into_iter()
+        .filter(|p| part.is_none_or(|part| part == *p))
+        .collect();
+
+    // Solve everything up front when running in parallel, otherwise each part is solved as
+    // it's reached so results stream out
+    let mut solved: HashMap<(u32, u32), Outcome> = HashMap::new();
+    if jobs > 1 {
+        let keys: Vec<(u32, u32)> = days
+            .iter()
+            .flat_map(|(s, _)| parts.iter().map(|&p| (s.day, p)))
+            .collect();
+        let tasks: Vec<(&str, PartFn)> = days
+            .iter()
+            .flat_map(|(s, input)| parts.iter().map(|&p| (input.as_str(), s.part(p))))
+            .collect();
+        solved = keys
+            .into_iter()
+            .zip(solve_parallel(&tasks, time_limit, jobs, memory_limit))
+            .collect();
+    }
+
+    let mut total = Duration::ZERO;
+    for (solution, input) in &days {
+        if output == OutputFormat::Text {
+            println!("Day {:02}", solution.day);
+        }
+
+        for &p in &parts {
+            let outcome = solved
+                .remove(&(solution.day, p))
+                .unwrap_or_else(|| solve_part(input, solution.part(p), time_limit));
+            let ((answer, solve, memory), phases) = outcome.unwrap_or_else(|timed_out| {
+                eprintln!(
+                    "Day {:02} part {} {}, aborting after {:.3} ms total",
+                    solution.day,
+                    p,
+                    timed_out,
+                    (total + timed_out.limit).as_secs_f64() * 1000.0
+                );
+                std::process::exit(1);
+            });
+            let answer = answer.unwrap_or_else(|e| {
+                eprintln!("error: day {} part {}: {}", solution.day, p, e);
+                std::process::exit(1);
+            });
+            total += solve;
+
+            // Parts running side by side share the process, so its memory can't be split
+            // between them
+            let memory = if jobs > 1 {
+                MemoryUsage::default()
+            } else {
+                memory
+            };
+
+            let result = RunResult {
+                day: solution.day,
+                part: p,
+                answer,
+                parse: Duration::ZERO,
+                solve,
+                memory,
+            };
+
+            match output {
+                OutputFormat::Text => {
+                    println!("  Part {}: {} ({})", p, result.answer, result.summary());
+                    for phase in phases {
+                        println!("    {}", phase);
+                    }
+                }
+                OutputFormat::Json => println!("{}", result.to_json()),
+            }
+
+            let answer = result.answer.to_string();
+            match answers.get(year, solution.day, p) {
+                Some(previous) if previous == answer => {}
+                Some(previous) if !update => {
+                    eprint!(
+                        "Day {:02} part {} answer changed:\n{}",
+                        solution.day,
+                        p,
+                        diff(previous, &answer)
+                    );
+                    changed += 1;
+                }
+                _ => answers.set(year, solution.day, p, answer),
+            }
+        }
+    }
+
+    if output == OutputFormat::Text {
+        println!("\nTotal: {:.3} ms", total.as_secs_f64() * 1000.0);
+    }
+
+    if changed > 0 {
+        eprintln!(
+            "{} answer(s) changed, re-run with --update if that's expected",
+            changed
+        );
+        std::process::exit(1);
+    }
+}
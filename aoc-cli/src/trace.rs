@@ -0,0 +1,169 @@
+use aoc::debug_view::{DebugView, Section};
+use aoc::search::{Trace, TraceEvent};
+use aoc::visualize;
+use std::collections::{HashMap, HashSet};
+
+/// The first `Coord(row, col)` in a state's `Debug` text, to place it on a grid
+fn coord_in(state: &str) -> Option<(i64, i64)> {
+    let (_, rest) = state.split_once("Coord(")?;
+    let (inner, _) = rest.split_once(')')?;
+    let (row, col) = inner.split_once(',')?;
+    Some((row.trim().parse().ok()?, col.trim().parse().ok()?))
+}
+
+/// How far a replay has got: everything up to and including one expansion
+struct Replay<'a> {
+    trace: &'a Trace<String>,
+    /// Which expansion this is, from 1
+    step: usize,
+    total: usize,
+    state: usize,
+    cost: u64,
+    /// The moves looked at from `state`, with whether each found a new lowest cost
+    moves: Vec<(usize, u64, bool)>,
+    /// Lowest cost found so far for each state
+    best: &'a HashMap<usize, u64>,
+    /// Every state expanded before this one
+    expanded: &'a HashSet<usize>,
+}
+
+impl Replay<'_> {
+    /// The states with coordinates laid out as a grid: `@` for the state being expanded, `#`
+    /// for those already expanded and `+` for those seen but not expanded yet
+    fn grid(&self) -> Option<Section> {
+        let coords: Vec<(i64, i64)> = self
+            .trace
+            .states
+            .iter()
+            .filter_map(|s| coord_in(s))
+            .collect();
+        let rows = coords.iter().map(|c| c.0);
+        let cols = coords.iter().map(|c| c.1);
+        let (top, bottom) = (rows.clone().min()?, rows.max()?);
+        let (left, right) = (cols.clone().min()?, cols.max()?);
+
+        let mut lines = vec![vec!['.'; (right - left + 1) as usize]; (bottom - top + 1) as usize];
+        let mut mark = |state: usize, c: char| {
+            if let Some((row, col)) = coord_in(&self.trace.states[state]) {
+                let cell = &mut lines[(row - top) as usize][(col - left) as usize];
+                // States sharing a position show the furthest along of them
+                if "@#+.".find(c) < "@#+.".find(*cell) {
+                    *cell = c;
+                }
+            }
+        };
+        self.best.keys().for_each(|&s| mark(s, '+'));
+        self.expanded.iter().for_each(|&s| mark(s, '#'));
+        mark(self.state, '@');
+
+        let mut section = Section::new("grid");
+        section.lines = lines.into_iter().map(String::from_iter).collect();
+        Some(section)
+    }
+}
+
+impl DebugView for Replay<'_> {
+    fn sections(&self) -> Vec<Section> {
+        let mut sections = vec![Section::new("expansion")
+            .field("step", format!("{} of {}", self.step, self.total))
+            .field("state", &self.trace.states[self.state])
+            .field("cost", self.cost)
+            .field("expanded", self.expanded.len())
+            .field(
+                "frontier",
+                self.best.len().saturating_sub(self.expanded.len() + 1),
+            )];
+
+        let mut moves = Section::new("moves");
+        for &(to, cost, improved) in &self.moves {
+            let verdict = if improved {
+                "new best".to_string()
+            } else {
+                format!("best is {}", self.best[&to])
+            };
+            moves = moves.line(format!(
+                "{} +{} = {} ({})",
+                self.trace.states[to],
+                cost,
+                self.cost + cost,
+                verdict
+            ));
+        }
+        sections.push(moves);
+
+        sections.extend(self.grid());
+        sections
+    }
+}
+
+/// Replay a search trace saved with `aoc::search::Trace::save`, stepping through each state
+/// it expanded along with the moves it looked at from there. States containing a `Coord` are
+/// also shown on a grid. Stepping follows the `[visualize]` config, like `--visualize`.
+pub fn main(mut args: impl Iterator<Item = String>) {
+    // Usage: aoc-cli trace <file>
+    let path = args.next().expect("No trace file provided");
+    if let Some(arg) = args.next() {
+        panic!("Unrecognized argument {}", arg);
+    }
+
+    let text =
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e));
+    let trace = Trace::parse(&text).unwrap_or_else(|e| panic!("Invalid trace {}: {}", path, e));
+
+    let total = trace.expanded().count();
+    println!("{} states seen, {} expanded", trace.states.len(), total);
+    visualize::enable();
+
+    let mut best: HashMap<usize, u64> = HashMap::new();
+    let mut expanded: HashSet<usize> = HashSet::new();
+    let mut events = trace.events.iter().peekable();
+    let mut step = 0;
+
+    while let Some(event) = events.next() {
+        let TraceEvent::Expand { state, cost } = *event else {
+            continue;
+        };
+        step += 1;
+        best.entry(state).or_insert(cost);
+
+        let mut moves = Vec::new();
+        while let Some(&&TraceEvent::Edge {
+            to,
+            cost: step_cost,
+            ..
+        }) = events.peek()
+        {
+            events.next();
+            let improved = best.get(&to).is_none_or(|&b| cost + step_cost < b);
+            if improved {
+                best.insert(to, cost + step_cost);
+            }
+            moves.push((to, step_cost, improved));
+        }
+
+        let replay = Replay {
+            trace: &trace,
+            step,
+            total,
+            state,
+            cost,
+            moves,
+            best: &best,
+            expanded: &expanded,
+        };
+        visualize::step(&format!("expansion {}", step), &replay);
+        expanded.insert(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coord_in() {
+        assert_eq!(coord_in("Coord(3, -4)"), Some((3, -4)));
+        assert_eq!(coord_in("(Coord(0, 12), East, 2)"), Some((0, 12)));
+        assert_eq!(coord_in("GridState { steps: 1 }"), None);
+    }
+}
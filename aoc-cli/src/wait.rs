@@ -0,0 +1,184 @@
+use crate::fetch::{fetch, session_token};
+use crate::{cached_input, day_dir, parse_year, workspace_root};
+use aoc::config::config;
+use std::fs;
+use std::io::Write;
+use std::process::Command;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Puzzles unlock at midnight US Eastern time. December is always EST (UTC-5), so there's
+/// no daylight savings to worry about.
+const UNLOCK_UTC_OFFSET_HOURS: i64 = -5;
+
+/// Downloads are retried with exponential backoff, since the servers are busy right at unlock
+/// and the input isn't always available the instant the clock ticks over
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_ATTEMPTS: u32 = 10;
+
+/// Days since the unix epoch for a date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // Howard Hinnant's algorithm, treating March as the first month of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Unix timestamp at which the given December day's puzzle unlocks
+fn unlock_time(year: u32, day: u32) -> i64 {
+    days_from_civil(year as i64, 12, day as i64) * 86400 - UNLOCK_UTC_OFFSET_HOURS * 3600
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System clock is before 1970")
+        .as_secs() as i64
+}
+
+fn format_countdown(seconds: i64) -> String {
+    let (days, rest) = (seconds / 86400, seconds % 86400);
+    let (hours, minutes, seconds) = (rest / 3600, rest % 3600 / 60, rest % 60);
+
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    }
+}
+
+/// Sleep until the unlock time, updating a countdown on the current line
+fn wait_until(unlock: i64) {
+    loop {
+        let remaining = unlock - now();
+        if remaining <= 0 {
+            break;
+        }
+
+        print!("\rUnlocks in {}  ", format_countdown(remaining));
+        std::io::stdout().flush().ok();
+
+        // Sleep to the next whole second, so the unlock isn't overshot
+        let subsec = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .subsec_nanos();
+        sleep(Duration::from_nanos(1_000_000_000 - subsec as u64));
+    }
+    println!();
+}
+
+/// Download the input, retrying with exponential backoff
+fn download_input(year: u32, day: u32) -> Result<String, String> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        match fetch(&format!("{}/day/{}/input", year, day)) {
+            Ok(input) => return Ok(input),
+            Err(e) if attempt == MAX_ATTEMPTS => return Err(e),
+            Err(e) => {
+                println!("{}, retrying in {}s", e, backoff.as_secs());
+                sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Wait for a day's puzzle to unlock, then download the input and create the day's project
+pub fn main(mut args: impl Iterator<Item = String>) {
+    // Usage: aoc-cli wait [--year <year>] --day <day>
+    let mut year = config().year;
+    let mut day = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("No value provided for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--year" => year = parse_year(&value()),
+            "--day" => day = Some(value().parse::<u32>().expect("Invalid day")),
+            _ => panic!("Unrecognized argument {}", arg),
+        }
+    }
+
+    let day = day.expect("No day provided");
+    // From 2025 on there are only 12 days of puzzles
+    let last_day = if year >= 2025 { 12 } else { 25 };
+    if !(1..=last_day).contains(&day) {
+        panic!("Day must be between 1 and {}", last_day);
+    }
+
+    wait_until(unlock_time(year, day));
+
+    println!("Fetching input for {} day {}", year, day);
+    let input = download_input(year, day).unwrap_or_else(|e| panic!("{}", e));
+
+    // With the default input_dir this is where aoc.sh looks, so it won't download it again
+    let cached = cached_input(year, day);
+    fs::create_dir_all(cached.parent().unwrap()).expect("Failed to create inputs directory");
+    fs::write(&cached, &input).expect("Failed to save input");
+
+    let dir = day_dir(year, day);
+    if !dir.exists() {
+        let mut command = Command::new(workspace_root().join("aoc.sh"));
+        command
+            .args(["new", &day.to_string(), &year.to_string()])
+            .current_dir(workspace_root());
+        // Pass the session on so aoc.sh doesn't prompt for one
+        if let Some(token) = session_token() {
+            command.env("AOC_SESSION", token);
+        }
+
+        if !command.status().expect("Failed to run aoc.sh").success() {
+            panic!("aoc.sh new {} failed", day);
+        }
+    }
+
+    fs::write(dir.join("input.txt"), &input).expect("Failed to write input.txt");
+    println!("Wrote {}", dir.join("input.txt").display());
+
+    if let Some(editor) = std::env::var_os("EDITOR") {
+        Command::new(editor)
+            .arg(dir.join("src").join("part_1.rs"))
+            .status()
+            .expect("Failed to open editor");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_time() {
+        // 2025-12-01T05:00:00Z
+        assert_eq!(unlock_time(2025, 1), 1764565200);
+        // 2025-12-12T05:00:00Z
+        assert_eq!(unlock_time(2025, 12), 1765515600);
+    }
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(59), "00:00:59");
+        assert_eq!(format_countdown(3 * 3600 + 61), "03:01:01");
+        assert_eq!(format_countdown(86400 + 5), "1d 00:00:05");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{day_dir, parse_year, target_dir, workspace_root};
+use aoc::config::config;
+use aoc::runner::package_name;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread::sleep;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often to check for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Editors often write files in several steps, so wait for things to settle after a change
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Latest modification time of any file under `path`
+fn latest_mtime(path: &Path) -> Option<SystemTime> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.is_file() {
+        return metadata.modified().ok();
+    }
+
+    fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| latest_mtime(&entry.ok()?.path()))
+        .max()
+}
+
+/// The day's sources and input files, plus the shared library
+fn watched_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![dir.join("src"), workspace_root().join("aoc").join("src")];
+    if let Ok(entries) = fs::read_dir(dir) {
+        paths.extend(
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "txt")),
+        );
+    }
+
+    paths
+}
+
+fn snapshot(dir: &Path) -> Option<SystemTime> {
+    watched_paths(dir)
+        .iter()
+        .filter_map(|p| latest_mtime(p))
+        .max()
+}
+
+/// Build the day, then run it and report how long the run took (not including the build)
+fn build_and_run(dir: &Path, package: &str, part: &str, input: &str) {
+    let status = Command::new("cargo")
+        .args(["build", "--release", "--quiet", "--package", package])
+        .current_dir(workspace_root())
+        .status()
+        .expect("Failed to run cargo");
+
+    if !status.success() {
+        return;
+    }
+
+    let start = Instant::now();
+    let status = Command::new(target_dir().join("release").join(package))
+        .args(["--part", part, "--input", input])
+        .current_dir(dir)
+        .status()
+        .expect("Failed to run solution");
+    let elapsed = start.elapsed();
+
+    if status.success() {
+        println!("Took {:.3} ms", elapsed.as_secs_f64() * 1000.0);
+    }
+}
+
+/// Re-run a day's solution whenever its source or inputs change
+pub fn main(mut args: impl Iterator<Item = String>) {
+    // Usage: aoc-cli watch [--year <year>] --day <day> [--part <part>] [--input <input>]
+    let mut year = config().year;
+    let mut day = None;
+    let mut part = "1".to_string();
+    let mut input = "example".to_string();
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| panic!("No value provided for {}", arg))
+        };
+
+        match arg.as_str() {
+            "--year" => year = parse_year(&value()),
+            "--day" => day = Some(value().parse::<u32>().expect("Invalid day")),
+            "--part" => part = value(),
+            "--input" => input = value(),
+            _ => panic!("Unrecognized argument {}", arg),
+        }
+    }
+
+    let day = day.expect("No day provided");
+    let dir = day_dir(year, day);
+    if !dir.exists() {
+        panic!("{} does not exist", dir.display());
+    }
+    let package = package_name(year, day);
+
+    let mut last = None;
+    loop {
+        let current = snapshot(&dir);
+
+        if current != last {
+            sleep(DEBOUNCE);
+            last = snapshot(&dir);
+
+            println!("\n=== {} part {} ({}) ===", package, part, input);
+            build_and_run(&dir, &package, &part, &input);
+        }
+
+        sleep(POLL_INTERVAL);
+    }
+}
@@ -3,37 +3,74 @@
 # Get the directory where the script is located
 ROOT_DIR="$(dirname "$(realpath "$0")")"
 
+# Days for this year live at the root (day01), days from other years under a directory
+# per year (2024/day17). Keep in sync with aoc::runner::MAIN_YEAR.
+MAIN_YEAR=2025
+
+# Directory for a year's days or inputs under the given base directory
+function year_dir() {
+    local base="$1"
+    local year="$2"
+
+    if [ "$year" = "$MAIN_YEAR" ]; then
+        echo "$base"
+    else
+        echo "${base}/${year}"
+    fi
+}
+
+# The config file the Rust tooling reads, see aoc::config
+CONFIG_FILE="${AOC_CONFIG:-${XDG_CONFIG_HOME:-$HOME/.config}/aoc/config.toml}"
+
+# Top level `session = "..."` from the config file, if there is one
+function config_session() {
+    if [ -f "$CONFIG_FILE" ]; then
+        # Settings after a [section] header belong to that section
+        awk '/^[[:space:]]*\[/ { exit }
+            /^[[:space:]]*session[[:space:]]*=/ {
+                sub(/^[^=]*=[[:space:]]*"/, ""); sub(/".*$/, ""); print; exit
+            }' "$CONFIG_FILE"
+    fi
+}
+
+# Session token from $AOC_SESSION or the config file, the same as aoc::config. If there isn't
+# one, ask for it and save it to the config file.
 function get_session_token() {
-    if [ -f "${ROOT_DIR}/.env" ]; then
-        source "${ROOT_DIR}/.env"
+    if [ -z "$AOC_SESSION" ]; then
+        AOC_SESSION="$(config_session)"
     fi
 
     if [ -z "$AOC_SESSION" ]; then
-        echo "AOC session token not found in .env"
+        echo "AOC session token not found in ${CONFIG_FILE} or \$AOC_SESSION"
         echo -n "Please enter your session token (input will be hidden): "
-        read -r -s session_token
+        read -r -s AOC_SESSION
         echo  # New line after hidden input
 
-        # Write to .env, creating it if it doesn't exist
-        if [ ! -f "${ROOT_DIR}/.env" ]; then
-            echo "AOC_SESSION=$session_token" > "${ROOT_DIR}/.env"
+        if [ -f "$CONFIG_FILE" ] && awk '/^[[:space:]]*\[/ { exit }
+                /^[[:space:]]*session[[:space:]]*=/ { found = 1; exit }
+                END { exit !found }' "$CONFIG_FILE"; then
+            echo "Not saving it, since ${CONFIG_FILE} already has a session setting"
         else
-            # Check if AOC_SESSION already exists in file (might not if it was set manually)
-            if ! grep -q "^AOC_SESSION=" "${ROOT_DIR}/.env"; then
-                echo "AOC_SESSION=$session_token" >> "${ROOT_DIR}/.env"
-            fi
+            # Top level settings have to come before any [section], so it goes first
+            mkdir -p "$(dirname "$CONFIG_FILE")"
+            (
+                umask 077
+                { echo "session = \"${AOC_SESSION}\""; cat "$CONFIG_FILE" 2>/dev/null; } > "${CONFIG_FILE}.tmp"
+            )
+            mv "${CONFIG_FILE}.tmp" "$CONFIG_FILE"
+            echo "Saved session token to ${CONFIG_FILE}"
         fi
-
-        # Set for current session
-        export AOC_SESSION=$session_token
     fi
+
+    export AOC_SESSION
 }
 
 # Fetch input, storing it in the inputs/ directory (.gitignore'd)
 function fetch_input() {
     local day_no_padding="$1"
+    local year="$2"
     local day=$(printf "%02d" "$day_no_padding")
-    local input_dir="${ROOT_DIR}/inputs"
+    local input_dir="$(year_dir "${ROOT_DIR}/inputs" "$year")"
     mkdir -p "$input_dir"
     local input_file="${input_dir}/day${day}.txt"
 
@@ -41,11 +78,11 @@ function fetch_input() {
     if [ ! -f "$input_file" ]; then
         get_session_token  # Ensure we have a session token
 
-        echo "Fetching https://adventofcode.com/2025/day/${day_no_padding}/input"
+        echo "Fetching https://adventofcode.com/${year}/day/${day_no_padding}/input"
         # Fetch the input using curl
         local http_status=$(curl -s -w "%{http_code}" -o "$input_file" \
             -H "Cookie: session=${AOC_SESSION}" \
-            "https://adventofcode.com/2025/day/${day_no_padding}/input")
+            "https://adventofcode.com/${year}/day/${day_no_padding}/input")
 
         if [ "$http_status" -ne 200 ]; then
             echo "Failed to fetch input for day ${day_no_padding} (HTTP ${http_status})"
@@ -63,76 +100,95 @@ function fetch_input() {
 function make_day_part() {
   local day=$(printf "%02d" "$1")
   local part=$2
+  local year=$3
 
   local name="day${day}${part}"
+  local dir="$(year_dir "${ROOT_DIR}" "$year")/${name}"
+  local register="day = $((10#$1))"
+
+  # Package names have to be unique across the workspace
+  if [ "$year" != "$MAIN_YEAR" ]; then
+    name="y${year}-${name}"
+    register="year = ${year}, ${register}"
 
-  if [ "$(pwd)" != "${ROOT_DIR}" ]; then
-    cd "${ROOT_DIR}" || exit 1
+    # Workspace member globs that match nothing are an error, so add each year once it exists
+    if ! grep -q "\"${year}/day\*\"" "${ROOT_DIR}/Cargo.toml"; then
+      sed -i "s|\"day\*\",|\"day*\", \"${year}/day*\",|" "${ROOT_DIR}/Cargo.toml"
+    fi
   fi
 
   cd "${ROOT_DIR}" || exit 1
-  cargo new "$name"
-  touch "$name/example.txt"
+  mkdir -p "$(dirname "$dir")"
+  cargo new "$dir" --name "$name"
+  touch "$dir/example.txt"
 
-  local input_file="${ROOT_DIR}/inputs/day${day}.txt"
+  local input_file="$(year_dir "${ROOT_DIR}/inputs" "$year")/day${day}.txt"
   if [ -f "${input_file}" ]; then
-    cp "${ROOT_DIR}/inputs/day${day}.txt" "$name/input.txt"
+    cp "${input_file}" "$dir/input.txt"
   else
-    touch "$name/input.txt"
+    touch "$dir/input.txt"
   fi
 
-  cp -r "${ROOT_DIR}/template/src" "$name/"
-  cd $name
-  cargo add aoc --path "../aoc"
+  cp -r "${ROOT_DIR}/template/src" "$dir/"
+  sed -i "s/^use template::/use ${name//-/_}::/" "$dir/src/main.rs"
+  sed -i "s/register_solution!(day = 0)/register_solution!(${register})/" "$dir/src/lib.rs"
+  cargo add --package "$name" aoc --path "${ROOT_DIR}/aoc"
+
+  # Registering with aoc-cli is all that's needed for the all-days runner to pick it up
+  cargo add --package aoc-cli "$name" --path "$dir"
 }
 
 function make_day() {
   local day="$1"
-  local day_padded=$(printf "%02d" "$1")
+  local year="$2"
 
-
-  fetch_input "$day"
+  fetch_input "$day" "$year"
   if [ $? -ne 0 ]; then
     echo "Input files will be empty, please fill manually"
   fi
 
-  make_day_part "$day" ""
+  make_day_part "$day" "" "$year"
 }
 
 function download_inputs() {
   local day="$1"
+  local year="$2"
   local day_padded=$(printf "%02d" "$day")
+  local dir="$(year_dir "${ROOT_DIR}" "$year")/day${day_padded}"
 
-  if [ ! -d "${ROOT_DIR}/day${day_padded}" ]; then
-    echo "Error: Day ${day} directory not found. Create first with '$0 new ${day}'"
+  if [ ! -d "$dir" ]; then
+    echo "Error: Day ${day} directory not found. Create first with '$0 new ${day} ${year}'"
     exit 1
   fi
   echo "Fetching for $day"
-  fetch_input "$day"
+  fetch_input "$day" "$year"
   if [ $? -ne 0 ]; then
     exit 1
   fi
 
-  cp "${ROOT_DIR}/inputs/day${day_padded}.txt" "${ROOT_DIR}/day${day_padded}/input.txt"
+  cp "$(year_dir "${ROOT_DIR}/inputs" "$year")/day${day_padded}.txt" "$dir/input.txt"
   # If example.txt doesn't exist
-  if [ ! -f "${ROOT_DIR}/day${day_padded}/example.txt" ]; then
-    echo "day${day_padded}/example.txt created empty, please fill manually"
-    touch "${ROOT_DIR}/day${day_padded}/example.txt"
+  if [ ! -f "$dir/example.txt" ]; then
+    echo "$dir/example.txt created empty, please fill manually"
+    touch "$dir/example.txt"
   fi
 }
 
 function usage() {
   echo "Advent of Code CLI
 
-Usage: $0 <command> <day>
+Usage: $0 <command> <day> [year]
 
 Commands:
-  new <day>         Create new project directory for the specified day
-  download <day>    Download input for the specified day (directories must exist)
+  new <day> [year]         Create new project directory for the specified day
+  download <day> [year]    Download input for the specified day (directories must exist)
+
+The year defaults to ${MAIN_YEAR}. Days from other years go in a directory for the year.
 
 Examples:
-  $0 new 1    Create day01 project, downloading inputs
-  $0 input 1  Download input for into pre-existing day01 project
+  $0 new 1          Create day01 project, downloading inputs
+  $0 new 17 2024    Create 2024/day17 project, downloading inputs
+  $0 download 1     Download input for into pre-existing day01 project
 "
 }
 
@@ -143,7 +199,7 @@ case "$1" in
       usage
       exit 1
     fi
-    make_day "$2"
+    make_day "$2" "${3:-$MAIN_YEAR}"
     ;;
   "download")
     if [ -z "$2" ]; then
@@ -151,7 +207,7 @@ case "$1" in
       usage
       exit 1
     fi
-    download_inputs "$2"
+    download_inputs "$2" "${3:-$MAIN_YEAR}"
     ;;
   *)
     usage
@@ -0,0 +1,747 @@
+use crate::math::checked_lcm_all;
+use crate::rng::Rng;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A transformation that can be composed with itself, so that applying it `n` times can be
+/// done in `O(log n)` compositions with [`power`]
+pub trait Compose: Clone {
+    /// The transformation that does nothing, with the same shape as `self`
+    fn identity_like(&self) -> Self;
+
+    /// Apply `self`, then `next`
+    fn then(&self, next: &Self) -> Self;
+}
+
+/// `step` composed with itself `n` times, by exponentiation by squaring
+pub fn power<T: Compose>(step: &T, mut n: u64) -> T {
+    let mut result = step.identity_like();
+    let mut square = step.clone();
+
+    while n > 0 {
+        if n & 1 == 1 {
+            result = result.then(&square);
+        }
+        square = square.then(&square);
+        n >>= 1;
+    }
+
+    result
+}
+
+/// A rearrangement of positions `0..n`. Position `i` moves to `map[i]`.
+///
+/// # Examples
+/// ```
+/// use aoc::algo::{power, Permutation};
+///
+/// // Rotate 5 items one to the right, a billion times
+/// let spin = Permutation::from_fn(5, |i| (i + 1) % 5).unwrap();
+/// let jump = power(&spin, 1_000_000_000);
+///
+/// assert_eq!(jump.permute(&['a', 'b', 'c', 'd', 'e']), vec!['a', 'b', 'c', 'd', 'e']);
+/// assert_eq!(power(&spin, 7).permute(&['a', 'b', 'c', 'd', 'e']), vec!['d', 'e', 'a', 'b', 'c']);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    map: Vec<usize>,
+}
+
+impl Permutation {
+    pub fn identity(n: usize) -> Self {
+        Self {
+            map: (0..n).collect(),
+        }
+    }
+
+    /// The permutation moving position `i` to `f(i)`, or None if `f` isn't a bijection on
+    /// `0..n`
+    pub fn from_fn(n: usize, f: impl Fn(usize) -> usize) -> Option<Self> {
+        let map: Vec<usize> = (0..n).map(f).collect();
+
+        let mut seen = vec![false; n];
+        for &to in &map {
+            if to >= n || seen[to] {
+                return None;
+            }
+            seen[to] = true;
+        }
+
+        Some(Self { map })
+    }
+
+    /// The permutation that puts `after[i]` at position `i`, given a sequence and its
+    /// rearrangement. Returns None if `after` isn't a rearrangement of `before` with distinct
+    /// items.
+    pub fn from_sequences<T: PartialEq>(before: &[T], after: &[T]) -> Option<Self> {
+        if before.len() != after.len() {
+            return None;
+        }
+
+        let mut map = vec![0; before.len()];
+        for (from, item) in before.iter().enumerate() {
+            map[from] = after.iter().position(|x| x == item)?;
+        }
+
+        Self::from_fn(before.len(), |i| map[i])
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Where position `i` ends up
+    pub fn apply(&self, i: usize) -> usize {
+        self.map[i]
+    }
+
+    /// The permutation that undoes this one
+    pub fn inverse(&self) -> Self {
+        let mut map = vec![0; self.len()];
+        for (from, &to) in self.map.iter().enumerate() {
+            map[to] = from;
+        }
+        Self { map }
+    }
+
+    /// This permutation applied `n` times, see [`power`]
+    pub fn pow(&self, n: u64) -> Self {
+        power(self, n)
+    }
+
+    /// The cycles of positions that move into each other, each starting from its smallest
+    /// position. Fixed points are cycles of length 1.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.len()];
+        let mut cycles = Vec::new();
+
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                cycle.push(i);
+                i = self.map[i];
+            }
+            cycles.push(cycle);
+        }
+
+        cycles
+    }
+
+    /// How many times this permutation must be applied to get back to the start, the lowest
+    /// common multiple of its cycle lengths. None if that doesn't fit in a `u64`, which takes a
+    /// few hundred elements.
+    pub fn order(&self) -> Option<u64> {
+        checked_lcm_all(self.cycles().iter().map(|cycle| cycle.len() as u64))
+    }
+
+    /// Rearrange `items` by this permutation
+    pub fn permute<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        assert_eq!(items.len(), self.len(), "Permutation length mismatch");
+
+        let mut result = items.to_vec();
+        for (from, item) in items.iter().enumerate() {
+            result[self.map[from]] = item.clone();
+        }
+        result
+    }
+}
+
+impl Compose for Permutation {
+    fn identity_like(&self) -> Self {
+        Self::identity(self.len())
+    }
+
+    fn then(&self, next: &Self) -> Self {
+        Self {
+            map: self.map.iter().map(|&i| next.map[i]).collect(),
+        }
+    }
+}
+
+/// An affine map `x -> Ax + b` on vectors of integers modulo `modulus`
+///
+/// # Examples
+/// ```
+/// use aoc::algo::{power, AffineMap};
+///
+/// // Position of a card after dealing with increment 3, then cutting 2, in a deck of 10
+/// let shuffle = AffineMap::detect(1, 10, |x| vec![(x[0] * 3 + 10 - 2) % 10]).unwrap();
+///
+/// assert_eq!(shuffle.apply(&[4]), vec![0]);
+/// assert_eq!(power(&shuffle, 2).apply(&[4]), vec![8]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AffineMap {
+    /// Row major, `matrix[row][col]`
+    pub matrix: Vec<Vec<u64>>,
+    pub offset: Vec<u64>,
+    pub modulus: u64,
+}
+
+impl AffineMap {
+    pub fn identity(dims: usize, modulus: u64) -> Self {
+        let matrix = (0..dims)
+            .map(|row| (0..dims).map(|col| (row == col) as u64).collect())
+            .collect();
+
+        Self {
+            matrix,
+            offset: vec![0; dims],
+            modulus,
+        }
+    }
+
+    /// Work out the affine map that `f` computes on vectors of `dims` integers modulo
+    /// `modulus`, by probing it with zero and unit vectors. Returns None if `f` doesn't agree
+    /// with that map on a sample of random vectors, ie. if it isn't affine, or if `modulus` is 0.
+    pub fn detect(dims: usize, modulus: u64, f: impl Fn(&[u64]) -> Vec<u64>) -> Option<Self> {
+        if modulus == 0 {
+            return None;
+        }
+        let reduce = |v: Vec<u64>| -> Option<Vec<u64>> {
+            (v.len() == dims).then(|| v.into_iter().map(|x| x % modulus).collect())
+        };
+
+        let offset = reduce(f(&vec![0; dims]))?;
+
+        let mut matrix = vec![vec![0; dims]; dims];
+        for col in 0..dims {
+            let mut unit = vec![0; dims];
+            unit[col] = 1 % modulus;
+            let image = reduce(f(&unit))?;
+            for row in 0..dims {
+                // In u128, as the sum overflows a u64 for moduli past u64::MAX / 2
+                let diff = image[row] as u128 + modulus as u128 - offset[row] as u128;
+                matrix[row][col] = (diff % modulus as u128) as u64;
+            }
+        }
+
+        let map = Self {
+            matrix,
+            offset,
+            modulus,
+        };
+
+        let mut rng = Rng::new(dims as u64 ^ modulus);
+        for _ in 0..16 {
+            let x: Vec<u64> = (0..dims).map(|_| rng.below(modulus)).collect();
+            if reduce(f(&x))? != map.apply(&x) {
+                return None;
+            }
+        }
+
+        Some(map)
+    }
+
+    pub fn apply(&self, x: &[u64]) -> Vec<u64> {
+        let m = self.modulus as u128;
+
+        self.matrix
+            .iter()
+            .zip(&self.offset)
+            .map(|(row, &b)| {
+                let dot = row
+                    .iter()
+                    .zip(x)
+                    .fold(b as u128, |acc, (&a, &x)| (acc + a as u128 * x as u128) % m);
+                dot as u64
+            })
+            .collect()
+    }
+}
+
+impl Compose for AffineMap {
+    fn identity_like(&self) -> Self {
+        Self::identity(self.offset.len(), self.modulus)
+    }
+
+    /// `next(self(x)) = N(Ax + b) + c = (NA)x + (Nb + c)`
+    fn then(&self, next: &Self) -> Self {
+        let m = self.modulus as u128;
+        let dims = self.offset.len();
+
+        let matrix = (0..dims)
+            .map(|row| {
+                (0..dims)
+                    .map(|col| {
+                        let sum = (0..dims).fold(0, |acc, k| {
+                            (acc + next.matrix[row][k] as u128 * self.matrix[k][col] as u128) % m
+                        });
+                        sum as u64
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            matrix,
+            offset: next.apply(&self.offset),
+            modulus: self.modulus,
+        }
+    }
+}
+
+/// Apply `step` to `initial` `n` times, for a step that rearranges items by position only (eg.
+/// spins and swaps, but not swaps by value). Rather than running the step `n` times, this runs
+/// it once to find the permutation it makes, then jumps by repeated squaring.
+///
+/// Returns None if the items aren't distinct, or `step` doesn't just rearrange them.
+///
+/// # Examples
+/// ```
+/// use aoc::algo::iterate_fast;
+///
+/// let spin = |items: &[char]| [&items[3..], &items[..3]].concat();
+///
+/// assert_eq!(
+///     iterate_fast(&['a', 'b', 'c', 'd', 'e'], spin, 1_000_000_001),
+///     Some(vec!['d', 'e', 'a', 'b', 'c'])
+/// );
+/// ```
+pub fn iterate_fast<T: Clone + PartialEq>(
+    initial: &[T],
+    step: impl Fn(&[T]) -> Vec<T>,
+    n: u64,
+) -> Option<Vec<T>> {
+    let permutation = Permutation::from_sequences(initial, &step(initial))?;
+    Some(power(&permutation, n).permute(initial))
+}
+
+/// Apply `step` to `initial` `n` times, for a step that is an affine map on vectors of integers
+/// modulo `modulus`. Returns None if the step isn't affine.
+///
+/// # Examples
+/// ```
+/// use aoc::algo::iterate_fast_affine;
+///
+/// // Tenth Fibonacci number, mod 1000
+/// let step = |x: &[u64]| vec![x[1], x[0] + x[1]];
+/// assert_eq!(iterate_fast_affine(&[0, 1], 1000, step, 10), Some(vec![55, 89]));
+/// ```
+pub fn iterate_fast_affine(
+    initial: &[u64],
+    modulus: u64,
+    step: impl Fn(&[u64]) -> Vec<u64>,
+    n: u64,
+) -> Option<Vec<u64>> {
+    let map = AffineMap::detect(initial.len(), modulus, step)?;
+    Some(power(&map, n).apply(initial))
+}
+
+/// Smallest value in `lo..=hi` for which `predicate` holds, for a predicate that's false up to
+/// some point and true from then on. Returns None if it's false for the whole range.
+///
+/// # Examples
+/// ```
+/// use aoc::algo::binary_search_answer;
+///
+/// // Shortest time to hold the button to beat the record of 200mm in a 30ms race
+/// let beats = |hold: i64| hold * (30 - hold) > 200;
+/// assert_eq!(binary_search_answer(0, 15, beats), Some(11));
+///
+/// assert_eq!(binary_search_answer(0, 10, |x| x * x >= 1000), None);
+/// ```
+pub fn binary_search_answer(lo: i64, hi: i64, predicate: impl FnMut(i64) -> bool) -> Option<i64> {
+    binary_search_boundary(lo, hi, predicate).1
+}
+
+/// Both sides of where `predicate` switches from false to true in `lo..=hi`: the last value
+/// where it's false and the first where it's true. Either is None if there's no such value in
+/// the range. Like [`binary_search_answer`], the predicate has to be false up to some point and
+/// true from then on.
+///
+/// # Examples
+/// ```
+/// use aoc::algo::binary_search_boundary;
+///
+/// assert_eq!(binary_search_boundary(1, 100, |x| x * x > 50), (Some(7), Some(8)));
+/// assert_eq!(binary_search_boundary(1, 100, |_| true), (None, Some(1)));
+/// assert_eq!(binary_search_boundary(1, 100, |_| false), (Some(100), None));
+/// ```
+pub fn binary_search_boundary(
+    lo: i64,
+    hi: i64,
+    mut predicate: impl FnMut(i64) -> bool,
+) -> (Option<i64>, Option<i64>) {
+    if lo > hi {
+        return (None, None);
+    }
+
+    // The first true value is in `start..end`, where `end` past `hi` means there isn't one.
+    // Wider than i64 so the midpoint and the ends never overflow.
+    let (mut start, mut end) = (lo as i128, hi as i128 + 1);
+    while start < end {
+        let mid = start + (end - start) / 2;
+        if predicate(mid as i64) {
+            end = mid;
+        } else {
+            start = mid + 1;
+        }
+    }
+
+    let last_false = (start > lo as i128).then(|| (start - 1) as i64);
+    let first_true = (start <= hi as i128).then_some(start as i64);
+    (last_false, first_true)
+}
+
+/// Smallest value in `lo..=hi` for which `predicate` holds, to within `tolerance`, for a
+/// predicate that's false up to some point and true from then on. The result is always a value
+/// the predicate is true for, unless it's false for the whole range, in which case it's `hi`.
+///
+/// # Examples
+/// ```
+/// use aoc::algo::binary_search_float;
+///
+/// let root = binary_search_float(0.0, 2.0, 1e-9, |x| x * x >= 2.0);
+/// assert!((root - 2f64.sqrt()).abs() < 1e-9);
+/// ```
+pub fn binary_search_float(
+    mut lo: f64,
+    mut hi: f64,
+    tolerance: f64,
+    mut predicate: impl FnMut(f64) -> bool,
+) -> f64 {
+    // Bounded as well, since a tolerance finer than the gaps between floats would never be met
+    for _ in 0..200 {
+        if hi - lo <= tolerance {
+            break;
+        }
+        let mid = lo + (hi - lo) / 2.0;
+        if predicate(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
+
+/// Every state of a sequence up to the point where it starts repeating, see [`find_cycle`]
+#[derive(Debug, Clone)]
+pub struct Cycle<T> {
+    /// Number of states before the first one that's part of the cycle
+    pub prefix_len: usize,
+    pub cycle_len: usize,
+    /// States `0..prefix_len + cycle_len`
+    states: Vec<T>,
+}
+
+impl<T> Cycle<T> {
+    /// The state after `n` steps from the initial state, without running them
+    pub fn state_after(&self, n: u64) -> &T {
+        let (prefix, cycle) = (self.prefix_len as u64, self.cycle_len as u64);
+        let i = if n < prefix {
+            n
+        } else {
+            prefix + (n - prefix) % cycle
+        };
+        &self.states[i as usize]
+    }
+}
+
+/// Run `step` from `initial` until a state repeats. Every state is kept, so that
+/// [`Cycle::state_after`] can look up the state after any number of steps. For states too big
+/// to keep, see [`find_cycle_brent`].
+///
+/// # Examples
+/// ```
+/// use aoc::algo::find_cycle;
+///
+/// // 0, 1, 2, 5, 4, 6, 4, 6, ...
+/// let cycle = find_cycle(0u32, |&x| (x * x + 1) % 11);
+///
+/// assert_eq!((cycle.prefix_len, cycle.cycle_len), (4, 2));
+/// assert_eq!(*cycle.state_after(3), 5);
+/// assert_eq!(*cycle.state_after(1_000_000_001), 6);
+/// ```
+pub fn find_cycle<T>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle<T>
+where
+    T: Clone + Eq + Hash,
+{
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+
+    loop {
+        if let Some(&first) = seen.get(&state) {
+            return Cycle {
+                prefix_len: first,
+                cycle_len: states.len() - first,
+                states,
+            };
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// `(prefix_len, cycle_len)` of the sequence from `initial`, like [`find_cycle`], using Brent's
+/// algorithm. Only a couple of states are kept at a time, at the cost of running each step
+/// around three times.
+///
+/// # Examples
+/// ```
+/// use aoc::algo::find_cycle_brent;
+///
+/// assert_eq!(find_cycle_brent(0u32, |&x| (x * x + 1) % 11), (4, 2));
+/// ```
+pub fn find_cycle_brent<T>(initial: T, mut step: impl FnMut(&T) -> T) -> (usize, usize)
+where
+    T: Clone + PartialEq,
+{
+    // Find the cycle length, by moving the tortoise up to the hare at each power of two
+    let mut power = 1;
+    let mut cycle_len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == cycle_len {
+            tortoise = hare.clone();
+            power *= 2;
+            cycle_len = 0;
+        }
+        hare = step(&hare);
+        cycle_len += 1;
+    }
+
+    // Then with the hare that far ahead, they first meet at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..cycle_len {
+        hare = step(&hare);
+    }
+    let mut prefix_len = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix_len += 1;
+    }
+
+    (prefix_len, cycle_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_cycle() {
+        for seed in 0..20u64 {
+            let step = |&x: &u64| (x * x + seed + 1) % 1009;
+            let cycle = find_cycle(seed, step);
+
+            assert_eq!(
+                find_cycle_brent(seed, step),
+                (cycle.prefix_len, cycle.cycle_len)
+            );
+
+            let mut naive = seed;
+            for n in 0..3000 {
+                assert_eq!(*cycle.state_after(n), naive, "seed {} n = {}", seed, n);
+                naive = step(&naive);
+            }
+        }
+    }
+
+    #[test]
+    fn test_binary_search_matches_linear() {
+        for threshold in -5..=15 {
+            let predicate = |x: i64| x >= threshold;
+            for (lo, hi) in [(0, 10), (3, 3), (-4, 7), (5, 4)] {
+                let first = (lo..=hi).find(|&x| predicate(x));
+                let last = (lo..=hi).rev().find(|&x| !predicate(x));
+
+                assert_eq!(binary_search_answer(lo, hi, predicate), first);
+                assert_eq!(binary_search_boundary(lo, hi, predicate), (last, first));
+            }
+        }
+
+        // The whole range, without overflowing
+        assert_eq!(
+            binary_search_answer(i64::MIN, i64::MAX, |x| x >= 42),
+            Some(42)
+        );
+        assert_eq!(
+            binary_search_boundary(i64::MIN, i64::MAX, |_| false),
+            (Some(i64::MAX), None)
+        );
+        assert_eq!(
+            binary_search_answer(i64::MIN, i64::MAX, |_| true),
+            Some(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn test_binary_search_float() {
+        let x = binary_search_float(0.0, 100.0, 1e-6, |x| x.exp() >= 1000.0);
+        assert!(x.exp() >= 1000.0);
+        assert!((x - 1000f64.ln()).abs() <= 1e-6);
+
+        // Finer than floats can go, so this has to give up rather than loop forever
+        assert_eq!(binary_search_float(1.0, 2.0, 0.0, |x| x >= 1.5), 1.5);
+    }
+
+    #[test]
+    fn test_permutation_power_matches_naive() {
+        let step = Permutation::from_fn(7, |i| (i * 3 + 2) % 7).unwrap();
+        let items: Vec<char> = "abcdefg".chars().collect();
+
+        let mut naive = items.clone();
+        for n in 0..50 {
+            assert_eq!(power(&step, n).permute(&items), naive, "n = {}", n);
+            naive = step.permute(&naive);
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let step = Permutation::from_fn(6, |i| (i * 5 + 1) % 6).unwrap();
+        let items = [1, 2, 3, 4, 5, 6];
+
+        assert_eq!(step.inverse().permute(&step.permute(&items)), items);
+        assert_eq!(step.then(&step.inverse()), Permutation::identity(6));
+        assert_eq!(step.pow(3).inverse(), step.inverse().pow(3));
+    }
+
+    #[test]
+    fn test_cycles() {
+        // 0 -> 2 -> 4 -> 0, 1 -> 3 -> 1, 5 fixed
+        let step = Permutation::from_fn(6, |i| [2, 3, 4, 1, 0, 5][i]).unwrap();
+
+        assert_eq!(step.cycles(), vec![vec![0, 2, 4], vec![1, 3], vec![5]]);
+        assert_eq!(step.order(), Some(6));
+        assert_eq!(step.pow(6), Permutation::identity(6));
+        assert_ne!(step.pow(3), Permutation::identity(6));
+
+        assert_eq!(Permutation::identity(0).cycles(), Vec::<Vec<usize>>::new());
+        assert_eq!(Permutation::identity(0).order(), Some(1));
+
+        // Cycles of every prime up to 53 have an order past u64::MAX
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        let mut map = Vec::new();
+        for len in primes {
+            let start = map.len();
+            map.extend((1..=len).map(|i| start + i % len));
+        }
+        let step = Permutation::from_fn(map.len(), |i| map[i]).unwrap();
+        assert_eq!(step.cycles().len(), primes.len());
+        assert_eq!(step.order(), None);
+    }
+
+    #[test]
+    fn test_permutation_from_sequences() {
+        // 2017 day 16's spin and exchange moves
+        let before: Vec<char> = "abcde".chars().collect();
+        let after: Vec<char> = "eabdc".chars().collect();
+        let dance = Permutation::from_sequences(&before, &after).unwrap();
+
+        assert_eq!(dance.permute(&before), after);
+        assert_eq!(
+            power(&dance, 2).permute(&before),
+            dance.permute(&dance.permute(&before))
+        );
+
+        assert_eq!(Permutation::from_sequences(&before, &before[1..]), None);
+        assert_eq!(Permutation::from_fn(3, |i| i / 2), None);
+    }
+
+    /// Where the card at `position` ends up after 2019 day 22's shuffle techniques
+    fn shuffle(position: u64, size: u64) -> u64 {
+        let position = (position * 7) % size; // deal with increment 7
+        let position = size - 1 - position; // deal into new stack
+        (position + size - 3) % size // cut 3
+    }
+
+    #[test]
+    fn test_affine_power_matches_naive() {
+        let size = 10_007;
+        let map = AffineMap::detect(1, size, |x| vec![shuffle(x[0], size)]).unwrap();
+
+        let mut naive = 2019;
+        for n in 0..100 {
+            assert_eq!(power(&map, n).apply(&[2019]), vec![naive], "n = {}", n);
+            naive = shuffle(naive, size);
+        }
+
+        // Big enough that products overflow u64
+        let size: u64 = 119_315_717_514_047;
+        let times_7 = AffineMap::detect(1, size, |x| {
+            vec![((x[0] as u128 * 7) % size as u128) as u64]
+        })
+        .unwrap();
+
+        let n: u64 = 101_741_582_076_661;
+        let (mut expected, mut base, mut exp) = (1u128, 7u128, n);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                expected = expected * base % size as u128;
+            }
+            base = base * base % size as u128;
+            exp >>= 1;
+        }
+        assert_eq!(power(&times_7, n).apply(&[1]), vec![expected as u64]);
+    }
+
+    #[test]
+    fn test_affine_matrix() {
+        // Fibonacci as a linear map on (F(n), F(n+1))
+        let fib = AffineMap::detect(2, 1_000_000_007, |x| vec![x[1], x[0] + x[1]]).unwrap();
+
+        assert_eq!(power(&fib, 10).apply(&[0, 1]), vec![55, 89]);
+        assert_eq!(
+            power(&fib, 90).apply(&[0, 1])[0],
+            2_880_067_194_370_816_120 % 1_000_000_007
+        );
+    }
+
+    #[test]
+    fn test_detect_non_affine() {
+        assert_eq!(AffineMap::detect(1, 101, |x| vec![x[0] * x[0]]), None);
+        assert_eq!(AffineMap::detect(2, 101, |x| vec![x[0] * x[1], x[0]]), None);
+        assert_eq!(AffineMap::detect(2, 101, |x| vec![x[0]]), None);
+        assert_eq!(AffineMap::detect(1, 0, |x| vec![x[0]]), None);
+    }
+
+    #[test]
+    fn test_detect_large_modulus() {
+        // The largest prime below 2^64
+        let m = 18_446_744_073_709_551_557u64;
+        let f = |x: &[u64]| vec![((x[0] as u128 * 3 + (m - 5) as u128) % m as u128) as u64];
+        let map = AffineMap::detect(1, m, f).unwrap();
+
+        assert_eq!(map.matrix, vec![vec![3]]);
+        assert_eq!(map.offset, vec![m - 5]);
+        assert_eq!(map.apply(&[2]), vec![1]);
+    }
+
+    #[test]
+    fn test_iterate_fast() {
+        // Swapping the first two items only works on distinct items
+        let swap = |items: &[u8]| [&items[1..2], &items[..1], &items[2..]].concat();
+        assert_eq!(iterate_fast(&[1, 2, 3], swap, 3), Some(vec![2, 1, 3]));
+        assert_eq!(iterate_fast(&[1, 1, 3], swap, 3), None);
+
+        // Not a rearrangement
+        assert_eq!(
+            iterate_fast(&[1, 2, 3], |items| items[1..].to_vec(), 3),
+            None
+        );
+
+        assert_eq!(
+            iterate_fast_affine(&[3], 100, |x| vec![x[0] * x[0]], 3),
+            None
+        );
+    }
+}
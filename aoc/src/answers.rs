@@ -0,0 +1,165 @@
+use crate::config::config;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+/// Where answers are saved: `answers.txt` in the configured input directory, or in `inputs/` in
+/// the workspace
+pub fn default_path() -> PathBuf {
+    config()
+        .input_dir
+        .clone()
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../inputs"))
+        .join("answers.txt")
+}
+
+/// Answers from previous runs, so that refactors which change an answer get caught.
+///
+/// Stored one per line as `<year> <day> <part> <answer>`, with newlines in answers escaped
+/// as `\n`.
+pub struct AnswerStore {
+    path: PathBuf,
+    answers: BTreeMap<(u32, u32, u32), String>,
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut unescaped = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+impl AnswerStore {
+    /// Load the store, treating a missing file as empty
+    pub fn load(path: PathBuf) -> Self {
+        let answers = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(4, ' ');
+                let year = parts.next()?.parse().ok()?;
+                let day = parts.next()?.parse().ok()?;
+                let part = parts.next()?.parse().ok()?;
+                Some(((year, day, part), unescape(parts.next()?)))
+            })
+            .collect();
+
+        Self { path, answers }
+    }
+
+    pub fn get(&self, year: u32, day: u32, part: u32) -> Option<&str> {
+        self.answers.get(&(year, day, part)).map(String::as_str)
+    }
+
+    /// Record an answer and write the store back to disk
+    pub fn set(&mut self, year: u32, day: u32, part: u32, answer: String) {
+        self.answers.insert((year, day, part), answer);
+
+        let contents: String = self
+            .answers
+            .iter()
+            .map(|((year, day, part), answer)| {
+                format!("{} {} {} {}\n", year, day, part, escape(answer))
+            })
+            .collect();
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).expect("Failed to create answers directory");
+        }
+        fs::write(&self.path, contents)
+            .unwrap_or_else(|e| panic!("Failed to write {}: {}", self.path.display(), e));
+    }
+}
+
+/// A before/after diff of two answers, line by line, in red and green when writing to a
+/// terminal
+pub fn diff(old: &str, new: &str) -> String {
+    let color = std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let paint = |code: &str, line: String| {
+        if color {
+            format!("\x1b[{}m{}\x1b[0m\n", code, line)
+        } else {
+            format!("{}\n", line)
+        }
+    };
+
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let mut diff = String::new();
+    for i in 0..old_lines.len().max(new_lines.len()) {
+        let (before, after) = (old_lines.get(i), new_lines.get(i));
+        if before == after {
+            diff.push_str(&format!("  {}\n", before.unwrap()));
+            continue;
+        }
+
+        if let Some(before) = before {
+            diff.push_str(&paint("1;31", format!("- {}", before)));
+        }
+        if let Some(after) = after {
+            diff.push_str(&paint("1;32", format!("+ {}", after)));
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_round_trip() {
+        for answer in ["5937", "#..#\n####", "a\\nb", ""] {
+            assert_eq!(unescape(&escape(answer)), answer);
+        }
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("aoc-answers-test-{}.txt", std::process::id()));
+
+        let mut store = AnswerStore::load(path.clone());
+        assert_eq!(store.get(2025, 1, 1), None);
+        store.set(2025, 1, 2, "6".to_string());
+        store.set(2025, 1, 1, "AB\nCD".to_string());
+        store.set(2024, 17, 1, "4,6,3".to_string());
+
+        let store = AnswerStore::load(path.clone());
+        assert_eq!(store.get(2025, 1, 1), Some("AB\nCD"));
+        assert_eq!(store.get(2025, 1, 2), Some("6"));
+        assert_eq!(store.get(2024, 1, 2), None);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "2024 17 1 4,6,3\n2025 1 1 AB\\nCD\n2025 1 2 6\n"
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_diff() {
+        // Not a terminal under test, so no colors
+        assert_eq!(diff("41", "42"), "- 41\n+ 42\n");
+        assert_eq!(diff("AB\nCD", "AB\nCE"), "  AB\n- CD\n+ CE\n");
+    }
+}
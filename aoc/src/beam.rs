@@ -0,0 +1,174 @@
+use crate::grid_2d::{Board, Coord, Dir, Orientation};
+use crate::search::{reachable_poses, PoseSet};
+
+/// What happens to a beam as it enters a cell, heading in some direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deflect {
+    /// Carry on in the same direction
+    Pass,
+    /// Leave the cell heading in a new direction
+    Turn(Dir),
+    /// Split into two beams, heading in each direction
+    Split(Dir, Dir),
+    /// The beam goes no further
+    Absorb,
+}
+
+impl Deflect {
+    /// The rules of the mirror contraption (2023 day 16): `/` and `\` are mirrors, `|` and `-`
+    /// split beams that hit their flat side, and anything else is empty space.
+    pub fn mirrors(tile: &char, dir: Dir) -> Self {
+        let horizontal = matches!(dir, Dir::East | Dir::West);
+        match (tile, horizontal) {
+            ('/', true) | ('\\', false) => Deflect::Turn(dir.rotate_left()),
+            ('/', false) | ('\\', true) => Deflect::Turn(dir.rotate_right()),
+            ('|', true) | ('-', false) => Deflect::Split(dir.rotate_left(), dir.rotate_right()),
+            _ => Deflect::Pass,
+        }
+    }
+}
+
+/// Every pose a beam reaches on the board, starting from `starts` (each a cell the beam enters,
+/// and the direction it's heading). `rule` decides what each cell does to a beam entering it.
+/// Beams leaving the board are dropped, and each pose is only followed once, so beams caught
+/// in loops stop.
+///
+/// `orientation` says which way north is on the board, [`Orientation::Screen`] for boards
+/// read from text.
+pub fn trace<T, R>(
+    board: &Board<T>,
+    orientation: Orientation,
+    starts: impl IntoIterator<Item = (Coord, Dir)>,
+    rule: R,
+) -> PoseSet
+where
+    T: Clone,
+    R: Fn(&T, Dir) -> Deflect,
+{
+    reachable_poses(board.size(), starts, |c, dir| {
+        let dirs = match rule(&board[c], dir) {
+            Deflect::Pass => [Some(dir), None],
+            Deflect::Turn(turned) => [Some(turned), None],
+            Deflect::Split(a, b) => [Some(a), Some(b)],
+            Deflect::Absorb => [None, None],
+        };
+        dirs.into_iter()
+            .flatten()
+            .map(move |dir| (orientation.step(c, dir), dir))
+    })
+}
+
+/// Number of cells a beam passes through, starting from one pose, on a board read from text.
+/// See [`trace`].
+///
+/// # Examples
+/// ```
+/// use aoc::beam::{energized, Deflect};
+/// use aoc::grid_2d::{Board, Coord, Dir};
+///
+/// let board = Board::from_str(".\\.\n.-.\n...");
+///
+/// assert_eq!(energized(&board, (Coord(0, 0), Dir::East), Deflect::mirrors), 5);
+/// ```
+pub fn energized<T, R>(board: &Board<T>, start: (Coord, Dir), rule: R) -> usize
+where
+    T: Clone,
+    R: Fn(&T, Dir) -> Deflect,
+{
+    trace(board, Orientation::Screen, [start], rule)
+        .cells()
+        .count()
+}
+
+/// Every pose a beam can enter a board read from text from the outside with, going clockwise
+/// from the top left corner heading south
+pub fn edge_starts(size: (usize, usize)) -> Vec<(Coord, Dir)> {
+    let (rows, cols) = (size.0 as i32, size.1 as i32);
+
+    let top = (0..cols).map(|col| (Coord(0, col), Dir::South));
+    let right = (0..rows).map(|row| (Coord(row, cols - 1), Dir::West));
+    let bottom = (0..cols)
+        .rev()
+        .map(|col| (Coord(rows - 1, col), Dir::North));
+    let left = (0..rows).rev().map(|row| (Coord(row, 0), Dir::East));
+
+    top.chain(right).chain(bottom).chain(left).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRAPTION: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn test_contraption() {
+        let board = Board::from_str(CONTRAPTION);
+
+        assert_eq!(
+            energized(&board, (Coord(0, 0), Dir::East), Deflect::mirrors),
+            46
+        );
+
+        let best = edge_starts(board.size())
+            .into_iter()
+            .map(|start| energized(&board, start, Deflect::mirrors))
+            .max();
+        assert_eq!(best, Some(51));
+    }
+
+    #[test]
+    fn test_absorb() {
+        let board = Board::from_str("..#..\n.....");
+        let rule = |tile: &char, _| match tile {
+            '#' => Deflect::Absorb,
+            _ => Deflect::Pass,
+        };
+
+        let poses = trace(
+            &board,
+            Orientation::Screen,
+            [(Coord(0, 0), Dir::East)],
+            rule,
+        );
+        assert_eq!(
+            poses.cells().collect::<Vec<_>>(),
+            vec![Coord(0, 0), Coord(0, 1), Coord(0, 2)]
+        );
+    }
+
+    #[test]
+    fn test_math_orientation() {
+        // The mirror turns the beam north, which is off the top on screen, but up the rows when
+        // the board is stored bottom row first
+        let board = Board::from_str("/.\n..");
+        let start = [(Coord(0, 0), Dir::East)];
+
+        let screen = trace(&board, Orientation::Screen, start, Deflect::mirrors);
+        assert_eq!(screen.cells().collect::<Vec<_>>(), vec![Coord(0, 0)]);
+
+        let math = trace(&board, Orientation::Math, start, Deflect::mirrors);
+        assert_eq!(
+            math.cells().collect::<Vec<_>>(),
+            vec![Coord(0, 0), Coord(1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_edge_starts() {
+        let starts = edge_starts((2, 3));
+        assert_eq!(starts.len(), 10);
+        assert_eq!(starts[0], (Coord(0, 0), Dir::South));
+        assert_eq!(starts[3], (Coord(0, 2), Dir::West));
+        assert_eq!(starts[9], (Coord(0, 0), Dir::East));
+    }
+}
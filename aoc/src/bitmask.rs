@@ -0,0 +1,217 @@
+/// Every subset of the bits in `mask`, from `mask` itself down to 0
+///
+/// # Examples
+/// ```
+/// use aoc::bitmask::subsets_of;
+///
+/// let subsets: Vec<u32> = subsets_of(0b1010).collect();
+/// assert_eq!(subsets, vec![0b1010, 0b1000, 0b0010, 0b0000]);
+/// ```
+pub fn subsets_of(mask: u32) -> impl Iterator<Item = u32> {
+    let mut next = Some(mask);
+    std::iter::from_fn(move || {
+        let subset = next?;
+        next = (subset != 0).then(|| (subset - 1) & mask);
+        Some(subset)
+    })
+}
+
+/// Indices of the bits set in `mask`, from lowest to highest
+///
+/// # Examples
+/// ```
+/// use aoc::bitmask::set_bits;
+///
+/// assert_eq!(set_bits(0b1001_0100).collect::<Vec<_>>(), vec![2, 4, 7]);
+/// ```
+pub fn set_bits(mask: u32) -> impl Iterator<Item = usize> {
+    let mut rest = mask;
+    std::iter::from_fn(move || {
+        let bit = (rest != 0).then(|| rest.trailing_zeros() as usize);
+        rest &= rest.wrapping_sub(1);
+        bit
+    })
+}
+
+/// Every mask of the lowest `n` bits with exactly `k` of them set, in increasing order. This is
+/// the order subset DP needs when it builds each subset from ones with one fewer element.
+///
+/// # Examples
+/// ```
+/// use aoc::bitmask::masks_with_ones;
+///
+/// let masks: Vec<u32> = masks_with_ones(4, 2).collect();
+/// assert_eq!(masks, vec![0b0011, 0b0101, 0b0110, 0b1001, 0b1010, 0b1100]);
+/// ```
+pub fn masks_with_ones(n: u32, k: u32) -> impl Iterator<Item = u32> {
+    assert!(n <= 32, "masks only have 32 bits");
+    let limit = 1u64 << n;
+    let mut next = (k <= n).then(|| (1u64 << k) - 1);
+
+    std::iter::from_fn(move || {
+        let mask = next?;
+        // Gosper's hack: move the lowest block of ones up by one, and the rest of that block
+        // back down to the bottom
+        next = if mask == 0 {
+            None
+        } else {
+            let lowest = mask & mask.wrapping_neg();
+            let ripple = mask + lowest;
+            let successor = (((ripple ^ mask) >> 2) / lowest) | ripple;
+            (successor < limit).then_some(successor)
+        };
+        Some(mask as u32)
+    })
+}
+
+/// Cheapest ways to visit every node, from [`tsp_like`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tours {
+    /// Visiting every node and stopping at whichever is last
+    pub path: Option<u64>,
+    /// Visiting every node and then going back to the start
+    pub round_trip: Option<u64>,
+}
+
+/// The cheapest ways to visit every node starting from `start`, where `dist[a][b]` is the cost
+/// of going from `a` to `b` or None if it can't be done. This fits straight onto the
+/// [`DistanceMatrix`](crate::distances::DistanceMatrix) of points of interest in a maze.
+///
+/// This is the Held-Karp dynamic program over subsets of visited nodes, taking
+/// `O(2^n * n^2)` time.
+///
+/// # Panics
+/// Panics if there are more than 20 nodes, or `start` isn't one of them
+///
+/// # Examples
+/// ```
+/// use aoc::bitmask::tsp_like;
+/// use aoc::distances::all_pairs;
+/// use aoc::grid_2d::{Board, Coord};
+///
+/// // 2016 day 24, visiting every numbered spot in the air ducts
+/// let board = Board::from_str(
+///     "###########\n\
+///      #0.1.....2#\n\
+///      #.#######.#\n\
+///      #4.......3#\n\
+///      ###########",
+/// );
+/// let points: Vec<(char, Coord)> = ('0'..='4').map(|c| (c, board.find(&c)[0])).collect();
+/// let distances = all_pairs(&board, &points, |c| *c != '#');
+///
+/// let tours = tsp_like(&distances.dist, 0);
+/// assert_eq!(tours.path, Some(14));
+/// assert_eq!(tours.round_trip, Some(20));
+/// ```
+pub fn tsp_like(dist: &[Vec<Option<u32>>], start: usize) -> Tours {
+    let n = dist.len();
+    assert!(n <= 20, "{} nodes is too many to visit every subset", n);
+    assert!(start < n, "start {} isn't one of the {} nodes", start, n);
+
+    // best[mask * n + last] is the cheapest way to visit the nodes in mask, ending at last.
+    // Adding a node only makes the mask bigger, so going through masks in order works.
+    let full = (1u32 << n) - 1;
+    let mut best = vec![u64::MAX; (1 << n) * n];
+    best[(1 << start) * n + start] = 0;
+    for mask in (0..=full).filter(|mask| mask & 1 << start != 0) {
+        for last in set_bits(mask) {
+            let cost = best[mask as usize * n + last];
+            if cost == u64::MAX {
+                continue;
+            }
+            for next in set_bits(full & !mask) {
+                if let Some(d) = dist[last][next] {
+                    let entry = &mut best[(mask | 1 << next) as usize * n + next];
+                    *entry = (*entry).min(cost + d as u64);
+                }
+            }
+        }
+    }
+
+    let ends = || {
+        best[full as usize * n..]
+            .iter()
+            .enumerate()
+            .filter(|(_, &cost)| cost != u64::MAX)
+    };
+    Tours {
+        path: ends().map(|(_, &cost)| cost).min(),
+        round_trip: ends()
+            .filter_map(|(last, &cost)| Some(cost + dist[last][start]? as u64))
+            .min(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter::permutations;
+    use crate::rng::Rng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_subsets_and_masks() {
+        for mask in [0, 1, 0b1011, 0b1111_0000, 0b1010_1010_1010] {
+            let subsets: Vec<u32> = subsets_of(mask).collect();
+            assert_eq!(subsets.len(), 1 << mask.count_ones());
+            assert_eq!(subsets.iter().collect::<HashSet<_>>().len(), subsets.len());
+            assert!(subsets.iter().all(|s| s & !mask == 0));
+            assert!(subsets.windows(2).all(|w| w[0] > w[1]));
+            assert_eq!(set_bits(mask).count(), mask.count_ones() as usize);
+        }
+
+        for n in 0..=8 {
+            let mut all: Vec<u32> = (0..=n).flat_map(|k| masks_with_ones(n, k)).collect();
+            for k in 0..=n {
+                let masks: Vec<u32> = masks_with_ones(n, k).collect();
+                assert!(masks.iter().all(|m| m.count_ones() == k));
+                assert!(masks.windows(2).all(|w| w[0] < w[1]));
+            }
+            all.sort_unstable();
+            assert_eq!(all, (0..1 << n).collect::<Vec<u32>>());
+        }
+        assert_eq!(masks_with_ones(3, 4).count(), 0);
+        assert_eq!(masks_with_ones(32, 32).collect::<Vec<_>>(), vec![u32::MAX]);
+    }
+
+    #[test]
+    fn test_tsp_matches_brute_force() {
+        let mut rng = Rng::new(1076);
+        for n in 1..=7 {
+            for _ in 0..10 {
+                let dist: Vec<Vec<Option<u32>>> = (0..n)
+                    .map(|_| {
+                        (0..n)
+                            .map(|_| (!rng.chance(0.2)).then(|| rng.range(1, 50) as u32))
+                            .collect()
+                    })
+                    .collect();
+                let start = rng.below(n as u64) as usize;
+
+                let others: Vec<usize> = (0..n).filter(|&i| i != start).collect();
+                let mut path = None;
+                let mut round_trip = None;
+                for order in permutations(&others, others.len()) {
+                    let stops: Vec<usize> = std::iter::once(start)
+                        .chain(order.into_iter().copied())
+                        .collect();
+                    let cost: Option<u64> = stops
+                        .windows(2)
+                        .map(|w| dist[w[0]][w[1]].map(u64::from))
+                        .sum();
+                    let Some(cost) = cost else {
+                        continue;
+                    };
+                    path = Some(path.map_or(cost, |p: u64| p.min(cost)));
+                    if let Some(back) = dist[*stops.last().unwrap()][start] {
+                        let total = cost + back as u64;
+                        round_trip = Some(round_trip.map_or(total, |r: u64| r.min(total)));
+                    }
+                }
+
+                assert_eq!(tsp_like(&dist, start), Tours { path, round_trip });
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::ops::Range;
+
+/// A set of bytes, checked with a single table lookup
+///
+/// # Examples
+/// ```
+/// use aoc::bytes::ByteSet;
+///
+/// const WALLS: ByteSet = ByteSet::new(b"#|");
+///
+/// assert!(WALLS.contains(b'#'));
+/// assert!(!WALLS.contains(b'.'));
+/// assert!(ByteSet::DIGITS.union(WALLS).contains(b'7'));
+/// assert!(!ByteSet::SYMBOLS.contains(b'.'));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteSet([bool; 256]);
+
+impl ByteSet {
+    pub const EMPTY: ByteSet = ByteSet([false; 256]);
+    pub const DIGITS: ByteSet = ByteSet::range(b'0', b'9');
+    pub const LETTERS: ByteSet = ByteSet::range(b'a', b'z').union(ByteSet::range(b'A', b'Z'));
+    /// ASCII punctuation other than `.`, which puzzles use for empty space
+    pub const SYMBOLS: ByteSet = ByteSet::new(b"!\"#$%&'()*+,-/:;<=>?@[\\]^_`{|}~");
+
+    pub const fn new(bytes: &[u8]) -> Self {
+        let mut table = [false; 256];
+        let mut i = 0;
+        while i < bytes.len() {
+            table[bytes[i] as usize] = true;
+            i += 1;
+        }
+        ByteSet(table)
+    }
+
+    /// Every byte from `first` to `last` inclusive
+    pub const fn range(first: u8, last: u8) -> Self {
+        let mut table = [false; 256];
+        let mut b = first as usize;
+        while b <= last as usize {
+            table[b] = true;
+            b += 1;
+        }
+        ByteSet(table)
+    }
+
+    pub const fn union(self, other: ByteSet) -> Self {
+        let mut table = self.0;
+        let mut b = 0;
+        while b < 256 {
+            table[b] |= other.0[b];
+            b += 1;
+        }
+        ByteSet(table)
+    }
+
+    /// Every byte not in the set
+    pub const fn complement(self) -> Self {
+        let mut table = self.0;
+        let mut b = 0;
+        while b < 256 {
+            table[b] = !table[b];
+            b += 1;
+        }
+        ByteSet(table)
+    }
+
+    #[inline]
+    pub const fn contains(&self, b: u8) -> bool {
+        self.0[b as usize]
+    }
+}
+
+pub fn is_digit(b: u8) -> bool {
+    ByteSet::DIGITS.contains(b)
+}
+
+/// See [`ByteSet::SYMBOLS`]
+pub fn is_symbol(b: u8) -> bool {
+    ByteSet::SYMBOLS.contains(b)
+}
+
+/// The ranges of each maximal run of bytes in `set`
+///
+/// # Examples
+/// ```
+/// use aoc::bytes::{spans, ByteSet};
+///
+/// assert_eq!(spans("ab..cde.", &ByteSet::LETTERS), vec![0..2, 4..7]);
+/// ```
+pub fn spans(line: impl AsRef<[u8]>, set: &ByteSet) -> Vec<Range<usize>> {
+    let line = line.as_ref();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < line.len() {
+        if !set.contains(line[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < line.len() && set.contains(line[i]) {
+            i += 1;
+        }
+        spans.push(start..i);
+    }
+
+    spans
+}
+
+/// Every run of digits in a line, with where it is and its value
+///
+/// # Panics
+/// Panics if a number doesn't fit in a `u32`
+///
+/// # Examples
+/// ```
+/// use aoc::bytes::number_spans;
+///
+/// assert_eq!(number_spans("467..114.."), vec![(0..3, 467), (5..8, 114)]);
+/// assert_eq!(number_spans("...*......"), vec![]);
+/// ```
+pub fn number_spans(line: impl AsRef<[u8]>) -> Vec<(Range<usize>, u32)> {
+    let line = line.as_ref();
+    spans(line, &ByteSet::DIGITS)
+        .into_iter()
+        .map(|span| {
+            let value = line[span.clone()].iter().fold(0u32, |n, &b| {
+                n.checked_mul(10)
+                    .and_then(|n| n.checked_add((b - b'0') as u32))
+                    .unwrap_or_else(|| panic!("number at {:?} doesn't fit in u32", span))
+            });
+            (span, value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sets() {
+        for b in 0..=255u8 {
+            assert_eq!(is_digit(b), b.is_ascii_digit());
+            assert_eq!(is_symbol(b), b.is_ascii_punctuation() && b != b'.');
+            assert_eq!(ByteSet::LETTERS.contains(b), b.is_ascii_alphabetic());
+            assert_ne!(
+                ByteSet::DIGITS.contains(b),
+                ByteSet::DIGITS.complement().contains(b)
+            );
+            assert!(!ByteSet::EMPTY.contains(b));
+        }
+    }
+
+    #[test]
+    fn test_gear_ratios() {
+        // 2023 day 3
+        let schematic = [
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ];
+
+        // Numbers next to a symbol, including diagonally
+        let symbol_at = |row: isize, col: isize| {
+            let line = schematic.get(usize::try_from(row).ok()?)?.as_bytes();
+            line.get(usize::try_from(col).ok()?)
+                .copied()
+                .filter(|&b| is_symbol(b))
+        };
+        let total: u32 = schematic
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| number_spans(line).into_iter().map(move |n| (row, n)))
+            .filter(|(row, (span, _))| {
+                let row = *row as isize;
+                (row - 1..=row + 1).any(|r| {
+                    (span.start as isize - 1..=span.end as isize).any(|c| symbol_at(r, c).is_some())
+                })
+            })
+            .map(|(_, (_, value))| value)
+            .sum();
+
+        assert_eq!(total, 4361);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in u32")]
+    fn test_overflow() {
+        number_spans("99999999999");
+    }
+}
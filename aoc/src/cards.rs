@@ -0,0 +1,224 @@
+use crate::parse::ParseError;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Poker-like hand types, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl HandType {
+    /// Classify a hand by the sizes of its groups of matching cards, largest first
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::cards::HandType;
+    ///
+    /// assert_eq!(HandType::from_groups(&[3, 2]), HandType::FullHouse);
+    /// assert_eq!(HandType::from_groups(&[2, 2, 1]), HandType::TwoPair);
+    /// ```
+    pub fn from_groups(groups: &[usize]) -> Self {
+        match groups {
+            [5, ..] => HandType::FiveOfAKind,
+            [4, ..] => HandType::FourOfAKind,
+            [3, 2, ..] => HandType::FullHouse,
+            [3, ..] => HandType::ThreeOfAKind,
+            [2, 2, ..] => HandType::TwoPair,
+            [2, ..] => HandType::OnePair,
+            _ => HandType::HighCard,
+        }
+    }
+}
+
+/// How hands are ranked: first by type, then card by card from the left
+#[derive(Debug, Clone, Copy)]
+pub struct Rules<'a> {
+    /// Card labels, weakest first
+    pub order: &'a str,
+    /// A card that counts as whichever card makes the hand strongest. It's still ranked by its
+    /// own place in `order` when breaking ties.
+    pub wildcard: Option<char>,
+    /// Turns the sizes of a hand's groups of matching cards, largest first, into its type
+    pub classify: fn(&[usize]) -> HandType,
+}
+
+impl Rules<'_> {
+    /// Camel cards (2023 day 7)
+    pub const CAMEL: Rules<'static> = Rules {
+        order: "23456789TJQKA",
+        wildcard: None,
+        classify: HandType::from_groups,
+    };
+
+    /// Camel cards with jokers, which are wild but the weakest card on their own
+    pub const CAMEL_JOKERS: Rules<'static> = Rules {
+        order: "J23456789TQKA",
+        wildcard: Some('J'),
+        classify: HandType::from_groups,
+    };
+
+    /// Strength of a card, from 0 for the weakest
+    ///
+    /// # Panics
+    /// Panics if the card isn't in `order`
+    pub fn strength(&self, card: char) -> usize {
+        self.order
+            .chars()
+            .position(|c| c == card)
+            .unwrap_or_else(|| panic!("card {:?} isn't in {:?}", card, self.order))
+    }
+
+    /// Sizes of the groups of matching cards in a hand, largest first. Wildcards join the
+    /// largest group.
+    pub fn groups(&self, hand: &str) -> Vec<usize> {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        let mut wild = 0;
+        for card in hand.chars() {
+            if Some(card) == self.wildcard {
+                wild += 1;
+            } else {
+                *counts.entry(card).or_default() += 1;
+            }
+        }
+
+        let mut groups: Vec<usize> = counts.into_values().collect();
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+        match groups.first_mut() {
+            Some(largest) => *largest += wild,
+            None if wild > 0 => groups.push(wild),
+            None => {}
+        }
+        groups
+    }
+
+    pub fn hand_type(&self, hand: &str) -> HandType {
+        (self.classify)(&self.groups(hand))
+    }
+
+    /// Compare two hands, by type and then by the strength of each card in turn
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::cards::Rules;
+    /// use std::cmp::Ordering;
+    ///
+    /// assert_eq!(Rules::CAMEL.compare("KK677", "KTJJT"), Ordering::Greater);
+    /// assert_eq!(Rules::CAMEL_JOKERS.compare("KK677", "KTJJT"), Ordering::Less);
+    /// ```
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.hand_type(a).cmp(&self.hand_type(b)).then_with(|| {
+            let a = a.chars().map(|c| self.strength(c));
+            let b = b.chars().map(|c| self.strength(c));
+            a.cmp(b)
+        })
+    }
+
+    /// Sort hands weakest first
+    pub fn sort<H: AsRef<str>>(&self, hands: &mut [H]) {
+        hands.sort_by(|a, b| self.compare(a.as_ref(), b.as_ref()));
+    }
+
+    /// Total winnings of a set of hands and their bids, where each bid is multiplied by its
+    /// hand's rank from 1 for the weakest
+    pub fn winnings(&self, bids: &[(&str, u64)]) -> u64 {
+        let mut bids = bids.to_vec();
+        bids.sort_by(|a, b| self.compare(a.0, b.0));
+        bids.iter()
+            .zip(1..)
+            .map(|((_, bid), rank)| bid * rank)
+            .sum()
+    }
+}
+
+/// Parse lines of a hand followed by its bid, like `32T3K 765`. Every card has to be in the
+/// rules' `order`, so that bad input is reported here rather than panicking when ranking.
+///
+/// # Examples
+/// ```
+/// use aoc::cards::{parse_bids, Rules};
+///
+/// let bids = parse_bids("32T3K 765\nT55J5 684", &Rules::CAMEL).unwrap();
+/// assert_eq!(bids[1], ("T55J5", 684));
+/// assert_eq!(
+///     parse_bids("32T3K\n", &Rules::CAMEL).unwrap_err().to_string(),
+///     "failed to parse line 1: \"32T3K\" (expected a hand and a bid)"
+/// );
+/// assert_eq!(
+///     parse_bids("32T3K 765\n32T1K 5", &Rules::CAMEL).unwrap_err().to_string(),
+///     "failed to parse line 2: \"32T1K 5\" (card '1' isn't one of 23456789TJQKA)"
+/// );
+/// ```
+pub fn parse_bids<'a>(input: &'a str, rules: &Rules) -> Result<Vec<(&'a str, u64)>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_bid(line, rules).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+fn parse_bid<'a>(line: &'a str, rules: &Rules) -> Result<(&'a str, u64), ParseError> {
+    let (hand, bid) = line
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| ParseError::new(line, "expected a hand and a bid"))?;
+    if let Some(card) = hand.chars().find(|&c| !rules.order.contains(c)) {
+        return Err(ParseError::new(
+            line,
+            format!("card {:?} isn't one of {}", card, rules.order),
+        ));
+    }
+    let bid = bid
+        .trim()
+        .parse()
+        .map_err(|e| ParseError::new(line, format!("bad bid: {}", e)))?;
+    Ok((hand, bid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn test_camel_cards() {
+        // 2023 day 7
+        let bids = parse_bids(EXAMPLE, &Rules::CAMEL).unwrap();
+
+        assert_eq!(Rules::CAMEL.winnings(&bids), 6440);
+        assert_eq!(Rules::CAMEL_JOKERS.winnings(&bids), 5905);
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let rules = Rules::CAMEL_JOKERS;
+
+        assert_eq!(rules.hand_type("JJJJJ"), HandType::FiveOfAKind);
+        assert_eq!(rules.hand_type("QJJQ2"), HandType::FourOfAKind);
+        assert_eq!(rules.hand_type("2345J"), HandType::OnePair);
+        assert_eq!(rules.hand_type("2233J"), HandType::FullHouse);
+        // Jokers are the weakest card when breaking ties
+        assert_eq!(rules.compare("JKKK2", "QQQQ2"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut hands = vec!["KK677", "32T3K", "QQQJA", "T55J5", "KTJJT"];
+        Rules::CAMEL.sort(&mut hands);
+
+        assert_eq!(hands, vec!["32T3K", "KTJJT", "KK677", "T55J5", "QQQJA"]);
+    }
+}
@@ -0,0 +1,414 @@
+use crate::answers::{self, diff, AnswerStore};
+use crate::config::{config, RunProfile};
+use crate::input::InputSource;
+use crate::memory::MemoryUsage;
+use crate::runner::{
+    day_from_package, parse_time_limit, solve_with_limit, year_from_package, Answer, IntoAnswer,
+    OutputFormat, RunResult, Solved, TimedOut,
+};
+use crate::timing::{self, Phase};
+use crate::visualize;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, ValueEnum};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Which part(s) of a day to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+    /// Both parts, reading the input once
+    #[value(alias = "all")]
+    Both,
+}
+
+impl Part {
+    pub fn numbers(self) -> Vec<u32> {
+        match self {
+            Part::One => vec![1],
+            Part::Two => vec![2],
+            Part::Both => vec![1, 2],
+        }
+    }
+}
+
+/// Command line arguments shared by every day's binary
+#[derive(Debug, Parser)]
+#[command(about = "Run a day's solution", args_override_self = true)]
+pub struct Args {
+    /// Start from the arguments of a profile from the config file (`quick` and `full` are
+    /// built in). Arguments given alongside it take precedence.
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Part to run
+    #[arg(short, long, value_enum, default_value_t = Part::Both)]
+    pub part: Part,
+
+    /// Input to run on: input, example, example2, ..., a path, or - for stdin. `example` picks
+    /// the part's own example file if there is one.
+    #[arg(short, long, default_value = "input")]
+    pub input: String,
+
+    /// Step through the solution's visualization, if it has one
+    #[arg(short, long, overrides_with = "no_visualize")]
+    pub visualize: bool,
+
+    /// Don't step through the visualization, eg. to turn off a profile's `visualize`
+    #[arg(long, overrides_with = "visualize")]
+    pub no_visualize: bool,
+
+    /// Print timings and memory usage alongside the answers
+    #[arg(short, long, overrides_with = "no_time")]
+    pub time: bool,
+
+    /// Don't print timings, eg. to turn off a profile's `time`
+    #[arg(long, overrides_with = "time")]
+    pub no_time: bool,
+
+    /// Check the answers against the ones saved by `aoc-cli`, exiting with an error if any
+    /// differ. Only for the real input.
+    #[arg(long, overrides_with = "no_check")]
+    pub check: bool,
+
+    /// Don't check the answers, eg. to turn off a profile's `check`
+    #[arg(long, overrides_with = "check")]
+    pub no_check: bool,
+
+    /// Print answers as text or as JSON lines with timings
+    #[arg(long, default_value = "text", value_parser = OutputFormat::from_arg)]
+    pub output: OutputFormat,
+
+    /// Give up with an error after this many (possibly fractional) seconds
+    #[arg(long, value_name = "SECS", value_parser = parse_time_limit)]
+    pub time_limit: Option<Duration>,
+
+    /// Solve both parts at once on separate threads. Memory usage isn't reported, since it
+    /// can't be split between parts running side by side.
+    #[arg(long, conflicts_with = "visualize")]
+    pub parallel: bool,
+}
+
+impl Args {
+    /// Parse `argv` (including the binary name), expanding `--profile` into the arguments it
+    /// stands for. Those go before the rest of `argv`, so anything given explicitly overrides
+    /// them.
+    pub fn parse_with_profiles<I, T>(
+        argv: I,
+        profiles: &BTreeMap<String, RunProfile>,
+    ) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let argv: Vec<OsString> = argv.into_iter().map(Into::into).collect();
+        let args = Self::try_parse_from(&argv)?;
+
+        let Some(name) = &args.profile else {
+            return Ok(args);
+        };
+        let profile = profiles.get(name).ok_or_else(|| {
+            let known: Vec<&str> = profiles.keys().map(String::as_str).collect();
+            Self::command().error(
+                ErrorKind::InvalidValue,
+                format!(
+                    "unknown profile {} (expected one of: {})",
+                    name,
+                    known.join(", ")
+                ),
+            )
+        })?;
+
+        let expanded = argv
+            .iter()
+            .take(1)
+            .cloned()
+            .chain(profile.to_args().into_iter().map(OsString::from))
+            .chain(argv.iter().skip(1).cloned());
+        Self::try_parse_from(expanded)
+    }
+}
+
+/// What solving one part produced, along with the phases it timed
+type Outcome = (Result<Solved<crate::Result<Answer>>, TimedOut>, Vec<Phase>);
+
+/// Index into `inputs` of the input for `part`, reading it unless it's the same source as the
+/// last one read
+fn load_input(inputs: &mut Vec<(InputSource, String, Duration)>, arg: &str, part: u32) -> usize {
+    let source = InputSource::from_arg(arg, &part.to_string());
+    if inputs.last().is_none_or(|(s, _, _)| *s != source) {
+        let start = Instant::now();
+        let input = source.read().unwrap_or_else(|e| {
+            eprintln!("error: failed to read input {}: {}", arg, e);
+            std::process::exit(1);
+        });
+        inputs.push((source, input, start.elapsed()));
+    }
+    inputs.len() - 1
+}
+
+/// Entry point for a day's binary: parse the command line, run the requested part(s),
+/// and print the results. Run with `--help` for usage.
+///
+/// With `--part both` (the default), the input is only read once, unless part 2 has its own
+/// example file. With `--parallel`, both inputs are read up front and the parts are solved at
+/// the same time, printing the results in order once both are done. Solutions returning an
+/// error exit with it, eg. `error: day 1 part 2: failed to parse line 37: "X12"`.
+///
+/// With `--check`, each answer is compared to the one `aoc-cli` saved for it, showing a diff and
+/// exiting with an error at the end if any changed. Every `--time`-style flag has a `--no-` form
+/// to turn off what a profile turned on.
+pub fn run<A, B>(package: &str, part_1: fn(&str) -> A, part_2: fn(&str) -> B)
+where
+    A: IntoAnswer + 'static,
+    B: IntoAnswer + 'static,
+{
+    let args = Args::parse_with_profiles(std::env::args_os(), &config().profiles)
+        .unwrap_or_else(|e| e.exit());
+
+    if args.visualize {
+        visualize::enable();
+    }
+    if args.check && args.input != "input" {
+        eprintln!(
+            "error: --check needs the real input, answers for {} aren't saved",
+            args.input
+        );
+        std::process::exit(1);
+    }
+
+    let parts = args.part.numbers();
+    let single = parts.len() == 1;
+    let parallel = args.parallel && !single;
+    let (year, day) = (year_from_package(package), day_from_package(package));
+    let saved = args
+        .check
+        .then(|| AnswerStore::load(answers::default_path()));
+
+    let solve = |part: u32, input: &str| -> Outcome {
+        let solved = match part {
+            1 => solve_with_limit(input, move |i| part_1(i).into_answer(), args.time_limit),
+            _ => solve_with_limit(input, move |i| part_2(i).into_answer(), args.time_limit),
+        };
+        (solved, timing::take_report())
+    };
+
+    // Print a part's result, returning whether it failed its check
+    let report = |part: u32, parse: Duration, (solved, phases): Outcome| -> bool {
+        let (answer, solve, memory) = solved.unwrap_or_else(|timed_out| {
+            eprintln!(
+                "Part {} {} (input loaded in {:.3} ms), aborting",
+                part,
+                timed_out,
+                parse.as_secs_f64() * 1000.0
+            );
+            std::process::exit(1);
+        });
+        let answer = answer.unwrap_or_else(|e| {
+            eprintln!("error: day {} part {}: {}", day, part, e);
+            std::process::exit(1);
+        });
+
+        let result = RunResult {
+            day,
+            part,
+            answer,
+            parse,
+            solve,
+            memory: if parallel {
+                MemoryUsage::default()
+            } else {
+                memory
+            },
+        };
+
+        let label = if single {
+            "Result".to_string()
+        } else {
+            format!("Part {}", part)
+        };
+
+        match args.output {
+            OutputFormat::Text if args.time => {
+                println!("{}: {} ({})", label, result.answer, result.summary());
+                for phase in phases {
+                    println!("  {}", phase);
+                }
+            }
+            OutputFormat::Text => println!("{}: {}", label, result.answer),
+            OutputFormat::Json => println!("{}", result.to_json()),
+        }
+
+        let Some(saved) = &saved else {
+            return false;
+        };
+        let answer = result.answer.to_string();
+        match saved.get(year, day, part) {
+            Some(expected) if expected == answer => false,
+            Some(expected) => {
+                eprint!("Part {} answer changed:\n{}", part, diff(expected, &answer));
+                true
+            }
+            None => {
+                eprintln!(
+                    "Part {} has no saved answer to check, run aoc-cli to save it",
+                    part
+                );
+                false
+            }
+        }
+    };
+
+    // Inputs read so far. Consecutive parts on the same source share it.
+    let mut inputs: Vec<(InputSource, String, Duration)> = Vec::new();
+    let mut changed = 0;
+
+    if !parallel {
+        for part in parts {
+            let i = load_input(&mut inputs, &args.input, part);
+            let (_, input, parse) = &inputs[i];
+            changed += report(part, *parse, solve(part, input)) as usize;
+        }
+    } else {
+        let loaded: Vec<(u32, usize)> = parts
+            .into_iter()
+            .map(|part| (part, load_input(&mut inputs, &args.input, part)))
+            .collect();
+
+        let solve = &solve;
+        let outcomes: Vec<Outcome> = thread::scope(|scope| {
+            let handles: Vec<_> = loaded
+                .iter()
+                .map(|&(part, i)| {
+                    let input = inputs[i].1.as_str();
+                    // Match the main thread's stack size, so deeply recursive solutions behave
+                    // the same
+                    thread::Builder::new()
+                        .stack_size(8 * 1024 * 1024)
+                        .spawn_scoped(scope, move || solve(part, input))
+                        .expect("Failed to spawn solver thread")
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .collect()
+        });
+
+        for (&(part, i), outcome) in loaded.iter().zip(outcomes) {
+            changed += report(part, inputs[i].2, outcome) as usize;
+        }
+    }
+
+    if changed > 0 {
+        eprintln!(
+            "{} answer(s) changed, run aoc-cli with --update if that's expected",
+            changed
+        );
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, clap::Error> {
+        Args::try_parse_from(std::iter::once("day01").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_defaults() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.part, Part::Both);
+        assert_eq!(args.input, "input");
+        assert_eq!(args.output, OutputFormat::Text);
+        assert_eq!(args.time_limit, None);
+        assert!(!args.visualize && !args.time && !args.parallel && !args.check);
+    }
+
+    #[test]
+    fn test_parts() {
+        assert_eq!(parse(&["--part", "1"]).unwrap().part.numbers(), vec![1]);
+        assert_eq!(parse(&["-p", "2"]).unwrap().part.numbers(), vec![2]);
+        assert_eq!(parse(&["-p", "all"]).unwrap().part.numbers(), vec![1, 2]);
+        assert!(parse(&["--part", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_flags() {
+        let args = parse(&[
+            "-i",
+            "example2",
+            "--output=json",
+            "--time-limit",
+            "0.5",
+            "-tv",
+        ])
+        .unwrap();
+
+        assert_eq!(args.input, "example2");
+        assert_eq!(args.output, OutputFormat::Json);
+        assert_eq!(args.time_limit, Some(Duration::from_millis(500)));
+        assert!(args.visualize && args.time);
+
+        assert!(parse(&["--parallel"]).unwrap().parallel);
+        assert!(parse(&["--parallel", "-v"]).is_err());
+        assert!(parse(&["--output", "yaml"]).is_err());
+        assert!(parse(&["--time-limit", "-1"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_profiles() {
+        let profiles = BTreeMap::from([(
+            "slow".to_string(),
+            RunProfile {
+                part: Some("2".to_string()),
+                input: Some("example".to_string()),
+                time: true,
+                time_limit: Some(60),
+                check: true,
+                ..Default::default()
+            },
+        )]);
+        let parse = |args: &[&str]| {
+            Args::parse_with_profiles(
+                std::iter::once("day01").chain(args.iter().copied()),
+                &profiles,
+            )
+        };
+
+        let args = parse(&["--profile", "slow"]).unwrap();
+        assert_eq!(args.part, Part::Two);
+        assert_eq!(args.input, "example");
+        assert_eq!(args.time_limit, Some(Duration::from_secs(60)));
+        assert!(args.time);
+
+        // Explicit arguments win, wherever they are relative to --profile
+        let args = parse(&["-i", "input", "--profile=slow", "--part", "1"]).unwrap();
+        assert_eq!(args.part, Part::One);
+        assert_eq!(args.input, "input");
+        assert!(args.time);
+
+        // Profile flags can be turned back off
+        let args = parse(&["--profile", "slow", "--no-time"]).unwrap();
+        assert!(!args.time && args.check);
+        let args = parse(&["--profile", "slow", "--no-check", "-v", "--no-visualize"]).unwrap();
+        assert!(args.time && !args.check && !args.visualize);
+        assert!(!parse(&["--no-time", "--profile", "slow"]).unwrap().time);
+
+        let err = parse(&["--profile", "missing"]).unwrap_err();
+        assert!(err.to_string().contains("expected one of: slow"));
+    }
+}
@@ -0,0 +1,433 @@
+//! User configuration, loaded from `~/.config/aoc/config.toml` (or `$XDG_CONFIG_HOME/aoc`, or
+//! the file named by `$AOC_CONFIG`). Every setting is optional, and can be overridden with an
+//! environment variable.
+//!
+//! ```toml
+//! session = "53616c7465645f5f..."   # AOC_SESSION
+//! year = 2025                       # AOC_YEAR
+//! input_dir = "~/aoc/inputs"        # AOC_INPUT_DIR
+//! cache_dir = "/tmp/aoc-cache"      # AOC_CACHE_DIR
+//!
+//! [visualize]
+//! delay_ms = 50                     # AOC_VISUALIZE_DELAY_MS
+//! clear = true                      # AOC_VISUALIZE_CLEAR
+//! frames_dir = "/tmp/frames"        # AOC_VISUALIZE_FRAMES_DIR
+//!
+//! # Defaults for a day's binary when run with `--profile slow`
+//! [profile.slow]
+//! part = "2"
+//! input = "input"
+//! time = true
+//! time_limit = 60
+//! check = true
+//! ```
+//!
+//! Only the small subset of TOML above is understood: `[section]` headers, and `key = value`
+//! with string, integer or boolean values.
+
+use crate::runner::MAIN_YEAR;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Session cookie for adventofcode.com
+    pub session: Option<String>,
+    /// Year that `aoc-cli` commands default to when `--year` isn't given
+    pub year: u32,
+    /// Where downloaded inputs and puzzle pages are kept. Tools default to `inputs/` in the
+    /// workspace when this isn't set.
+    pub input_dir: Option<PathBuf>,
+    /// Where expensive intermediate results (eg. [`crate::distances`]) are cached
+    pub cache_dir: PathBuf,
+    pub visualize: VisualizeConfig,
+    /// Named sets of arguments for a day's binary, chosen with `--profile`. `quick` and `full`
+    /// are built in, and can be changed like any other profile.
+    pub profiles: BTreeMap<String, RunProfile>,
+}
+
+/// A named set of defaults for the command line of day binaries (`aoc::cli`), from a
+/// `[profile.<name>]` section. Arguments given on the command line take precedence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunProfile {
+    pub part: Option<String>,
+    pub input: Option<String>,
+    pub time: bool,
+    pub visualize: bool,
+    pub output: Option<String>,
+    /// In seconds
+    pub time_limit: Option<u64>,
+    /// Check the answers against the ones saved by `aoc-cli`
+    pub check: bool,
+}
+
+impl RunProfile {
+    /// The profile as command line arguments
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut option = |flag: &str, value: Option<String>| {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value);
+            }
+        };
+
+        option("--part", self.part.clone());
+        option("--input", self.input.clone());
+        option("--output", self.output.clone());
+        option("--time-limit", self.time_limit.map(|secs| secs.to_string()));
+
+        if self.time {
+            args.push("--time".to_string());
+        }
+        if self.visualize {
+            args.push("--visualize".to_string());
+        }
+        if self.check {
+            args.push("--check".to_string());
+        }
+
+        args
+    }
+}
+
+/// The profiles available without any configuration
+fn builtin_profiles() -> BTreeMap<String, RunProfile> {
+    BTreeMap::from([
+        (
+            "quick".to_string(),
+            RunProfile {
+                part: Some("1".to_string()),
+                input: Some("example".to_string()),
+                ..Default::default()
+            },
+        ),
+        (
+            "full".to_string(),
+            RunProfile {
+                part: Some("both".to_string()),
+                input: Some("input".to_string()),
+                time: true,
+                check: true,
+                ..Default::default()
+            },
+        ),
+    ])
+}
+
+/// Defaults for [`crate::visualize::step`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VisualizeConfig {
+    /// Advance automatically after this many milliseconds, instead of waiting for enter
+    pub delay_ms: Option<u64>,
+    /// Clear the terminal before drawing each step
+    pub clear: bool,
+    /// Also write each step to a numbered file in this directory
+    pub frames_dir: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            year: MAIN_YEAR,
+            input_dir: None,
+            cache_dir: std::env::temp_dir().join("aoc-cache"),
+            visualize: VisualizeConfig::default(),
+            profiles: builtin_profiles(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+/// Parse the supported TOML subset into a map from `section.key` (or just `key` before any
+/// section) to value
+fn parse_toml(text: &str) -> Result<HashMap<String, Value>, String> {
+    let mut values = HashMap::new();
+    let mut section = String::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let error = |msg: &str| format!("line {}: {}", i + 1, msg);
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| error("unterminated section header"))?;
+            section = format!("{}.", name.trim());
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected key = value"))?;
+        let value = value.trim();
+
+        let value = if let Some(s) = value.strip_prefix('"') {
+            let s = s
+                .strip_suffix('"')
+                .ok_or_else(|| error("unterminated string"))?;
+            Value::Str(s.replace("\\\"", "\"").replace("\\\\", "\\"))
+        } else if value == "true" || value == "false" {
+            Value::Bool(value == "true")
+        } else {
+            Value::Int(
+                value
+                    .replace('_', "")
+                    .parse()
+                    .map_err(|_| error(&format!("unsupported value {}", value)))?,
+            )
+        };
+
+        values.insert(format!("{}{}", section, key.trim()), value);
+    }
+
+    Ok(values)
+}
+
+/// Remove a trailing `# comment`, ignoring any `#` inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' if prev != '\\' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+        prev = c;
+    }
+    line
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+impl Config {
+    /// Location of the config file
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os("AOC_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(config_home.join("aoc").join("config.toml"))
+    }
+
+    /// Parse a config file's contents, without applying environment overrides
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let values = parse_toml(text)?;
+        let mut config = Config::default();
+
+        for (key, value) in values {
+            match (key.as_str(), value) {
+                ("session", Value::Str(s)) => config.session = Some(s),
+                ("year", Value::Int(n)) => {
+                    config.year = u32::try_from(n).map_err(|_| format!("invalid year {}", n))?
+                }
+                ("input_dir", Value::Str(s)) => config.input_dir = Some(expand_home(&s)),
+                ("cache_dir", Value::Str(s)) => config.cache_dir = expand_home(&s),
+                ("visualize.delay_ms", Value::Int(n)) => {
+                    config.visualize.delay_ms =
+                        Some(u64::try_from(n).map_err(|_| format!("invalid delay_ms {}", n))?)
+                }
+                ("visualize.clear", Value::Bool(b)) => config.visualize.clear = b,
+                ("visualize.frames_dir", Value::Str(s)) => {
+                    config.visualize.frames_dir = Some(expand_home(&s))
+                }
+                (key, value) if key.starts_with("profile.") => {
+                    let (name, setting) = key["profile.".len()..]
+                        .rsplit_once('.')
+                        .ok_or_else(|| format!("unexpected setting {}", key))?;
+                    let profile = config.profiles.entry(name.to_string()).or_default();
+
+                    match (setting, value) {
+                        ("part", Value::Str(s)) => profile.part = Some(s),
+                        ("input", Value::Str(s)) => profile.input = Some(s),
+                        ("output", Value::Str(s)) => profile.output = Some(s),
+                        ("time", Value::Bool(b)) => profile.time = b,
+                        ("visualize", Value::Bool(b)) => profile.visualize = b,
+                        ("check", Value::Bool(b)) => profile.check = b,
+                        ("time_limit", Value::Int(n)) => {
+                            profile.time_limit = Some(
+                                u64::try_from(n)
+                                    .map_err(|_| format!("invalid time_limit {}", n))?,
+                            )
+                        }
+                        (_, value) => {
+                            return Err(format!("unexpected setting {} = {:?}", key, value))
+                        }
+                    }
+                }
+                (key, value) => return Err(format!("unexpected setting {} = {:?}", key, value)),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Load a config file, treating a missing file as empty
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Override settings with any `AOC_*` environment variables that are set
+    pub fn apply_env(&mut self) -> Result<(), String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+        if let Some(session) = var("AOC_SESSION") {
+            self.session = Some(session);
+        }
+        if let Some(year) = var("AOC_YEAR") {
+            self.year = year
+                .parse()
+                .map_err(|_| format!("invalid AOC_YEAR {}", year))?;
+        }
+        if let Some(dir) = var("AOC_INPUT_DIR") {
+            self.input_dir = Some(expand_home(&dir));
+        }
+        if let Some(dir) = var("AOC_CACHE_DIR") {
+            self.cache_dir = expand_home(&dir);
+        }
+        if let Some(delay) = var("AOC_VISUALIZE_DELAY_MS") {
+            self.visualize.delay_ms = Some(
+                delay
+                    .parse()
+                    .map_err(|_| format!("invalid AOC_VISUALIZE_DELAY_MS {}", delay))?,
+            );
+        }
+        if let Some(clear) = var("AOC_VISUALIZE_CLEAR") {
+            self.visualize.clear = matches!(clear.as_str(), "1" | "true");
+        }
+        if let Some(dir) = var("AOC_VISUALIZE_FRAMES_DIR") {
+            self.visualize.frames_dir = Some(expand_home(&dir));
+        }
+
+        Ok(())
+    }
+
+    /// Load the config file and apply environment overrides
+    pub fn load() -> Result<Self, String> {
+        let mut config = match Self::path() {
+            Some(path) => Self::load_from(&path)?,
+            None => Config::default(),
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+}
+
+/// The configuration, loaded once on first use
+///
+/// # Panics
+/// Panics if the config file or an environment override is invalid
+pub fn config() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(|| Config::load().unwrap_or_else(|e| panic!("Invalid config: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r#"
+# Top level settings
+session = "abc#123"  # not part of the token
+year = 2_024
+
+[visualize]
+delay_ms = 50
+clear = true
+frames_dir = "/tmp/frames"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.session.as_deref(), Some("abc#123"));
+        assert_eq!(config.year, 2024);
+        assert_eq!(config.input_dir, None);
+        assert_eq!(
+            config.visualize,
+            VisualizeConfig {
+                delay_ms: Some(50),
+                clear: true,
+                frames_dir: Some(PathBuf::from("/tmp/frames")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        let config = Config::parse(
+            r#"
+[profile.quick]
+input = "example2"
+
+[profile.slow]
+part = "2"
+time = true
+time_limit = 60
+check = true
+"#,
+        )
+        .unwrap();
+
+        // Built in profiles can be partially overridden
+        assert_eq!(
+            config.profiles["quick"].to_args(),
+            vec!["--part", "1", "--input", "example2"]
+        );
+        assert_eq!(
+            config.profiles["full"].to_args(),
+            vec!["--part", "both", "--input", "input", "--time", "--check"]
+        );
+        assert_eq!(
+            config.profiles["slow"].to_args(),
+            vec!["--part", "2", "--time-limit", "60", "--time", "--check"]
+        );
+
+        assert!(Config::parse("[profile.slow]\ntime = 5").is_err());
+        assert!(Config::parse("[profile]\ntime = true").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Config::parse("year = \"2024\"").is_err());
+        assert!(Config::parse("[visualize\nclear = true").is_err());
+        assert!(Config::parse("sesion = \"typo\"").is_err());
+        assert!(Config::parse("session").is_err());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let config = Config::load_from(Path::new("/nonexistent/aoc/config.toml")).unwrap();
+        assert_eq!(config, Config::default());
+    }
+}
@@ -0,0 +1,218 @@
+use crate::grid_2d::Board;
+use std::fmt::{Debug, Display};
+
+/// Maximum width of a rendered row of sections before wrapping onto the next row
+const MAX_WIDTH: usize = 160;
+
+/// Gap between sections rendered side by side
+const GAP: &str = "   ";
+
+/// A titled block of text lines, rendered as one column of a [`DebugView`].
+#[derive(Debug, Clone, Default)]
+pub struct Section {
+    pub title: String,
+    pub lines: Vec<String>,
+    /// Key-value fields, aligned on the key when rendered. Shown before `lines`.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Section {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a line of free text
+    pub fn line<D: Display>(mut self, line: D) -> Self {
+        self.lines.push(line.to_string());
+        self
+    }
+
+    /// Add a `key: value` field, formatted with `Display`
+    pub fn field<D: Display>(mut self, key: &str, value: D) -> Self {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a `key: value` field, formatted with `Debug`
+    pub fn field_debug<D: Debug>(mut self, key: &str, value: D) -> Self {
+        self.fields.push((key.to_string(), format!("{:?}", value)));
+        self
+    }
+
+    /// A section containing a rendered board
+    pub fn board<T>(title: &str, board: &Board<T>) -> Self
+    where
+        T: Clone + Display,
+    {
+        let mut section = Self::new(title);
+        section.lines = board
+            .matrix
+            .iter()
+            .map(|row| row.iter().map(|e| e.to_string()).collect())
+            .collect();
+        section
+    }
+
+    /// The rendered lines of this section, not including the title
+    fn body(&self) -> Vec<String> {
+        let key_width = self
+            .fields
+            .iter()
+            .map(|(k, _)| k.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        self.fields
+            .iter()
+            .map(|(k, v)| format!("{:>width$}: {}", k, v, width = key_width))
+            .chain(self.lines.iter().cloned())
+            .collect()
+    }
+}
+
+/// A compound puzzle state (eg. a board plus agents plus counters) that can be rendered
+/// as several labelled sections for debugging.
+///
+/// # Examples
+/// ```
+/// use aoc::debug_view::{DebugView, Section};
+///
+/// struct Dial {
+///     position: i32,
+///     zeros: usize,
+/// }
+///
+/// impl DebugView for Dial {
+///     fn sections(&self) -> Vec<Section> {
+///         vec![Section::new("dial")
+///             .field("position", self.position)
+///             .field("zeros", self.zeros)]
+///     }
+/// }
+///
+/// let rendered = Dial { position: 50, zeros: 3 }.render();
+/// assert!(rendered.contains("position: 50"));
+/// assert!(rendered.contains("   zeros: 3"));
+/// ```
+pub trait DebugView {
+    fn sections(&self) -> Vec<Section>;
+
+    /// Render all sections side by side, wrapping onto new rows as needed
+    fn render(&self) -> String {
+        render_sections(&self.sections())
+    }
+}
+
+impl<T> DebugView for Board<T>
+where
+    T: Clone + Display,
+{
+    fn sections(&self) -> Vec<Section> {
+        vec![Section::board("board", self)]
+    }
+}
+
+/// Render sections as columns laid out side by side, each with an underlined title.
+/// Sections that would exceed the maximum width wrap onto a new row.
+pub fn render_sections(sections: &[Section]) -> String {
+    let rendered: Vec<(usize, Vec<String>)> = sections
+        .iter()
+        .map(|section| {
+            let mut lines = vec![section.title.clone()];
+            let body = section.body();
+
+            let width = body
+                .iter()
+                .chain(lines.iter())
+                .map(|l| l.chars().count())
+                .max()
+                .unwrap_or(0);
+
+            lines.push("-".repeat(width));
+            lines.extend(body);
+
+            (width, lines)
+        })
+        .collect();
+
+    // Group the sections into rows that fit within the maximum width
+    let mut rows: Vec<Vec<&(usize, Vec<String>)>> = Vec::new();
+    let mut row_width = 0;
+    for section in rendered.iter() {
+        match rows.last_mut() {
+            Some(row) if row_width + GAP.len() + section.0 <= MAX_WIDTH => {
+                row.push(section);
+                row_width += GAP.len() + section.0;
+            }
+            _ => {
+                rows.push(vec![section]);
+                row_width = section.0;
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+
+        let height = row.iter().map(|(_, lines)| lines.len()).max().unwrap_or(0);
+        for line_no in 0..height {
+            let line: Vec<String> = row
+                .iter()
+                .map(|(width, lines)| {
+                    let line = lines.get(line_no).map(String::as_str).unwrap_or("");
+                    let padding = width - line.chars().count();
+                    format!("{}{}", line, " ".repeat(padding))
+                })
+                .collect();
+
+            out.push_str(line.join(GAP).trim_end());
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_by_side() {
+        let board = Board::from_str("#.\n.#");
+        let sections = vec![
+            Section::board("grid", &board),
+            Section::new("agent")
+                .field("pos", "(0, 1)")
+                .field("facing", "N"),
+        ];
+
+        let expected = "\
+grid   agent
+----   --------------
+#.        pos: (0, 1)
+.#     facing: N
+";
+
+        assert_eq!(render_sections(&sections), expected);
+    }
+
+    #[test]
+    fn test_wraps_wide_sections() {
+        let wide = "x".repeat(MAX_WIDTH - 10);
+        let sections = vec![Section::new("a").line(&wide), Section::new("b").line(&wide)];
+
+        let rendered = render_sections(&sections);
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "a");
+        assert_eq!(lines[4], "b");
+    }
+}
@@ -0,0 +1,364 @@
+use crate::config::config;
+use crate::grid_2d::{Board, Coord};
+use std::collections::VecDeque;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Shortest path distances between every pair of a set of labelled points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix<L> {
+    pub labels: Vec<L>,
+    /// `dist[i][j]` is the distance from `labels[i]` to `labels[j]`, or None if unreachable
+    pub dist: Vec<Vec<Option<u32>>>,
+}
+
+impl<L> DistanceMatrix<L>
+where
+    L: PartialEq,
+{
+    pub fn index_of(&self, label: &L) -> Option<usize> {
+        self.labels.iter().position(|l| l == label)
+    }
+
+    /// Distance between two labelled points, or None if either label is unknown or there is
+    /// no path between them
+    pub fn get(&self, from: &L, to: &L) -> Option<u32> {
+        self.dist[self.index_of(from)?][self.index_of(to)?]
+    }
+}
+
+/// Panic if `c` isn't on the board, rather than indexing out of bounds or wrapping a negative
+/// coordinate around
+fn check_on_board<T: Clone>(board: &Board<T>, c: Coord) {
+    assert!(board.get(&c).is_some(), "{:?} is outside the board", c);
+}
+
+/// Distances from `start` to every reachable cell, moving in the cardinal directions
+/// through cells where `passable` is true
+fn bfs_from<T, P>(board: &Board<T>, start: Coord, passable: &P) -> Vec<Vec<Option<u32>>>
+where
+    T: Clone,
+    P: Fn(&T) -> bool,
+{
+    let mut dist: Vec<Vec<Option<u32>>> = board
+        .matrix
+        .iter()
+        .map(|row| vec![None; row.len()])
+        .collect();
+
+    check_on_board(board, start);
+    let mut queue = VecDeque::from([(start, 0)]);
+    dist[start.0 as usize][start.1 as usize] = Some(0);
+
+    while let Some((pos, d)) = queue.pop_front() {
+        for (next, cell) in board.cardinal_neighbours(&pos) {
+            if !passable(cell) {
+                continue;
+            }
+
+            let seen = &mut dist[next.0 as usize][next.1 as usize];
+            if seen.is_none() {
+                *seen = Some(d + 1);
+                queue.push_back((next, d + 1));
+            }
+        }
+    }
+
+    dist
+}
+
+/// Compute the BFS distance between every pair of labelled points on the board, moving in
+/// the cardinal directions through cells where `passable` is true.
+///
+/// # Panics
+/// Panics if any of the points are outside the board
+///
+/// # Examples
+/// ```
+/// use aoc::distances::all_pairs;
+/// use aoc::grid_2d::{Board, Coord};
+///
+/// let board = Board::from_str(
+///     "#####\n\
+///      #0.1#\n\
+///      #.#.#\n\
+///      #2..#\n\
+///      #####",
+/// );
+/// let points: Vec<(char, Coord)> = ['0', '1', '2']
+///     .into_iter()
+///     .map(|c| (c, board.find(&c)[0]))
+///     .collect();
+///
+/// let distances = all_pairs(&board, &points, |c| *c != '#');
+/// assert_eq!(distances.get(&'0', &'1'), Some(2));
+/// assert_eq!(distances.get(&'1', &'2'), Some(4));
+/// ```
+pub fn all_pairs<T, L, P>(board: &Board<T>, points: &[(L, Coord)], passable: P) -> DistanceMatrix<L>
+where
+    T: Clone,
+    L: Clone,
+    P: Fn(&T) -> bool,
+{
+    for &(_, c) in points {
+        check_on_board(board, c);
+    }
+
+    let dist = points
+        .iter()
+        .map(|(_, from)| {
+            let from_here = bfs_from(board, *from, &passable);
+            points
+                .iter()
+                .map(|(_, to)| from_here[to.0 as usize][to.1 as usize])
+                .collect()
+        })
+        .collect();
+
+    DistanceMatrix {
+        labels: points.iter().map(|(l, _)| l.clone()).collect(),
+        dist,
+    }
+}
+
+/// BFS distance from `start` to every cell of the board, or None for cells that can't be
+/// reached, moving in the cardinal directions through cells where `passable` is true
+///
+/// # Panics
+/// Panics if `start` is outside the board
+pub fn distance_field<T, P>(board: &Board<T>, start: Coord, passable: P) -> Board<Option<u32>>
+where
+    T: Clone,
+    P: Fn(&T) -> bool,
+{
+    Board::new(bfs_from(board, start, &passable))
+}
+
+/// Number of shortcuts through a maze that save at least `min_saving` steps, where a shortcut
+/// jumps between two reachable cells at most `max_skip` steps apart, ignoring walls (the race
+/// condition of 2024 day 20). `dist` is the distance of every cell along the maze's single path,
+/// as given by [`distance_field`].
+///
+/// # Examples
+/// ```
+/// use aoc::distances::{cheats, distance_field};
+/// use aoc::grid_2d::{Board, Coord};
+///
+/// let board = Board::from_str("S#.\n.#.\n...");
+/// let dist = distance_field(&board, Coord(0, 0), |&c| c != '#');
+///
+/// // Through the wall from the start to the top right saves 4 steps
+/// assert_eq!(cheats(&dist, 2, 4), 1);
+/// assert_eq!(cheats(&dist, 2, 2), 2);
+/// ```
+pub fn cheats(dist: &Board<Option<u32>>, max_skip: u32, min_saving: u32) -> usize {
+    dist.enumerate_coords()
+        .filter_map(|(c, d)| Some((c, (*d)?)))
+        .map(|(from, start)| {
+            // Moving a single step is never a shortcut
+            (2..=max_skip)
+                .flat_map(|skip| from.manhattan_ring(skip).map(move |to| (to, skip)))
+                .filter(|(to, skip)| {
+                    dist.get(to)
+                        .copied()
+                        .flatten()
+                        .is_some_and(|end| end >= start + skip + min_saving)
+                })
+                .count()
+        })
+        .sum()
+}
+
+/// 64 bit FNV-1a. Unlike `DefaultHasher` its output is stable between runs and Rust
+/// versions, so it can be used for cache keys.
+struct Fnv64(u64);
+
+impl Hasher for Fnv64 {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Directory used for cached distances, the configured `cache_dir` (see [`crate::config`])
+pub fn default_cache_dir() -> PathBuf {
+    config().cache_dir.clone()
+}
+
+fn serialize(dist: &[Vec<Option<u32>>]) -> String {
+    dist.iter()
+        .map(|row| {
+            let row: Vec<String> = row
+                .iter()
+                .map(|d| d.map_or("-".to_string(), |d| d.to_string()))
+                .collect();
+            row.join(" ") + "\n"
+        })
+        .collect()
+}
+
+fn deserialize(s: &str, n: usize) -> Option<Vec<Vec<Option<u32>>>> {
+    let dist: Vec<Vec<Option<u32>>> = s
+        .lines()
+        .map(|line| {
+            line.split(' ')
+                .map(|d| match d {
+                    "-" => Some(None),
+                    d => d.parse().ok().map(Some),
+                })
+                .collect::<Option<Vec<_>>>()
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    (dist.len() == n && dist.iter().all(|row| row.len() == n)).then_some(dist)
+}
+
+/// Like [`all_pairs`], but caches the result on disk in `cache_dir`, keyed by a hash of the
+/// puzzle input and the points. Subsequent runs on the same input load the matrix instead of
+/// recomputing it.
+///
+/// The `passable` closure can't be part of the key, so clear the cache after changing it.
+///
+/// # Panics
+/// Panics if any of the points are outside the board, whether or not they're cached
+pub fn all_pairs_cached<T, L, P>(
+    input: &str,
+    board: &Board<T>,
+    points: &[(L, Coord)],
+    passable: P,
+    cache_dir: &Path,
+) -> DistanceMatrix<L>
+where
+    T: Clone,
+    L: Clone + Hash,
+    P: Fn(&T) -> bool,
+{
+    for &(_, c) in points {
+        check_on_board(board, c);
+    }
+
+    let mut hasher = Fnv64(0xcbf29ce484222325);
+    input.hash(&mut hasher);
+    for (label, coord) in points {
+        label.hash(&mut hasher);
+        coord.hash(&mut hasher);
+    }
+    let path = cache_dir.join(format!("distances-{:016x}.txt", hasher.finish()));
+
+    if let Some(dist) = fs::read_to_string(&path)
+        .ok()
+        .and_then(|s| deserialize(&s, points.len()))
+    {
+        return DistanceMatrix {
+            labels: points.iter().map(|(l, _)| l.clone()).collect(),
+            dist,
+        };
+    }
+
+    let distances = all_pairs(board, points, passable);
+
+    // Failing to cache shouldn't fail the solution
+    let _ =
+        fs::create_dir_all(cache_dir).and_then(|_| fs::write(&path, serialize(&distances.dist)));
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+#######
+#0..#.#
+#.#.#2#
+#1..#.#
+#######";
+
+    fn points(board: &Board<char>) -> Vec<(char, Coord)> {
+        ['0', '1', '2']
+            .into_iter()
+            .map(|c| (c, board.find(&c)[0]))
+            .collect()
+    }
+
+    #[test]
+    fn test_cheats() {
+        // 2024 day 20
+        let input = "\
+###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+        let board = Board::from_str(input);
+        let dist = distance_field(&board, board.find(&'S')[0], |&c| c != '#');
+        assert_eq!(dist[board.find(&'E')[0]], Some(84));
+
+        assert_eq!(cheats(&dist, 2, 20), 5);
+        assert_eq!(cheats(&dist, 2, 64), 1);
+        assert_eq!(cheats(&dist, 20, 50), 285);
+        assert_eq!(cheats(&dist, 20, 76), 3);
+    }
+
+    #[test]
+    fn test_all_pairs() {
+        let board = Board::from_str(MAZE);
+        let distances = all_pairs(&board, &points(&board), |c| *c != '#');
+
+        assert_eq!(distances.get(&'0', &'0'), Some(0));
+        assert_eq!(distances.get(&'0', &'1'), Some(2));
+        assert_eq!(distances.get(&'1', &'0'), Some(2));
+        assert_eq!(distances.get(&'0', &'2'), None);
+        assert_eq!(distances.get(&'0', &'9'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Coord(-1, 0) is outside the board")]
+    fn test_start_outside_board() {
+        let board = Board::from_str(MAZE);
+        distance_field(&board, Coord(-1, 0), |c| *c != '#');
+    }
+
+    #[test]
+    #[should_panic(expected = "Coord(1, 7) is outside the board")]
+    fn test_point_outside_board() {
+        let board = Board::from_str(MAZE);
+        let mut points = points(&board);
+        points.push(('x', Coord(1, 7)));
+        all_pairs(&board, &points, |c| *c != '#');
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc-distances-test-{}", std::process::id()));
+        let board = Board::from_str(MAZE);
+
+        let computed = all_pairs_cached(MAZE, &board, &points(&board), |c| *c != '#', &dir);
+        // A passable function that would give different results shows the cache is used
+        let cached = all_pairs_cached(MAZE, &board, &points(&board), |_| true, &dir);
+
+        assert_eq!(computed, cached);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
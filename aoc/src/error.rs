@@ -0,0 +1,148 @@
+use std::fmt::{self, Debug, Display};
+
+/// An error from a solution, carrying a message and the context it was raised in. Any
+/// standard error converts into this with `?`, and [`Context`] adds to it on the way up, so
+/// that failures read like `day 1 part 2: failed to parse line 37: "X12"`.
+pub struct Error {
+    message: String,
+    /// Innermost first
+    context: Vec<String>,
+}
+
+/// A result defaulting to [`Error`], for solutions that can fail
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    pub fn msg(message: impl Display) -> Self {
+        Self {
+            message: message.to_string(),
+            context: Vec::new(),
+        }
+    }
+
+    /// Wrap the error in another layer of context
+    pub fn context(mut self, context: impl Display) -> Self {
+        self.context.push(context.to_string());
+        self
+    }
+
+    /// The original message, without any context
+    pub fn root_cause(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in self.context.iter().rev() {
+            write!(f, "{}: ", context)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+// Errors mostly end up in `unwrap` and test failures, where the message is what matters
+impl Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+// `Error` deliberately doesn't implement `std::error::Error`, which would conflict with this
+impl<E: std::error::Error> From<E> for Error {
+    fn from(error: E) -> Self {
+        Self::msg(error)
+    }
+}
+
+/// Attach context to errors and missing values, converting them into an [`Error`]
+///
+/// # Examples
+/// ```
+/// use aoc::error::Context;
+///
+/// fn parse(line: &str) -> aoc::Result<u32> {
+///     let (_, num) = line.split_once(':').context("missing colon")?;
+///     num.trim().parse::<u32>().with_context(|| format!("bad number in {:?}", line))
+/// }
+///
+/// assert_eq!(parse("a: 12").unwrap(), 12);
+/// assert_eq!(parse("a 12").unwrap_err().to_string(), "missing colon");
+/// assert_eq!(
+///     parse("a: x").unwrap_err().to_string(),
+///     "bad number in \"a: x\": invalid digit found in string"
+/// );
+/// ```
+pub trait Context<T> {
+    fn context(self, context: impl Display) -> Result<T>;
+
+    /// Like [`Context::context`], only building the context when there's an error
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Display) -> Result<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
+
+impl<T> Context<T> for Option<T> {
+    fn context(self, context: impl Display) -> Result<T> {
+        self.ok_or_else(|| Error::msg(context))
+    }
+
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.ok_or_else(|| Error::msg(context()))
+    }
+}
+
+/// Return early with an [`Error`] built from a format string
+///
+/// ```
+/// fn check(n: i64) -> aoc::Result<i64> {
+///     if n < 0 {
+///         aoc::bail!("expected a positive number, got {}", n);
+///     }
+///     Ok(n)
+/// }
+///
+/// assert_eq!(check(-2).unwrap_err().to_string(), "expected a positive number, got -2");
+/// ```
+#[macro_export]
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err($crate::error::Error::msg(format!($($arg)*)))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_chain() {
+        let err: Result<u32> = "x".parse::<u32>().context("line 3").context("day 1 part 2");
+
+        let err = err.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 1 part 2: line 3: invalid digit found in string"
+        );
+        assert_eq!(err.root_cause(), "invalid digit found in string");
+        assert_eq!(format!("{:?}", err), err.to_string());
+    }
+
+    #[test]
+    fn test_question_mark() {
+        fn parse(s: &str) -> Result<i64> {
+            Ok(s.parse::<i64>()? * 2)
+        }
+
+        assert_eq!(parse("21").unwrap(), 42);
+        assert!(parse("").is_err());
+    }
+}
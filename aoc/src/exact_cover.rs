@@ -0,0 +1,313 @@
+//! Exact cover solver using Knuth's Algorithm X with dancing links.
+//!
+//! An exact cover problem is a set of columns (constraints) and a set of rows (choices) which
+//! each cover some of the columns. A solution is a set of rows that covers every primary column
+//! exactly once, and every secondary column at most once.
+//!
+//! Tiling puzzles map onto this with one primary column per cell that must be filled (or per
+//! piece that must be placed), and one row per possible placement of a piece. Cells that may be
+//! left empty become secondary columns.
+//!
+//! # Examples
+//! ```
+//! use aoc::exact_cover::ExactCover;
+//!
+//! // Cover columns 0..4 using pairs
+//! let mut problem = ExactCover::new(4, 0);
+//! let a = problem.add_row(&[0, 1]);
+//! let _ = problem.add_row(&[1, 2]);
+//! let b = problem.add_row(&[2, 3]);
+//!
+//! let mut solution = problem.solve().unwrap();
+//! solution.sort();
+//! assert_eq!(solution, vec![a, b]);
+//! ```
+
+/// Builder for an exact cover matrix. Columns `0..primary` must be covered exactly once, and
+/// columns `primary..primary + secondary` at most once.
+#[derive(Debug, Clone, Default)]
+pub struct ExactCover {
+    primary: usize,
+    secondary: usize,
+    rows: Vec<Vec<usize>>,
+}
+
+impl ExactCover {
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        Self {
+            primary,
+            secondary,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.primary + self.secondary
+    }
+
+    /// Add a row covering the given columns, returning its index for identifying it in
+    /// solutions
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        for (i, &col) in columns.iter().enumerate() {
+            assert!(
+                col < self.columns(),
+                "column {} out of range for {} columns",
+                col,
+                self.columns()
+            );
+            debug_assert!(!columns[..i].contains(&col), "duplicate column {}", col);
+        }
+
+        self.rows.push(columns.to_vec());
+        self.rows.len() - 1
+    }
+
+    pub fn rows(&self) -> &[Vec<usize>] {
+        &self.rows
+    }
+
+    /// Find any one solution, as a list of row indices
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        self.solutions(1).pop()
+    }
+
+    /// Find up to `limit` solutions
+    pub fn solutions(&self, limit: usize) -> Vec<Vec<usize>> {
+        let mut solutions = Vec::new();
+        if limit == 0 {
+            return solutions;
+        }
+
+        Dlx::new(self).search(&mut Vec::new(), &mut |solution| {
+            solutions.push(solution.to_vec());
+            solutions.len() < limit
+        });
+
+        solutions
+    }
+
+    /// Count every solution
+    pub fn count(&self) -> usize {
+        let mut count = 0;
+        Dlx::new(self).search(&mut Vec::new(), &mut |_| {
+            count += 1;
+            true
+        });
+        count
+    }
+}
+
+/// The dancing links structure. Node 0 is the root, nodes `1..=columns` are the column
+/// headers, and the rest are the 1s in the matrix. Links are indices into the arrays.
+struct Dlx {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Column header of each node
+    column: Vec<usize>,
+    /// Row index of each non-header node
+    row: Vec<usize>,
+    /// Number of nodes in each column, indexed by header node
+    size: Vec<usize>,
+}
+
+impl Dlx {
+    fn new(problem: &ExactCover) -> Self {
+        let headers = problem.columns() + 1;
+        let mut dlx = Self {
+            left: Vec::with_capacity(headers),
+            right: Vec::with_capacity(headers),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+        };
+
+        // Only the root and primary columns are linked into the header list, so the search is
+        // done once they're all covered. Secondary headers link to themselves.
+        for i in 0..headers {
+            if i <= problem.primary {
+                dlx.left.push(if i == 0 { problem.primary } else { i - 1 });
+                dlx.right.push(if i == problem.primary { 0 } else { i + 1 });
+            } else {
+                dlx.left.push(i);
+                dlx.right.push(i);
+            }
+        }
+
+        for (row, columns) in problem.rows.iter().enumerate() {
+            let first = dlx.column.len();
+
+            for (i, &col) in columns.iter().enumerate() {
+                let header = col + 1;
+                let node = dlx.column.len();
+
+                dlx.column.push(header);
+                dlx.row.push(row);
+
+                // Insert at the bottom of the column
+                dlx.up.push(dlx.up[header]);
+                dlx.down.push(header);
+                let above = dlx.up[header];
+                dlx.down[above] = node;
+                dlx.up[header] = node;
+                dlx.size[header] += 1;
+
+                // Insert at the end of the row, which is circular
+                dlx.left.push(if i == 0 { node } else { node - 1 });
+                dlx.right.push(first);
+                let prev = dlx.left[node];
+                dlx.right[prev] = node;
+                dlx.left[first] = node;
+            }
+        }
+
+        dlx
+    }
+
+    fn cover(&mut self, col: usize) {
+        let (l, r) = (self.left[col], self.right[col]);
+        self.right[l] = r;
+        self.left[r] = l;
+
+        let mut i = self.down[col];
+        while i != col {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, col: usize) {
+        let mut i = self.up[col];
+        while i != col {
+            let mut j = self.left[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                self.size[self.column[j]] += 1;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        let (l, r) = (self.left[col], self.right[col]);
+        self.right[l] = col;
+        self.left[r] = col;
+    }
+
+    /// Depth-first search for solutions, calling `found` with each one. Returns false if
+    /// `found` asked to stop.
+    fn search<F: FnMut(&[usize]) -> bool>(
+        &mut self,
+        partial: &mut Vec<usize>,
+        found: &mut F,
+    ) -> bool {
+        if self.right[0] == 0 {
+            return found(partial);
+        }
+
+        // Branch on the column with the fewest options
+        let mut col = self.right[0];
+        let mut c = self.right[col];
+        while c != 0 {
+            if self.size[c] < self.size[col] {
+                col = c;
+            }
+            c = self.right[c];
+        }
+
+        if self.size[col] == 0 {
+            return true;
+        }
+
+        self.cover(col);
+
+        let mut keep_going = true;
+        let mut r = self.down[col];
+        while r != col && keep_going {
+            partial.push(self.row[r]);
+
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+
+            keep_going = self.search(partial, found);
+
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+
+            partial.pop();
+            r = self.down[r];
+        }
+
+        self.uncover(col);
+        keep_going
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_knuth_example() {
+        // The example from Knuth's Dancing Links paper, with columns A-G as 0-6
+        let mut problem = ExactCover::new(7, 0);
+        problem.add_row(&[2, 4, 5]);
+        problem.add_row(&[0, 3, 6]);
+        problem.add_row(&[1, 2, 5]);
+        problem.add_row(&[0, 3]);
+        problem.add_row(&[1, 6]);
+        problem.add_row(&[3, 4, 6]);
+
+        let mut solution = problem.solve().unwrap();
+        solution.sort();
+
+        assert_eq!(solution, vec![0, 3, 4]);
+        assert_eq!(problem.count(), 1);
+    }
+
+    #[test]
+    fn test_no_solution() {
+        let mut problem = ExactCover::new(3, 0);
+        problem.add_row(&[0, 1]);
+        problem.add_row(&[1, 2]);
+
+        assert_eq!(problem.solve(), None);
+        assert_eq!(problem.count(), 0);
+    }
+
+    #[test]
+    fn test_n_queens() {
+        // Ranks and files are primary, diagonals are secondary since not all are used
+        let n = 8;
+        let diagonals = 2 * n - 1;
+        let mut problem = ExactCover::new(2 * n, 2 * diagonals);
+
+        for rank in 0..n {
+            for file in 0..n {
+                let diagonal = 2 * n + rank + file;
+                let anti_diagonal = 2 * n + diagonals + rank + (n - 1 - file);
+                problem.add_row(&[rank, n + file, diagonal, anti_diagonal]);
+            }
+        }
+
+        assert_eq!(problem.count(), 92);
+        assert_eq!(problem.solutions(10).len(), 10);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::grid_2d::{Board, Coord};
+
+/// Height of every glyph in the block font
+pub const GLYPH_HEIGHT: usize = 6;
+
+/// Blank columns between adjacent glyphs
+const SPACING: usize = 1;
+
+/// The 6-pixel-tall block font that AoC puzzles draw their answers in. Not every letter
+/// has appeared in a puzzle, so only these are known.
+const GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 19] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+    (' ', ["....", "....", "....", "....", "....", "...."]),
+];
+
+/// Get the rows of the glyph for a character, if it's in the font
+pub fn glyph(c: char) -> Option<&'static [&'static str; GLYPH_HEIGHT]> {
+    GLYPHS
+        .iter()
+        .find(|(g, _)| *g == c.to_ascii_uppercase())
+        .map(|(_, rows)| rows)
+}
+
+/// Width in cells of `text` when rendered, including the spacing between glyphs
+///
+/// # Panics
+/// Panics if the text contains a character that isn't in the font
+pub fn text_width(text: &str) -> usize {
+    let glyphs = text.chars().map(glyph_or_panic);
+    let width: usize = glyphs.map(|g| g[0].len() + SPACING).sum();
+
+    width.saturating_sub(SPACING)
+}
+
+fn glyph_or_panic(c: char) -> &'static [&'static str; GLYPH_HEIGHT] {
+    glyph(c).unwrap_or_else(|| panic!("Character {:?} is not in the font", c))
+}
+
+/// Render text in the block font as a new board of `#` and `.`
+///
+/// # Examples
+/// ```
+/// use aoc::font::render;
+///
+/// let board = render("HI");
+/// assert_eq!(board.size(), (6, 9));
+/// assert_eq!(board.matrix[2].iter().collect::<String>(), "####...#.");
+/// ```
+///
+/// # Panics
+/// Panics if the text contains a character that isn't in the font
+pub fn render(text: &str) -> Board<char> {
+    let mut board = Board::from_size((GLYPH_HEIGHT, text_width(text)), '.');
+    draw(&mut board, text, Coord(0, 0), '#');
+    board
+}
+
+/// Draw text in the block font onto an existing board with its top left corner at `origin`,
+/// setting lit pixels to `lit` and leaving the rest untouched. Pixels that fall outside of
+/// the board are skipped.
+///
+/// # Panics
+/// Panics if the text contains a character that isn't in the font
+pub fn draw<T>(board: &mut Board<T>, text: &str, origin: Coord, lit: T)
+where
+    T: Clone,
+{
+    let mut col = origin.1;
+    for c in text.chars() {
+        let glyph = glyph_or_panic(c);
+
+        for (i, row) in glyph.iter().enumerate() {
+            for (j, pixel) in row.chars().enumerate() {
+                let pos = Coord(origin.0 + i as i32, col + j as i32);
+                if pixel == '#' && board.get(&pos).is_some() {
+                    board.set(&pos, lit.clone());
+                }
+            }
+        }
+
+        col += (glyph[0].len() + SPACING) as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let board = render("Ez");
+        let rendered: Vec<String> = board.matrix.iter().map(|r| r.iter().collect()).collect();
+
+        assert_eq!(
+            rendered,
+            vec![
+                "####.####",
+                "#.......#",
+                "###....#.",
+                "#.....#..",
+                "#....#...",
+                "####.####",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_draw_clips_to_board() {
+        let mut board = Board::from_size((6, 6), false);
+        draw(&mut board, "L", Coord(0, 3), true);
+
+        assert_eq!(board.get(&Coord(0, 3)), Some(&true));
+        assert_eq!(board.get(&Coord(5, 5)), Some(&true));
+        assert_eq!(board.get(&Coord(0, 4)), Some(&false));
+        // The last column of the bottom bar falls off the board
+        assert_eq!(board.find(&true).len(), 8);
+    }
+
+    #[test]
+    fn test_all_glyphs_are_rectangular() {
+        for (c, rows) in GLYPHS.iter() {
+            assert!(rows.iter().all(|r| r.len() == rows[0].len()), "{}", c);
+        }
+    }
+}
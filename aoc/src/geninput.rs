@@ -0,0 +1,67 @@
+use crate::rng::Rng;
+
+/// A random input generator for a day, for stress testing and benchmarking on inputs much
+/// larger than the real one. Generated inputs should be valid puzzle inputs, but don't need a
+/// unique or even sensible answer.
+///
+/// # Examples
+/// ```
+/// use aoc::geninput::GenInput;
+/// use aoc::rng::Rng;
+///
+/// struct Numbers;
+///
+/// impl GenInput for Numbers {
+///     fn generate(size: usize, rng: &mut Rng) -> String {
+///         (0..size).map(|_| format!("{}\n", rng.range(1, 99))).collect()
+///     }
+/// }
+///
+/// assert_eq!(Numbers::generate(3, &mut Rng::new(1)).lines().count(), 3);
+/// ```
+pub trait GenInput {
+    /// Generate an input with `size` units of whatever makes sense for the day, eg. lines
+    fn generate(size: usize, rng: &mut Rng) -> String;
+}
+
+/// A day's registered input generator
+#[derive(Debug, Clone, Copy)]
+pub struct Generator {
+    pub year: u32,
+    pub day: u32,
+    pub generate: fn(usize, &mut Rng) -> String,
+}
+
+inventory::collect!(Generator);
+
+/// Register a day's [`GenInput`] so that `aoc-cli geninput` can find it. Called from the day's
+/// `lib.rs`, like [`register_solution!`](crate::register_solution). The year defaults to
+/// [`MAIN_YEAR`](crate::runner::MAIN_YEAR).
+///
+/// ```ignore
+/// pub mod geninput;
+///
+/// aoc::register_generator!(day = 1, geninput::Rotations);
+/// ```
+#[macro_export]
+macro_rules! register_generator {
+    (day = $day:literal, $generator:ty) => {
+        $crate::register_generator!(year = $crate::runner::MAIN_YEAR, day = $day, $generator);
+    };
+    (year = $year:expr, day = $day:literal, $generator:ty) => {
+        $crate::inventory::submit! {
+            $crate::geninput::Generator {
+                year: $year,
+                day: $day,
+                generate: <$generator as $crate::geninput::GenInput>::generate,
+            }
+        }
+    };
+}
+
+/// The registered generator for a day, if it has one
+pub fn generator(year: u32, day: u32) -> Option<&'static Generator> {
+    inventory::iter::<Generator>
+        .into_iter()
+        .find(|g| g.year == year && g.day == day)
+}
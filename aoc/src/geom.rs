@@ -0,0 +1,929 @@
+use crate::grid_2d::{Coord, Orientation};
+use crate::grid_3d::Coord3;
+use crate::rng::Rng;
+use num::rational::Ratio;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Column range `[from, to]` of cells on a row
+type Span = (i32, i32);
+
+/// Merge overlapping or touching spans
+fn merge_spans(mut spans: Vec<Span>) -> Vec<Span> {
+    spans.sort();
+
+    let mut merged: Vec<Span> = Vec::with_capacity(spans.len());
+    for (from, to) in spans {
+        match merged.last_mut() {
+            Some(last) if from <= last.1 + 1 => last.1 = last.1.max(to),
+            _ => merged.push((from, to)),
+        }
+    }
+
+    merged
+}
+
+/// Cells of a single row that are inside or on the boundary of the polygon
+fn row_spans(vertices: &[Coord], row: i32) -> Vec<Span> {
+    let edges = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .take(vertices.len());
+
+    let mut crossings = Vec::new();
+    let mut spans = Vec::new();
+
+    for (a, b) in edges {
+        let (top, bottom) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+        if row < top.0 || row > bottom.0 {
+            continue;
+        }
+
+        if top.0 == bottom.0 {
+            // Horizontal edges are boundary cells, but don't cross the scanline
+            spans.push((top.1.min(bottom.1), top.1.max(bottom.1)));
+            continue;
+        }
+
+        // Exact column of the edge on this row, as a fraction
+        let num = (top.1 as i64) * (bottom.0 - top.0) as i64
+            + (row - top.0) as i64 * (bottom.1 - top.1) as i64;
+        let den = (bottom.0 - top.0) as i64;
+
+        // The edge itself is boundary if it passes through the middle of a cell
+        if num % den == 0 {
+            let col = (num / den) as i32;
+            spans.push((col, col));
+        }
+
+        // Half-open, so a vertex shared by two edges is only counted as a crossing once
+        if row < bottom.0 {
+            crossings.push((num, den));
+        }
+    }
+
+    crossings.sort_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)));
+    for pair in crossings.chunks_exact(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let from = from.0.div_euclid(from.1) + (from.0.rem_euclid(from.1) != 0) as i64;
+        let to = to.0.div_euclid(to.1);
+        if from <= to {
+            spans.push((from as i32, to as i32));
+        }
+    }
+
+    merge_spans(spans)
+}
+
+/// Rasterize a polygon, yielding every cell that is inside it or on its boundary, row by row.
+/// The polygon doesn't have to be convex, and its edges don't have to be axis aligned.
+///
+/// This visits every cell, so it's only suitable when the coordinates are small. To just count
+/// the cells of a large polygon, use the shoelace formula with Pick's theorem instead.
+///
+/// # Examples
+/// ```
+/// use aoc::geom::fill_polygon;
+/// use aoc::grid_2d::Coord;
+///
+/// // A right triangle with legs of length 2
+/// let cells: Vec<Coord> = fill_polygon(&[Coord(0, 0), Coord(2, 0), Coord(2, 2)]).collect();
+///
+/// assert_eq!(
+///     cells,
+///     vec![
+///         Coord(0, 0),
+///         Coord(1, 0),
+///         Coord(1, 1),
+///         Coord(2, 0),
+///         Coord(2, 1),
+///         Coord(2, 2)
+///     ]
+/// );
+/// ```
+pub fn fill_polygon(vertices: &[Coord]) -> impl Iterator<Item = Coord> + '_ {
+    let min_row = vertices.iter().map(|v| v.0).min().unwrap_or(0);
+    let max_row = vertices.iter().map(|v| v.0).max().unwrap_or(-1);
+
+    (min_row..=max_row).flat_map(move |row| {
+        row_spans(vertices, row)
+            .into_iter()
+            .flat_map(move |(from, to)| (from..=to).map(move |col| Coord(row, col)))
+    })
+}
+
+/// A point with integer coordinates in up to 4 dimensions, for the spatial algorithms below
+pub trait Point: Copy {
+    /// Coordinates along each axis, with unused axes 0
+    fn axes(&self) -> [i64; 4];
+}
+
+impl Point for Coord {
+    fn axes(&self) -> [i64; 4] {
+        [self.0 as i64, self.1 as i64, 0, 0]
+    }
+}
+
+impl Point for Coord3 {
+    fn axes(&self) -> [i64; 4] {
+        [self.0 as i64, self.1 as i64, self.2 as i64, 0]
+    }
+}
+
+impl Point for [i64; 4] {
+    fn axes(&self) -> [i64; 4] {
+        *self
+    }
+}
+
+/// How to measure the distance between two points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Manhattan,
+    /// The largest difference along any one axis, ie. king moves
+    Chebyshev,
+    /// Squared euclidean distance, which stays an integer
+    EuclideanSquared,
+}
+
+impl Metric {
+    pub fn distance<P: Point>(&self, a: &P, b: &P) -> i64 {
+        let diffs = a
+            .axes()
+            .into_iter()
+            .zip(b.axes())
+            .map(|(a, b)| (a - b).abs());
+
+        match self {
+            Metric::Manhattan => diffs.sum(),
+            Metric::Chebyshev => diffs.max().unwrap(),
+            Metric::EuclideanSquared => diffs.map(|d| d * d).sum(),
+        }
+    }
+
+    /// The largest difference along one axis that two points within `distance` of each other
+    /// can have
+    fn max_axis_diff(&self, distance: i64) -> i64 {
+        match self {
+            Metric::Manhattan | Metric::Chebyshev => distance,
+            Metric::EuclideanSquared => (distance as f64).sqrt().ceil() as i64,
+        }
+    }
+}
+
+/// Spatial hash of points into cubes of a fixed size. Points within the cube size of each
+/// other along every axis are always in the same or adjacent cubes.
+struct SpatialHash {
+    size: i64,
+    cells: HashMap<[i64; 4], Vec<usize>>,
+}
+
+impl SpatialHash {
+    fn new(size: i64) -> Self {
+        Self {
+            size: size.max(1),
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell<P: Point>(&self, p: &P) -> [i64; 4] {
+        p.axes().map(|a| a.div_euclid(self.size))
+    }
+
+    fn insert<P: Point>(&mut self, p: &P, index: usize) {
+        self.cells.entry(self.cell(p)).or_default().push(index);
+    }
+
+    /// Indices of points in the cube containing `p` and all adjacent cubes
+    fn nearby<P: Point>(&self, p: &P) -> impl Iterator<Item = usize> + '_ {
+        let cell = self.cell(p);
+
+        // Every combination of -1, 0, 1 on each axis, 3^4 in total
+        (0..81).flat_map(move |mut n| {
+            let mut neighbour = cell;
+            for axis in neighbour.iter_mut() {
+                *axis += n % 3 - 1;
+                n /= 3;
+            }
+            self.cells.get(&neighbour).into_iter().flatten().copied()
+        })
+    }
+}
+
+/// The two closest points, and the distance between them. Returns None if there are fewer
+/// than 2 points.
+///
+/// Uses randomized incremental construction over a spatial hash, which is expected linear time.
+/// The order is shuffled with a fixed seed so results are reproducible.
+///
+/// # Examples
+/// ```
+/// use aoc::geom::{closest_pair, Metric};
+/// use aoc::grid_2d::Coord;
+///
+/// let points = [Coord(0, 0), Coord(10, 10), Coord(3, 4), Coord(11, 12)];
+/// assert_eq!(
+///     closest_pair(&points, Metric::EuclideanSquared),
+///     Some((1, 3, 5))
+/// );
+/// ```
+pub fn closest_pair<P: Point>(points: &[P], metric: Metric) -> Option<(usize, usize, i64)> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    Rng::new(0x5eed).shuffle(&mut order);
+
+    let (a, b) = (order[0], order[1]);
+    let mut best = (a.min(b), a.max(b), metric.distance(&points[a], &points[b]));
+
+    let mut grid = SpatialHash::new(metric.max_axis_diff(best.2));
+    grid.insert(&points[a], a);
+    grid.insert(&points[b], b);
+
+    for i in 2..order.len() {
+        if best.2 == 0 {
+            break;
+        }
+
+        let p = order[i];
+        let closer = grid
+            .nearby(&points[p])
+            .map(|q| (q, metric.distance(&points[p], &points[q])))
+            .filter(|(_, d)| *d < best.2)
+            .min_by_key(|(_, d)| *d);
+
+        match closer {
+            // The cubes are now too big, rebuild with the new distance
+            Some((q, d)) => {
+                best = (p.min(q), p.max(q), d);
+                grid = SpatialHash::new(metric.max_axis_diff(d));
+                for &r in &order[..=i] {
+                    grid.insert(&points[r], r);
+                }
+            }
+            None => grid.insert(&points[p], p),
+        }
+    }
+
+    Some(best)
+}
+
+/// Group points into clusters by single linkage: two points are in the same cluster if there's
+/// a chain of points between them, each within `distance` of the next. Returns the indices of
+/// the points in each cluster.
+///
+/// # Examples
+/// ```
+/// use aoc::geom::{clusters, Metric};
+/// use aoc::grid_3d::Coord3;
+///
+/// let points = [Coord3(0, 0, 0), Coord3(0, 0, 3), Coord3(0, 0, 6), Coord3(0, 0, 10)];
+/// assert_eq!(
+///     clusters(&points, Metric::Manhattan, 3),
+///     vec![vec![0, 1, 2], vec![3]]
+/// );
+/// ```
+pub fn clusters<P: Point>(points: &[P], metric: Metric, distance: i64) -> Vec<Vec<usize>> {
+    let mut grid = SpatialHash::new(metric.max_axis_diff(distance));
+    for (i, p) in points.iter().enumerate() {
+        grid.insert(p, i);
+    }
+
+    // Union-find, always keeping the smaller index as the root
+    let mut parent: Vec<usize> = (0..points.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for (i, p) in points.iter().enumerate() {
+        for j in grid.nearby(p) {
+            if j > i && metric.distance(p, &points[j]) <= distance {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    let mut cluster_of_root = HashMap::new();
+    for i in 0..points.len() {
+        let r = root(&mut parent, i);
+        let cluster = *cluster_of_root.entry(r).or_insert_with(|| {
+            clusters.push(Vec::new());
+            clusters.len() - 1
+        });
+        clusters[cluster].push(i);
+    }
+
+    clusters
+}
+
+/// An axis-aligned box of cells, from `min` to `max` inclusive on every axis
+///
+/// # Examples
+/// ```
+/// use aoc::geom::Cuboid;
+/// use aoc::grid_3d::Coord3;
+///
+/// let a = Cuboid::new(Coord3(0, 0, 0), Coord3(2, 2, 2));
+/// let b = Cuboid::new(Coord3(3, 1, 1), Coord3(1, 5, 5));
+///
+/// assert_eq!(a.volume(), 27);
+/// assert_eq!(a.intersect(&b), Some(Cuboid::new(Coord3(1, 1, 1), Coord3(2, 2, 2))));
+/// assert!(a.contains(&Coord3(2, 0, 1)));
+///
+/// // What's left of a is in disjoint pieces
+/// let rest = a.subtract(&b);
+/// assert_eq!(rest.iter().map(|c| c.volume()).sum::<u64>(), 27 - 8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: Coord3,
+    pub max: Coord3,
+}
+
+impl Cuboid {
+    /// The cuboid with opposite corners `a` and `b`, in any order
+    pub fn new(a: Coord3, b: Coord3) -> Self {
+        Self {
+            min: Coord3(a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
+            max: Coord3(a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)),
+        }
+    }
+
+    /// Number of cells in the cuboid
+    pub fn volume(&self) -> u64 {
+        let (size_x, size_y, size_z) = (self.max - self.min + Coord3(1, 1, 1)).into();
+        size_x as u64 * size_y as u64 * size_z as u64
+    }
+
+    pub fn contains(&self, c: &Coord3) -> bool {
+        (self.min.0..=self.max.0).contains(&c.0)
+            && (self.min.1..=self.max.1).contains(&c.1)
+            && (self.min.2..=self.max.2).contains(&c.2)
+    }
+
+    /// The cells in both cuboids, if there are any
+    pub fn intersect(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = Coord3(
+            self.min.0.max(other.min.0),
+            self.min.1.max(other.min.1),
+            self.min.2.max(other.min.2),
+        );
+        let max = Coord3(
+            self.max.0.min(other.max.0),
+            self.max.1.min(other.max.1),
+            self.max.2.min(other.max.2),
+        );
+        (min.0 <= max.0 && min.1 <= max.1 && min.2 <= max.2).then_some(Cuboid { min, max })
+    }
+
+    /// The cells of this cuboid that aren't in `other`, as up to 6 disjoint cuboids
+    pub fn subtract(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let Some(hole) = self.intersect(other) else {
+            return vec![*self];
+        };
+
+        // Slice off the parts on either side of the hole along x, then what's left along y,
+        // then along z
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        if rest.min.0 < hole.min.0 {
+            pieces.push(Cuboid::new(
+                rest.min,
+                Coord3(hole.min.0 - 1, rest.max.1, rest.max.2),
+            ));
+        }
+        if hole.max.0 < rest.max.0 {
+            pieces.push(Cuboid::new(
+                Coord3(hole.max.0 + 1, rest.min.1, rest.min.2),
+                rest.max,
+            ));
+        }
+        rest.min.0 = hole.min.0;
+        rest.max.0 = hole.max.0;
+
+        if rest.min.1 < hole.min.1 {
+            pieces.push(Cuboid::new(
+                rest.min,
+                Coord3(rest.max.0, hole.min.1 - 1, rest.max.2),
+            ));
+        }
+        if hole.max.1 < rest.max.1 {
+            pieces.push(Cuboid::new(
+                Coord3(rest.min.0, hole.max.1 + 1, rest.min.2),
+                rest.max,
+            ));
+        }
+        rest.min.1 = hole.min.1;
+        rest.max.1 = hole.max.1;
+
+        if rest.min.2 < hole.min.2 {
+            pieces.push(Cuboid::new(
+                rest.min,
+                Coord3(rest.max.0, rest.max.1, hole.min.2 - 1),
+            ));
+        }
+        if hole.max.2 < rest.max.2 {
+            pieces.push(Cuboid::new(
+                Coord3(rest.min.0, rest.min.1, hole.max.2 + 1),
+                rest.max,
+            ));
+        }
+
+        pieces
+    }
+}
+
+/// Which way a path turns, see [`turn_direction`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    /// Counter-clockwise
+    Left,
+    /// Clockwise
+    Right,
+    Straight,
+}
+
+/// Cross product of `p - o` and `q - r`, positive when turning from the first to the second is
+/// to the left on screen. Differences of `i32`s need 33 bits, so their products need `i128`.
+fn cross(o: Coord, p: Coord, r: Coord, q: Coord) -> i128 {
+    let (p_row, p_col) = (p.0 as i128 - o.0 as i128, p.1 as i128 - o.1 as i128);
+    let (q_row, q_col) = (q.0 as i128 - r.0 as i128, q.1 as i128 - r.1 as i128);
+    p_row * q_col - p_col * q_row
+}
+
+/// Which way the path from `a` to `b` to `c` turns at `b`. Left and right depend on which way
+/// up the coordinates are, see [`Orientation`].
+///
+/// # Examples
+/// ```
+/// use aoc::geom::{turn_direction, Turn};
+/// use aoc::grid_2d::{Coord, Orientation};
+///
+/// // Heading east, then down a row
+/// let (a, b, c) = (Coord(0, 0), Coord(0, 1), Coord(1, 1));
+/// assert_eq!(turn_direction(Orientation::Screen, a, b, c), Turn::Right);
+/// assert_eq!(turn_direction(Orientation::Math, a, b, c), Turn::Left);
+///
+/// let (a, b, c) = (Coord(0, 0), Coord(2, 2), Coord(5, 5));
+/// assert_eq!(turn_direction(Orientation::Screen, a, b, c), Turn::Straight);
+/// ```
+pub fn turn_direction(orientation: Orientation, a: Coord, b: Coord, c: Coord) -> Turn {
+    let left = match orientation {
+        Orientation::Screen => Ordering::Greater,
+        Orientation::Math => Ordering::Less,
+    };
+    match cross(a, b, a, c).cmp(&0) {
+        Ordering::Equal => Turn::Straight,
+        sign if sign == left => Turn::Left,
+        _ => Turn::Right,
+    }
+}
+
+/// Where two line segments meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Intersection {
+    /// A single point, as exact (row, col), which might not be a whole cell
+    Point(Ratio<i128>, Ratio<i128>),
+    /// The segments are collinear and share the stretch between these two endpoints
+    Overlap(Coord, Coord),
+}
+
+/// Where the segments `a` and `b`, each including its endpoints, meet. Everything is computed
+/// exactly, so there's no tolerance to tune.
+///
+/// # Examples
+/// ```
+/// use aoc::geom::{segment_intersection, Intersection};
+/// use aoc::grid_2d::Coord;
+/// use num::rational::Ratio;
+///
+/// let cross = segment_intersection((Coord(0, 0), Coord(3, 3)), (Coord(0, 3), Coord(3, 0)));
+/// assert_eq!(
+///     cross,
+///     Some(Intersection::Point(Ratio::new(3, 2), Ratio::new(3, 2)))
+/// );
+///
+/// let overlap = segment_intersection((Coord(0, 0), Coord(0, 5)), (Coord(0, 7), Coord(0, 3)));
+/// assert_eq!(overlap, Some(Intersection::Overlap(Coord(0, 3), Coord(0, 5))));
+///
+/// let parallel = segment_intersection((Coord(0, 0), Coord(0, 5)), (Coord(1, 0), Coord(1, 5)));
+/// assert_eq!(parallel, None);
+/// ```
+pub fn segment_intersection(a: (Coord, Coord), b: (Coord, Coord)) -> Option<Intersection> {
+    let (a1, a2) = a;
+    let (b1, b2) = b;
+    let denominator = cross(a1, a2, b1, b2);
+
+    if denominator != 0 {
+        // a1 + t * (a2 - a1) = b1 + u * (b2 - b1), with both t and u in [0, 1]
+        let t = Ratio::new(cross(a1, b1, b1, b2), denominator);
+        let u = Ratio::new(cross(a1, b1, a1, a2), denominator);
+        let unit = Ratio::from_integer(1);
+        let zero = Ratio::from_integer(0);
+        if t < zero || t > unit || u < zero || u > unit {
+            return None;
+        }
+        let along = |start: i32, end: i32| {
+            Ratio::from_integer(start as i128) + t * (end as i128 - start as i128)
+        };
+        return Some(Intersection::Point(along(a1.0, a2.0), along(a1.1, a2.1)));
+    }
+
+    // Parallel, so they only meet if they're on the same line. Zero-length segments are
+    // parallel to everything, hence checking from both sides.
+    if cross(a1, a2, a1, b1) != 0 || cross(b1, b2, b1, a1) != 0 {
+        return None;
+    }
+
+    // Points on a line are in the same order as their (row, col) tuples
+    let key = |c: Coord| (c.0, c.1);
+    let (a_lo, a_hi) = if key(a1) <= key(a2) {
+        (a1, a2)
+    } else {
+        (a2, a1)
+    };
+    let (b_lo, b_hi) = if key(b1) <= key(b2) {
+        (b1, b2)
+    } else {
+        (b2, b1)
+    };
+    let lo = if key(a_lo) >= key(b_lo) { a_lo } else { b_lo };
+    let hi = if key(a_hi) <= key(b_hi) { a_hi } else { b_hi };
+
+    match key(lo).cmp(&key(hi)) {
+        Ordering::Less => Some(Intersection::Overlap(lo, hi)),
+        Ordering::Equal => Some(Intersection::Point(
+            Ratio::from_integer(lo.0 as i128),
+            Ratio::from_integer(lo.1 as i128),
+        )),
+        Ordering::Greater => None,
+    }
+}
+
+/// The corners of the smallest convex polygon containing all the points, going clockwise on
+/// screen from the one with the smallest (row, col). That's counter-clockwise with
+/// [`Orientation::Math`], where rows go up. Points along the edges aren't included.
+///
+/// # Examples
+/// ```
+/// use aoc::geom::convex_hull;
+/// use aoc::grid_2d::Coord;
+///
+/// let points = [Coord(0, 0), Coord(1, 1), Coord(2, 0), Coord(0, 2), Coord(2, 2), Coord(0, 1)];
+/// assert_eq!(
+///     convex_hull(&points),
+///     vec![Coord(0, 0), Coord(0, 2), Coord(2, 2), Coord(2, 0)]
+/// );
+/// ```
+pub fn convex_hull(points: &[Coord]) -> Vec<Coord> {
+    let mut points = points.to_vec();
+    points.sort_by_key(|c| (c.0, c.1));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Andrew's monotone chain: one half of the hull going forwards through the sorted points
+    // and the other going backwards, each only ever turning left
+    let mut hull: Vec<Coord> = Vec::with_capacity(points.len() + 1);
+    for &p in &points {
+        while hull.len() >= 2
+            && cross(
+                hull[hull.len() - 2],
+                hull[hull.len() - 1],
+                hull[hull.len() - 2],
+                p,
+            ) <= 0
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    let lower_len = hull.len();
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() > lower_len
+            && cross(
+                hull[hull.len() - 2],
+                hull[hull.len() - 1],
+                hull[hull.len() - 2],
+                p,
+            ) <= 0
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    hull.pop();
+
+    // That goes counter-clockwise, so turn it around while keeping the same start
+    hull[1..].reverse();
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_reactor_reboot() {
+        // 2021 day 22, keeping the cubes that are on as disjoint cuboids
+        let steps = [
+            (true, Coord3(10, 10, 10), Coord3(12, 12, 12)),
+            (true, Coord3(11, 11, 11), Coord3(13, 13, 13)),
+            (false, Coord3(9, 9, 9), Coord3(11, 11, 11)),
+            (true, Coord3(10, 10, 10), Coord3(10, 10, 10)),
+        ];
+
+        let mut on: Vec<Cuboid> = Vec::new();
+        for (turn_on, a, b) in steps {
+            let cuboid = Cuboid::new(a, b);
+            on = on.iter().flat_map(|c| c.subtract(&cuboid)).collect();
+            if turn_on {
+                on.push(cuboid);
+            }
+        }
+
+        assert_eq!(on.iter().map(Cuboid::volume).sum::<u64>(), 39);
+    }
+
+    #[test]
+    fn test_subtract_cells() {
+        let cells = |c: &Cuboid| -> HashSet<Coord3> {
+            let mut cells = HashSet::new();
+            for x in c.min.0..=c.max.0 {
+                for y in c.min.1..=c.max.1 {
+                    for z in c.min.2..=c.max.2 {
+                        cells.insert(Coord3(x, y, z));
+                    }
+                }
+            }
+            cells
+        };
+
+        let mut rng = Rng::new(22);
+        let mut corner = || {
+            Coord3(
+                rng.range(0, 5) as i32,
+                rng.range(0, 5) as i32,
+                rng.range(0, 5) as i32,
+            )
+        };
+        for _ in 0..200 {
+            let a = Cuboid::new(corner(), corner());
+            let b = Cuboid::new(corner(), corner());
+
+            let pieces = a.subtract(&b);
+            let mut remaining = HashSet::new();
+            for piece in &pieces {
+                for cell in cells(piece) {
+                    // Pieces don't overlap
+                    assert!(remaining.insert(cell));
+                }
+            }
+            assert_eq!(remaining, &cells(&a) - &cells(&b));
+
+            let both = a.intersect(&b).map(|c| cells(&c)).unwrap_or_default();
+            assert_eq!(both, &cells(&a) & &cells(&b));
+            assert_eq!(a.volume(), cells(&a).len() as u64);
+        }
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let cells: Vec<Coord> =
+            fill_polygon(&[Coord(1, 1), Coord(1, 4), Coord(3, 4), Coord(3, 1)]).collect();
+
+        assert_eq!(cells.len(), 12);
+        assert_eq!(cells[0], Coord(1, 1));
+        assert_eq!(cells[11], Coord(3, 4));
+    }
+
+    #[test]
+    fn test_concave() {
+        // A U shape, with the notch between columns 2 and 4 open at the top
+        let u = [
+            Coord(0, 0),
+            Coord(0, 2),
+            Coord(2, 2),
+            Coord(2, 4),
+            Coord(0, 4),
+            Coord(0, 6),
+            Coord(4, 6),
+            Coord(4, 0),
+        ];
+        let cells: HashSet<Coord> = fill_polygon(&u).collect();
+
+        // 7x5 box, minus the 1x2 notch interior
+        assert_eq!(cells.len(), 35 - 2);
+        assert!(!cells.contains(&Coord(0, 3)));
+        assert!(!cells.contains(&Coord(1, 3)));
+        assert!(cells.contains(&Coord(2, 3)));
+    }
+
+    #[test]
+    fn test_diagonal_edges() {
+        // A diamond with radius 3 covers every cell within manhattan distance 3
+        let diamond = [Coord(-3, 0), Coord(0, 3), Coord(3, 0), Coord(0, -3)];
+        let cells: HashSet<Coord> = fill_polygon(&diamond).collect();
+
+        let expected: HashSet<Coord> = (-3..=3)
+            .flat_map(|r| (-3..=3).map(move |c| Coord(r, c)))
+            .filter(|c| c.l1_norm() <= 3)
+            .collect();
+
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(fill_polygon(&[]).count(), 0);
+        assert_eq!(fill_polygon(&[Coord(0, 0), Coord(0, 5)]).count(), 6);
+    }
+
+    fn random_points(n: usize, seed: u64) -> Vec<Coord3> {
+        let mut rng = Rng::new(seed);
+        (0..n)
+            .map(|_| {
+                Coord3(
+                    rng.range(-1000, 1000) as i32,
+                    rng.range(-1000, 1000) as i32,
+                    rng.range(-1000, 1000) as i32,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_closest_pair_matches_brute_force() {
+        for metric in [
+            Metric::Manhattan,
+            Metric::Chebyshev,
+            Metric::EuclideanSquared,
+        ] {
+            let points = random_points(300, 11);
+            let (_, _, d) = closest_pair(&points, metric).unwrap();
+
+            let brute = (0..points.len())
+                .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+                .map(|(i, j)| metric.distance(&points[i], &points[j]))
+                .min()
+                .unwrap();
+
+            assert_eq!(d, brute, "{:?}", metric);
+        }
+    }
+
+    #[test]
+    fn test_closest_pair_edge_cases() {
+        assert_eq!(closest_pair(&[Coord(1, 1)], Metric::Manhattan), None);
+        assert_eq!(
+            closest_pair(&[Coord(5, 5), Coord(0, 0), Coord(5, 5)], Metric::Manhattan),
+            Some((0, 2, 0))
+        );
+    }
+
+    #[test]
+    fn test_constellations() {
+        // Four dimensional example from 2018 day 25, which has 4 constellations
+        let points: Vec<[i64; 4]> = [
+            [-1, 2, 2, 0],
+            [0, 0, 2, -2],
+            [0, 0, 0, -2],
+            [-1, 2, 0, 0],
+            [-2, -2, -2, 2],
+            [3, 0, 2, -1],
+            [-1, 3, 2, 2],
+            [-1, 0, -1, 0],
+            [0, 2, 1, -2],
+            [3, 0, 0, 0],
+        ]
+        .to_vec();
+
+        assert_eq!(clusters(&points, Metric::Manhattan, 3).len(), 4);
+    }
+
+    #[test]
+    fn test_clusters_match_brute_force() {
+        let points = random_points(200, 4);
+        let found = clusters(&points, Metric::EuclideanSquared, 150 * 150);
+
+        // Every pair within the distance must be in the same cluster
+        let cluster_of: HashMap<usize, usize> = found
+            .iter()
+            .enumerate()
+            .flat_map(|(c, members)| members.iter().map(move |&i| (i, c)))
+            .collect();
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                if Metric::EuclideanSquared.distance(&points[i], &points[j]) <= 150 * 150 {
+                    assert_eq!(cluster_of[&i], cluster_of[&j]);
+                }
+            }
+        }
+
+        assert_eq!(cluster_of.len(), points.len());
+        assert!(found.len() > 1 && found.len() < points.len());
+    }
+
+    #[test]
+    fn test_segment_intersection_matches_orientations() {
+        let turn = |a, b, c| turn_direction(Orientation::Screen, a, b, c);
+        // The textbook test: either each segment's ends are on opposite sides of the other, or
+        // an end of one lies on the other
+        let on_segment = |p: Coord, (a, b): (Coord, Coord)| {
+            turn(a, b, p) == Turn::Straight
+                && (a.0.min(b.0)..=a.0.max(b.0)).contains(&p.0)
+                && (a.1.min(b.1)..=a.1.max(b.1)).contains(&p.1)
+        };
+        let meet = |a: (Coord, Coord), b: (Coord, Coord)| {
+            let (a1, a2) = (turn(a.0, a.1, b.0), turn(a.0, a.1, b.1));
+            let (b1, b2) = (turn(b.0, b.1, a.0), turn(b.0, b.1, a.1));
+            let proper = a1 != a2 && b1 != b2 && ![a1, a2, b1, b2].contains(&Turn::Straight);
+            proper
+                || on_segment(b.0, a)
+                || on_segment(b.1, a)
+                || on_segment(a.0, b)
+                || on_segment(a.1, b)
+        };
+
+        let mut rng = Rng::new(71);
+        let mut random_coord = || Coord(rng.range(-3, 3) as i32, rng.range(-3, 3) as i32);
+        for _ in 0..5000 {
+            let a = (random_coord(), random_coord());
+            let b = (random_coord(), random_coord());
+            let found = segment_intersection(a, b);
+            assert_eq!(found.is_some(), meet(a, b), "{:?} {:?}", a, b);
+
+            match found {
+                Some(Intersection::Point(row, col)) => {
+                    // On both lines, scaling up by the denominators to stay in integers
+                    for (p, q) in [a, b] {
+                        let along = (row - p.0 as i128) * (q.1 - p.1) as i128
+                            - (col - p.1 as i128) * (q.0 - p.0) as i128;
+                        assert_eq!(along, Ratio::from_integer(0), "{:?} {:?}", a, b);
+                    }
+                }
+                Some(Intersection::Overlap(lo, hi)) => {
+                    assert_ne!(lo, hi);
+                    for p in [lo, hi] {
+                        assert!(on_segment(p, a) && on_segment(p, b), "{:?} {:?}", a, b);
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_extreme_coordinates() {
+        // Differences don't fit in an i32, and their products don't fit in an i64
+        let (min, max) = (i32::MIN, i32::MAX);
+        let diagonal = (Coord(min, min), Coord(max, max));
+        let anti = (Coord(min, max), Coord(max, min));
+        let half = Ratio::new(-1, 2);
+        assert_eq!(
+            segment_intersection(diagonal, anti),
+            Some(Intersection::Point(half, half))
+        );
+
+        let (a, b, c) = (Coord(min, min), Coord(min, max), Coord(max, max));
+        assert_eq!(turn_direction(Orientation::Screen, a, b, c), Turn::Right);
+        assert_eq!(turn_direction(Orientation::Math, a, b, c), Turn::Left);
+        assert_eq!(convex_hull(&[a, b, c, Coord(0, 0)]), vec![a, b, c]);
+    }
+
+    #[test]
+    fn test_convex_hull_encloses_points() {
+        let turn = |a, b, c| turn_direction(Orientation::Screen, a, b, c);
+        let mut rng = Rng::new(1071);
+        for n in 0..40 {
+            let points: Vec<Coord> = (0..n)
+                .map(|_| Coord(rng.range(-10, 10) as i32, rng.range(-10, 10) as i32))
+                .collect();
+            let hull = convex_hull(&points);
+
+            if hull.len() >= 3 {
+                for i in 0..hull.len() {
+                    let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+                    let c = hull[(i + 2) % hull.len()];
+                    assert_eq!(turn(a, b, c), Turn::Right);
+                    assert!(points.iter().all(|&p| turn(a, b, p) != Turn::Left));
+                }
+            }
+            assert!(hull.iter().all(|c| points.contains(c)));
+        }
+
+        // Collinear points only keep the ends
+        let line = [Coord(2, 2), Coord(0, 0), Coord(1, 1), Coord(3, 3)];
+        assert_eq!(convex_hull(&line), vec![Coord(0, 0), Coord(3, 3)]);
+    }
+}
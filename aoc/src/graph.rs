@@ -0,0 +1,1101 @@
+use crate::grid_2d::{Board, Coord};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+
+/// A directed graph stored as adjacency lists, with nodes of type `N` and edge weights of type
+/// `E`. Nodes are stored once and referred to by index internally, so `N` can be anything
+/// hashable like a name or a [`Coord`].
+///
+/// # Examples
+/// ```
+/// use aoc::graph::Graph;
+///
+/// let mut graph = Graph::new();
+/// graph.add_edge("a", "b", 3);
+/// graph.add_undirected_edge("b", "c", 5);
+///
+/// assert_eq!(graph.len(), 3);
+/// assert_eq!(graph.neighbours(&"b").collect::<Vec<_>>(), vec![(&"c", &5)]);
+/// assert_eq!(graph.neighbours(&"c").count(), 1);
+/// assert_eq!(graph.neighbours(&"d").count(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Graph<N, E = ()> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, E)>>,
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A graph with a directed edge for each `(from, to, weight)`
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N, E)>) -> Self {
+        let mut graph = Self::new();
+        for (from, to, weight) in edges {
+            graph.add_edge(from, to, weight);
+        }
+        graph
+    }
+
+    /// A graph with edges both ways for each `(a, b, weight)`
+    pub fn from_undirected_edges(edges: impl IntoIterator<Item = (N, N, E)>) -> Self
+    where
+        E: Clone,
+    {
+        let mut graph = Self::new();
+        for (a, b, weight) in edges {
+            graph.add_undirected_edge(a, b, weight);
+        }
+        graph
+    }
+
+    /// Add a node if it isn't already in the graph, returning its index
+    pub fn add_node(&mut self, node: N) -> usize {
+        *self.index.entry(node.clone()).or_insert_with(|| {
+            self.nodes.push(node);
+            self.edges.push(Vec::new());
+            self.nodes.len() - 1
+        })
+    }
+
+    /// Add an edge from `from` to `to`, adding either node if needed
+    pub fn add_edge(&mut self, from: N, to: N, weight: E) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+    }
+
+    /// Add edges from `a` to `b` and back
+    pub fn add_undirected_edge(&mut self, a: N, b: N, weight: E)
+    where
+        E: Clone,
+    {
+        self.add_edge(a.clone(), b.clone(), weight.clone());
+        self.add_edge(b, a, weight);
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    /// Every node, in the order they were added
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter()
+    }
+
+    /// Every edge as `(from, to, weight)`
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N, &E)> {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges
+                    .iter()
+                    .map(move |(to, weight)| (&self.nodes[from], &self.nodes[*to], weight))
+            })
+    }
+
+    /// Nodes reachable by one edge from `node`, with the weights of those edges. Nodes that
+    /// aren't in the graph have no neighbours.
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = (&N, &E)> {
+        self.index
+            .get(node)
+            .map(|&i| self.edges[i].as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|(to, weight)| (&self.nodes[*to], weight))
+    }
+
+    /// Nodes reachable from `start`, in breadth first order with the number of edges to each
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::graph::Graph;
+    ///
+    /// let graph = Graph::from_edges([(1, 2, ()), (1, 3, ()), (2, 4, ()), (3, 4, ())]);
+    ///
+    /// assert_eq!(graph.bfs(&1), vec![(&1, 0), (&2, 1), (&3, 1), (&4, 2)]);
+    /// assert_eq!(graph.bfs(&4), vec![(&4, 0)]);
+    /// ```
+    pub fn bfs(&self, start: &N) -> Vec<(&N, usize)> {
+        let Some(&start) = self.index.get(start) else {
+            return Vec::new();
+        };
+
+        let mut seen = vec![false; self.len()];
+        let mut queue = VecDeque::from([(start, 0)]);
+        let mut order = Vec::new();
+        seen[start] = true;
+
+        while let Some((i, depth)) = queue.pop_front() {
+            order.push((&self.nodes[i], depth));
+            for &(next, _) in &self.edges[i] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Nodes reachable from `start`, in depth first order. Edges are followed in the order they
+    /// were added.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::graph::Graph;
+    ///
+    /// let graph = Graph::from_edges([(1, 2, ()), (1, 3, ()), (2, 4, ()), (3, 4, ())]);
+    ///
+    /// assert_eq!(graph.dfs(&1), vec![&1, &2, &4, &3]);
+    /// ```
+    pub fn dfs(&self, start: &N) -> Vec<&N> {
+        let Some(&start) = self.index.get(start) else {
+            return Vec::new();
+        };
+
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+        let mut order = Vec::new();
+
+        while let Some(i) = stack.pop() {
+            if seen[i] {
+                continue;
+            }
+            seen[i] = true;
+            order.push(&self.nodes[i]);
+            // Reversed so the first edge is popped first
+            stack.extend(self.edges[i].iter().rev().map(|&(next, _)| next));
+        }
+
+        order
+    }
+
+    /// Groups of nodes connected to each other, ignoring the direction of edges. Groups are in
+    /// the order of their first node, and nodes within a group are in the order they were added.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::graph::Graph;
+    ///
+    /// let mut graph = Graph::from_edges([('a', 'b', ()), ('c', 'b', ()), ('d', 'e', ())]);
+    /// graph.add_node('f');
+    ///
+    /// assert_eq!(
+    ///     graph.components(),
+    ///     vec![vec![&'a', &'b', &'c'], vec![&'d', &'e'], vec![&'f']]
+    /// );
+    /// ```
+    pub fn components(&self) -> Vec<Vec<&N>> {
+        let mut undirected = vec![Vec::new(); self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, _) in edges {
+                undirected[from].push(to);
+                undirected[to].push(from);
+            }
+        }
+
+        let mut label = vec![usize::MAX; self.len()];
+        let mut count = 0;
+        for start in 0..self.len() {
+            if label[start] != usize::MAX {
+                continue;
+            }
+            label[start] = count;
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                for &next in &undirected[i] {
+                    if label[next] == usize::MAX {
+                        label[next] = count;
+                        stack.push(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+
+        let mut components = vec![Vec::new(); count];
+        for (node, &label) in self.nodes.iter().zip(&label) {
+            components[label].push(node);
+        }
+        components
+    }
+
+    /// Strongly connected components: groups of nodes that can all reach each other. Also
+    /// returns the condensation, the graph with an edge between components wherever there's an
+    /// edge between their nodes, whose nodes are indices into the components.
+    ///
+    /// Components are in topological order, so edges of the condensation always go from a lower
+    /// index to a higher one. Nodes within a component are in the order they were added.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::graph::Graph;
+    ///
+    /// let graph = Graph::from_edges([
+    ///     ('a', 'b', ()),
+    ///     ('b', 'a', ()),
+    ///     ('b', 'c', ()),
+    ///     ('c', 'd', ()),
+    ///     ('d', 'e', ()),
+    ///     ('e', 'c', ()),
+    /// ]);
+    /// let (components, condensation) = graph.scc();
+    ///
+    /// assert_eq!(components, vec![vec![&'a', &'b'], vec![&'c', &'d', &'e']]);
+    /// assert_eq!(condensation.edges().collect::<Vec<_>>(), vec![(&0, &1, &())]);
+    /// ```
+    pub fn scc(&self) -> (Vec<Vec<&N>>, Graph<usize>) {
+        // Tarjan's algorithm, with an explicit stack of nodes and how many of their edges have
+        // been followed so far, since puzzle graphs are big enough to overflow the call stack
+        const UNSEEN: usize = usize::MAX;
+        let mut order = vec![UNSEEN; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut calls: Vec<(usize, usize)> = Vec::new();
+        let mut next_order = 0;
+        let mut components: Vec<Vec<usize>> = Vec::new();
+
+        for root in 0..self.len() {
+            if order[root] != UNSEEN {
+                continue;
+            }
+
+            calls.push((root, 0));
+            while let Some(&mut (v, ref mut edge)) = calls.last_mut() {
+                if order[v] == UNSEEN {
+                    order[v] = next_order;
+                    low[v] = next_order;
+                    next_order += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                }
+
+                if let Some(&(w, _)) = self.edges[v].get(*edge) {
+                    *edge += 1;
+                    if order[w] == UNSEEN {
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(order[w]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[v]);
+                }
+                if low[v] == order[v] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+
+        // Tarjan finds components in reverse topological order
+        components.reverse();
+        let mut component_of = vec![0; self.len()];
+        for (c, component) in components.iter().enumerate() {
+            for &i in component {
+                component_of[i] = c;
+            }
+        }
+
+        let mut condensation = Graph::new();
+        for c in 0..components.len() {
+            condensation.add_node(c);
+        }
+        let mut seen = HashSet::new();
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, _) in edges {
+                let (a, b) = (component_of[from], component_of[to]);
+                if a != b && seen.insert((a, b)) {
+                    condensation.add_edge(a, b, ());
+                }
+            }
+        }
+
+        let components = components
+            .into_iter()
+            .map(|component| component.into_iter().map(|i| &self.nodes[i]).collect())
+            .collect();
+        (components, condensation)
+    }
+
+    /// Order the nodes so that every edge goes from an earlier node to a later one. When there's
+    /// a choice, nodes come in the order they were added.
+    ///
+    /// Fails with one of the cycles if there's no such order.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::graph::Graph;
+    ///
+    /// // Page ordering rules, where each page has to come before the other
+    /// let mut rules = Graph::from_edges([(47, 53, ()), (97, 47, ()), (75, 53, ()), (97, 75, ())]);
+    /// assert_eq!(rules.topo_sort().unwrap(), vec![97, 47, 75, 53]);
+    /// assert!(!rules.has_cycle());
+    ///
+    /// rules.add_edge(53, 97, ());
+    /// assert_eq!(rules.topo_sort().unwrap_err().cycle, vec![47, 53, 97]);
+    /// ```
+    pub fn topo_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        let mut waiting_on = vec![0; self.len()];
+        for &(to, _) in self.edges.iter().flatten() {
+            waiting_on[to] += 1;
+        }
+
+        // Smallest index first, so ties go to the earliest added node
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.len())
+            .filter(|&i| waiting_on[i] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(Reverse(i)) = ready.pop() {
+            order.push(self.nodes[i].clone());
+            for &(next, _) in &self.edges[i] {
+                waiting_on[next] -= 1;
+                if waiting_on[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+
+        if order.len() < self.len() {
+            return Err(CycleError {
+                cycle: self.find_cycle(&waiting_on),
+            });
+        }
+        Ok(order)
+    }
+
+    /// Whether following edges can ever lead back to the same node
+    pub fn has_cycle(&self) -> bool {
+        self.topo_sort().is_err()
+    }
+
+    /// A cycle among the nodes that [`Graph::topo_sort`] couldn't place, which are exactly
+    /// those still waiting on an edge. Every one of them has an edge in from another, so
+    /// walking those edges backwards has to come round in a loop.
+    fn find_cycle(&self, waiting_on: &[usize]) -> Vec<N> {
+        let mut previous = vec![None; self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            if waiting_on[from] == 0 {
+                continue;
+            }
+            for &(to, _) in edges {
+                previous[to].get_or_insert(from);
+            }
+        }
+
+        let mut seen = vec![false; self.len()];
+        let mut i = (0..self.len()).find(|&i| waiting_on[i] > 0).unwrap();
+        while !seen[i] {
+            seen[i] = true;
+            i = previous[i].unwrap();
+        }
+
+        // `i` is on the cycle, so walk round it once more to collect it
+        let mut cycle = vec![self.nodes[i].clone()];
+        let mut j = previous[i].unwrap();
+        while j != i {
+            cycle.push(self.nodes[j].clone());
+            j = previous[j].unwrap();
+        }
+        cycle.reverse();
+
+        // Start from the earliest added node, so the result doesn't depend on where we started
+        let first = (0..cycle.len())
+            .min_by_key(|&k| self.index[&cycle[k]])
+            .unwrap();
+        cycle.rotate_left(first);
+        cycle
+    }
+}
+
+/// The nodes of a cycle that stopped a [`Graph::topo_sort`], in the order the edges go, starting
+/// from the earliest added node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    pub cycle: Vec<N>,
+}
+
+impl<N: Debug> Display for CycleError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph has a cycle: {:?}", self.cycle)
+    }
+}
+
+impl<N: Debug> std::error::Error for CycleError<N> {}
+
+impl Graph<Coord, ()> {
+    /// A graph of the `passable` cells of a board, with an edge each way between cardinal
+    /// neighbours that are both passable
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::graph::Graph;
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// let board = Board::from_str("..#\n#.#\n#..");
+    /// let graph = Graph::from_board(&board, |&tile| tile != '#');
+    ///
+    /// assert_eq!(graph.len(), 5);
+    /// assert_eq!(graph.bfs(&Coord(0, 0)).last(), Some(&(&Coord(2, 2), 4)));
+    /// ```
+    pub fn from_board<T, P>(board: &Board<T>, passable: P) -> Self
+    where
+        T: Clone,
+        P: Fn(&T) -> bool,
+    {
+        let mut graph = Self::new();
+        for (c, tile) in board.enumerate_coords() {
+            if !passable(tile) {
+                continue;
+            }
+            graph.add_node(c);
+            for (n, _) in board.cardinal_neighbours(&c).filter(|(_, n)| passable(n)) {
+                graph.add_edge(c, n, ());
+            }
+        }
+        graph
+    }
+}
+
+/// A fixed size set of node indices, one bit each
+#[derive(Clone)]
+struct Bits(Vec<u64>);
+
+impl Bits {
+    fn new(len: usize) -> Self {
+        Bits(vec![0; len.div_ceil(64)])
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn remove(&mut self, i: usize) {
+        self.0[i / 64] &= !(1 << (i % 64));
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|&w| w == 0)
+    }
+
+    fn and(&self, other: &Bits) -> Bits {
+        Bits(self.0.iter().zip(&other.0).map(|(a, b)| a & b).collect())
+    }
+
+    fn and_not(&self, other: &Bits) -> Bits {
+        Bits(self.0.iter().zip(&other.0).map(|(a, b)| a & !b).collect())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(k, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    k * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// Bron–Kerbosch with pivoting. `found` is called with each maximal clique, and returns a size
+/// that later cliques have to beat to be worth finding, which is kept in `bound`.
+fn bron_kerbosch<F>(
+    adjacent: &[Bits],
+    clique: &mut Vec<usize>,
+    mut p: Bits,
+    mut x: Bits,
+    bound: &mut usize,
+    found: &mut F,
+) where
+    F: FnMut(&[usize]) -> usize,
+{
+    if p.is_empty() && x.is_empty() {
+        *bound = found(clique);
+        return;
+    }
+
+    // Pivot on the node with the most candidates as neighbours, since only candidates that
+    // aren't its neighbours need to be tried
+    let pivot = p
+        .iter()
+        .chain(x.iter())
+        .max_by_key(|&u| p.and(&adjacent[u]).len())
+        .unwrap();
+
+    for v in p.and_not(&adjacent[pivot]).iter().collect::<Vec<_>>() {
+        if clique.len() + p.len() <= *bound {
+            return;
+        }
+        clique.push(v);
+        let (next_p, next_x) = (p.and(&adjacent[v]), x.and(&adjacent[v]));
+        bron_kerbosch(adjacent, clique, next_p, next_x, bound, found);
+        clique.pop();
+        p.remove(v);
+        x.insert(v);
+    }
+}
+
+/// Run [`bron_kerbosch`] over a graph, ignoring the direction of its edges
+fn cliques<N, E, F>(graph: &Graph<N, E>, mut found: F)
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&[usize]) -> usize,
+{
+    let n = graph.len();
+    let mut adjacent = vec![Bits::new(n); n];
+    for (from, edges) in graph.edges.iter().enumerate() {
+        for &(to, _) in edges {
+            if from != to {
+                adjacent[from].insert(to);
+                adjacent[to].insert(from);
+            }
+        }
+    }
+
+    let mut all = Bits::new(n);
+    for i in 0..n {
+        all.insert(i);
+    }
+    let mut bound = 0;
+    bron_kerbosch(
+        &adjacent,
+        &mut Vec::new(),
+        all,
+        Bits::new(n),
+        &mut bound,
+        &mut found,
+    );
+}
+
+/// Every maximal clique of a graph: sets of nodes that are all connected to each other, and
+/// that can't be made any bigger. Edges are treated as undirected. Nodes within a clique are in
+/// the order they were added to the graph.
+///
+/// # Examples
+/// ```
+/// use aoc::graph::{all_maximal_cliques, Graph};
+///
+/// let graph = Graph::from_undirected_edges([(1, 2, ()), (2, 3, ()), (1, 3, ()), (3, 4, ())]);
+/// let mut cliques = all_maximal_cliques(&graph);
+/// cliques.sort();
+///
+/// assert_eq!(cliques, vec![vec![&1, &2, &3], vec![&3, &4]]);
+/// ```
+pub fn all_maximal_cliques<N, E>(graph: &Graph<N, E>) -> Vec<Vec<&N>>
+where
+    N: Clone + Eq + Hash,
+{
+    let mut all = Vec::new();
+    cliques(graph, |clique| {
+        if !clique.is_empty() {
+            all.push(graph.clique_nodes(clique));
+        }
+        0
+    });
+    all
+}
+
+/// The largest clique of a graph, see [`all_maximal_cliques`]. If there's more than one, which
+/// is returned is unspecified.
+///
+/// # Examples
+/// ```
+/// use aoc::graph::{max_clique, Graph};
+///
+/// // 2024 day 23, connections between computers at a LAN party
+/// let graph = Graph::from_undirected_edges(
+///     ["ka-co", "ta-co", "de-co", "ta-ka", "de-ta", "ka-de", "wq-ta", "ub-qp"]
+///         .iter()
+///         .map(|line| (&line[..2], &line[3..], ())),
+/// );
+/// let mut password = max_clique(&graph);
+/// password.sort();
+///
+/// assert_eq!(password, vec![&"co", &"de", &"ka", &"ta"]);
+/// ```
+pub fn max_clique<N, E>(graph: &Graph<N, E>) -> Vec<&N>
+where
+    N: Clone + Eq + Hash,
+{
+    let mut best = Vec::new();
+    cliques(graph, |clique| {
+        if clique.len() > best.len() {
+            best = clique.to_vec();
+        }
+        best.len()
+    });
+    graph.clique_nodes(&best)
+}
+
+impl<N, E> Graph<N, E> {
+    /// Nodes of a clique found by [`bron_kerbosch`], in the order they were added
+    fn clique_nodes(&self, clique: &[usize]) -> Vec<&N> {
+        let mut clique = clique.to_vec();
+        clique.sort_unstable();
+        clique.into_iter().map(|i| &self.nodes[i]).collect()
+    }
+}
+
+/// Residual network for Dinic's algorithm. Arcs are stored in pairs, so arc `a ^ 1` is the
+/// reverse of arc `a`.
+struct FlowNetwork {
+    /// Each arc's target and remaining capacity
+    arcs: Vec<(usize, u64)>,
+    /// Arcs out of each node
+    out: Vec<Vec<usize>>,
+    level: Vec<usize>,
+    /// Next arc to try out of each node in the current phase
+    next: Vec<usize>,
+}
+
+impl FlowNetwork {
+    fn new<N>(graph: &Graph<N, u64>, source: usize, sink: usize) -> (Self, u64) {
+        let n = graph.nodes.len();
+        let mut network = FlowNetwork {
+            arcs: Vec::new(),
+            out: vec![Vec::new(); n],
+            level: vec![0; n],
+            next: vec![0; n],
+        };
+        for (from, edges) in graph.edges.iter().enumerate() {
+            for &(to, capacity) in edges {
+                network.out[from].push(network.arcs.len());
+                network.arcs.push((to, capacity));
+                network.out[to].push(network.arcs.len());
+                network.arcs.push((from, 0));
+            }
+        }
+
+        let mut flow = 0;
+        while network.levels(source, sink) {
+            network.next.fill(0);
+            loop {
+                let pushed = network.push(source, sink);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+        (network, flow)
+    }
+
+    /// Label nodes by their distance from the source over arcs with capacity left, returning
+    /// whether the sink can still be reached
+    fn levels(&mut self, source: usize, sink: usize) -> bool {
+        self.level.fill(usize::MAX);
+        self.level[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(i) = queue.pop_front() {
+            for &a in &self.out[i] {
+                let (to, capacity) = self.arcs[a];
+                if capacity > 0 && self.level[to] == usize::MAX {
+                    self.level[to] = self.level[i] + 1;
+                    queue.push_back(to);
+                }
+            }
+        }
+        self.level[sink] != usize::MAX
+    }
+
+    /// Push as much as fits along one path of increasing levels from the source, returning how
+    /// much got through. The path is kept as an explicit stack of arcs rather than recursing, so
+    /// long paths don't overflow the call stack.
+    fn push(&mut self, source: usize, sink: usize) -> u64 {
+        let mut path: Vec<usize> = Vec::new();
+        let mut i = source;
+
+        while i != sink {
+            match self.out[i].get(self.next[i]) {
+                Some(&a) => {
+                    let (to, capacity) = self.arcs[a];
+                    if capacity > 0 && self.level[to] == self.level[i] + 1 {
+                        path.push(a);
+                        i = to;
+                    } else {
+                        self.next[i] += 1;
+                    }
+                }
+                None => {
+                    // A dead end, so back up and don't try the arc that led here again
+                    let Some(a) = path.pop() else {
+                        return 0;
+                    };
+                    i = self.arcs[a ^ 1].0;
+                    self.next[i] += 1;
+                }
+            }
+        }
+
+        let pushed = path.iter().map(|&a| self.arcs[a].1).min().unwrap();
+        for &a in &path {
+            self.arcs[a].1 -= pushed;
+            self.arcs[a ^ 1].1 += pushed;
+        }
+        pushed
+    }
+}
+
+impl<N> Graph<N, u64>
+where
+    N: Clone + Eq + Hash,
+{
+    /// Indices of a source and sink for [`max_flow`] and [`min_cut`], or None if either isn't
+    /// in the graph, in which case nothing can flow
+    fn terminals(&self, source: &N, sink: &N) -> Option<(usize, usize)> {
+        let source = *self.index.get(source)?;
+        let sink = *self.index.get(sink)?;
+        assert_ne!(source, sink, "source and sink have to be different nodes");
+        Some((source, sink))
+    }
+}
+
+/// The most that can flow from `source` to `sink`, where edge weights are capacities, using
+/// Dinic's algorithm. Undirected edges can be added as an edge each way.
+///
+/// # Panics
+/// Panics if `source` and `sink` are the same node
+///
+/// # Examples
+/// ```
+/// use aoc::graph::{max_flow, Graph};
+///
+/// let graph = Graph::from_edges([
+///     ('s', 'a', 3),
+///     ('s', 'b', 2),
+///     ('a', 'b', 1),
+///     ('a', 't', 2),
+///     ('b', 't', 3),
+/// ]);
+///
+/// assert_eq!(max_flow(&graph, &'s', &'t'), 5);
+/// assert_eq!(max_flow(&graph, &'t', &'s'), 0);
+/// ```
+pub fn max_flow<N>(graph: &Graph<N, u64>, source: &N, sink: &N) -> u64
+where
+    N: Clone + Eq + Hash,
+{
+    match graph.terminals(source, sink) {
+        Some((source, sink)) => FlowNetwork::new(graph, source, sink).1,
+        None => 0,
+    }
+}
+
+/// A minimum set of edges whose removal stops anything flowing from `source` to `sink`, and its
+/// total capacity, which is the same as the [`max_flow`]. Each edge is `(from, to)` with `from`
+/// on the source's side, so an undirected edge only appears once.
+///
+/// # Panics
+/// Panics if `source` and `sink` are the same node
+///
+/// # Examples
+/// ```
+/// use aoc::graph::{min_cut, Graph};
+///
+/// // Two triangles joined by a single wire
+/// let graph = Graph::from_undirected_edges([
+///     ('a', 'b', 1),
+///     ('b', 'c', 1),
+///     ('c', 'a', 1),
+///     ('c', 'd', 1),
+///     ('d', 'e', 1),
+///     ('e', 'f', 1),
+///     ('f', 'd', 1),
+/// ]);
+///
+/// assert_eq!(min_cut(&graph, &'a', &'f'), (1, vec![(&'c', &'d')]));
+/// ```
+pub fn min_cut<'a, N>(graph: &'a Graph<N, u64>, source: &N, sink: &N) -> (u64, Vec<(&'a N, &'a N)>)
+where
+    N: Clone + Eq + Hash,
+{
+    let Some((source, sink)) = graph.terminals(source, sink) else {
+        return (0, Vec::new());
+    };
+    let (mut network, flow) = FlowNetwork::new(graph, source, sink);
+
+    // After the last phase, the levels mark everything still reachable from the source
+    network.levels(source, sink);
+    let source_side = |i: usize| network.level[i] != usize::MAX;
+    let cut = graph
+        .edges
+        .iter()
+        .enumerate()
+        .flat_map(|(from, edges)| {
+            edges
+                .iter()
+                .map(move |&(to, capacity)| (from, to, capacity))
+        })
+        .filter(|&(from, to, capacity)| capacity > 0 && source_side(from) && !source_side(to))
+        .map(|(from, to, _)| (&graph.nodes[from], &graph.nodes[to]))
+        .collect();
+    (flow, cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_caves() {
+        // 2021 day 12
+        let graph = Graph::from_undirected_edges(
+            ["start-A", "start-b", "A-c", "A-b", "b-d", "A-end", "b-end"]
+                .iter()
+                .map(|line| {
+                    let (a, b) = line.split_once('-').unwrap();
+                    (a, b, ())
+                }),
+        );
+
+        assert_eq!(graph.len(), 6);
+        assert_eq!(graph.edges().count(), 14);
+        assert_eq!(graph.components().len(), 1);
+
+        let depths: HashMap<&str, usize> = graph
+            .bfs(&"start")
+            .into_iter()
+            .map(|(&n, depth)| (n, depth))
+            .collect();
+        assert_eq!(depths["end"], 2);
+        assert_eq!(depths["d"], 2);
+        assert_eq!(graph.dfs(&"d").len(), 6);
+    }
+
+    #[test]
+    fn test_sleigh_steps() {
+        // 2018 day 7
+        let graph = Graph::from_edges(
+            ["CA", "CF", "AB", "AD", "BE", "DE", "FE"]
+                .iter()
+                .map(|step| (step.as_bytes()[0] as char, step.as_bytes()[1] as char, ())),
+        );
+
+        let order: String = graph.topo_sort().unwrap().into_iter().collect();
+        assert_eq!(order, "CAFBDE");
+
+        let mut graph = graph;
+        graph.add_edge('E', 'C', ());
+        let err = graph.topo_sort().unwrap_err();
+        assert_eq!(err.cycle.first(), Some(&'C'));
+        assert_eq!(err.cycle.last(), Some(&'E'));
+        assert_eq!(err.to_string()[..25], *"graph has a cycle: ['C', ");
+    }
+
+    #[test]
+    fn test_two_sat() {
+        // (x or y) and (!x or y) and (!y or z) and (!z or !y), as implications between literals
+        let clauses = [(1, 2), (-1, 2), (-2, 3), (-3, -2)];
+        let graph = Graph::from_edges(
+            clauses
+                .iter()
+                .flat_map(|&(a, b)| [(-a, b, ()), (-b, a, ())]),
+        );
+
+        // Satisfiable exactly when no variable is in the same component as its negation
+        let (components, condensation) = graph.scc();
+        let component_of: HashMap<i32, usize> = components
+            .iter()
+            .enumerate()
+            .flat_map(|(c, nodes)| nodes.iter().map(move |&&n| (n, c)))
+            .collect();
+        assert!((1..=3).any(|x| component_of[&x] == component_of[&-x]));
+
+        assert_eq!(
+            components.iter().map(|c| c.len()).sum::<usize>(),
+            graph.len()
+        );
+        assert!(condensation.edges().all(|(a, b, _)| a < b));
+        assert!(!condensation.has_cycle());
+    }
+
+    #[test]
+    fn test_scc_long_chain() {
+        // Deep enough that a recursive search would overflow the stack
+        let n = 200_000;
+        let graph = Graph::from_edges((0..n).map(|i| (i, (i + 1) % n, ())));
+        let (components, condensation) = graph.scc();
+
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), n);
+        assert_eq!(condensation.len(), 1);
+    }
+
+    #[test]
+    fn test_max_flow_long_path() {
+        // Deep enough that a recursive search would overflow the stack
+        let n = 200_000;
+        let graph = Graph::from_edges((0..n).map(|i| (i, i + 1, 2 + (i % 3) as u64)));
+        assert_eq!(max_flow(&graph, &0, &n), 2);
+        assert_eq!(min_cut(&graph, &0, &n).0, 2);
+    }
+
+    #[test]
+    fn test_cliques_against_brute_force() {
+        let mut rng = Rng::new(23);
+        for _ in 0..50 {
+            let n = rng.range(1, 12) as usize;
+            let mut graph = Graph::new();
+            for a in 0..n {
+                graph.add_node(a);
+                for b in a + 1..n {
+                    if rng.chance(0.5) {
+                        graph.add_undirected_edge(a, b, ());
+                    }
+                }
+            }
+
+            let is_clique = |nodes: &[usize]| {
+                nodes.iter().all(|a| {
+                    nodes
+                        .iter()
+                        .all(|b| a == b || graph.neighbours(a).any(|(n, _)| n == b))
+                })
+            };
+            let subsets: Vec<Vec<usize>> = (0..1u32 << n)
+                .map(|mask| (0..n).filter(|i| mask & (1 << i) != 0).collect())
+                .filter(|nodes: &Vec<usize>| is_clique(nodes))
+                .collect();
+            let maximal: HashSet<Vec<usize>> = subsets
+                .iter()
+                .filter(|a| {
+                    (0..n).all(|v| a.contains(&v) || !is_clique(&[a.as_slice(), &[v]].concat()))
+                })
+                .cloned()
+                .collect();
+
+            let found: HashSet<Vec<usize>> = all_maximal_cliques(&graph)
+                .into_iter()
+                .map(|clique| {
+                    let mut clique: Vec<usize> = clique.into_iter().copied().collect();
+                    clique.sort_unstable();
+                    clique
+                })
+                .collect();
+            assert_eq!(found, maximal);
+
+            let largest = subsets.iter().map(|s| s.len()).max().unwrap();
+            let best = max_clique(&graph);
+            assert_eq!(best.len(), largest);
+            assert!(is_clique(&best.into_iter().copied().collect::<Vec<_>>()));
+        }
+    }
+
+    #[test]
+    fn test_snowverload() {
+        // 2023 day 25
+        let input = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+        let wires: Vec<(&str, &str)> = input
+            .lines()
+            .flat_map(|line| {
+                let (a, rest) = line.split_once(": ").unwrap();
+                rest.split(' ').map(move |b| (a, b))
+            })
+            .collect();
+        let graph = Graph::from_undirected_edges(wires.iter().map(|&(a, b)| (a, b, 1)));
+
+        // Some node has to be on the other side of the three wires from the first
+        let first = graph.nodes().next().unwrap();
+        let (flow, cut) = graph
+            .nodes()
+            .skip(1)
+            .map(|sink| min_cut(&graph, first, sink))
+            .find(|(flow, _)| *flow == 3)
+            .unwrap();
+        assert_eq!(flow, 3);
+
+        let mut cut: Vec<(&str, &str)> = cut
+            .into_iter()
+            .map(|(&a, &b)| (a.min(b), a.max(b)))
+            .collect();
+        cut.sort();
+        assert_eq!(cut, vec![("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+
+        let split = Graph::from_undirected_edges(
+            wires
+                .iter()
+                .filter(|&&(a, b)| !cut.contains(&(a.min(b), a.max(b))))
+                .map(|&(a, b)| (a, b, ())),
+        );
+        let sizes: Vec<usize> = split.components().iter().map(|c| c.len()).collect();
+        assert_eq!(sizes.iter().product::<usize>(), 54);
+    }
+
+    #[test]
+    fn test_board_regions() {
+        let board = Board::from_str("..#..\n..#..\n#####\n....#");
+        let graph = Graph::from_board(&board, |&tile| tile == '.');
+
+        let sizes: Vec<usize> = graph.components().iter().map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![4, 4, 4]);
+        assert!(graph.has_cycle());
+        // Edges go both ways between each pair of neighbours
+        assert_eq!(graph.edges().count(), 2 * (4 + 4 + 3));
+    }
+}
@@ -1,8 +1,11 @@
+use crate::bytes;
+use crate::parse::ParseError;
 use num::Integer;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::hash::Hash;
-use std::ops::{Add, Mul, Sub};
+use std::iter::Sum;
+use std::ops::{Add, Index, IndexMut, Mul, Range, Sub};
 
 /// A (row, col) coordinate pair or vector. Using i32 so that we can subtract
 /// or have negative vectors.
@@ -13,13 +16,21 @@ impl Coord {
     /// Simplify the coordinate vector by dividing both components by their
     /// greatest common divisor.
     ///
+    /// The zero vector is left as it is.
+    ///
     /// # Examples
     /// ```
-    /// let c = Coord(4, 6);
-    /// assert!(c.simplify() == Coord(2, 3));
+    /// use aoc::grid_2d::Coord;
+    ///
+    /// assert_eq!(Coord(4, 6).simplify(), Coord(2, 3));
+    /// assert_eq!(Coord(-3, 0).simplify(), Coord(-1, 0));
+    /// assert_eq!(Coord(0, 0).simplify(), Coord(0, 0));
     /// ```
     pub fn simplify(&self) -> Coord {
         let gcd = self.0.gcd(&self.1);
+        if gcd == 0 {
+            return *self;
+        }
 
         Coord(self.0 / gcd, self.1 / gcd)
     }
@@ -36,15 +47,45 @@ impl Coord {
         ]
     }
 
+    /// Get the neighbours of a coordinate in the diagonal directions.
+    ///
+    /// Sorted clockwise starting from the north east.
+    pub fn diagonal_neighbours(&self) -> [Coord; 4] {
+        [
+            self + Dir::NorthEast,
+            self + Dir::SouthEast,
+            self + Dir::SouthWest,
+            self + Dir::NorthWest,
+        ]
+    }
+
+    /// Get all 8 neighbours of a coordinate.
+    ///
+    /// Sorted clockwise starting from the north.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::Coord;
+    ///
+    /// let neighbours = Coord(0, 0).all_neighbours();
+    /// assert_eq!(neighbours[0], Coord(-1, 0));
+    /// assert_eq!(neighbours[1], Coord(-1, 1));
+    /// assert_eq!(neighbours[7], Coord(-1, -1));
+    /// ```
+    pub fn all_neighbours(&self) -> [Coord; 8] {
+        Dir::all().map(|dir| self + dir)
+    }
+
     /// Wrap the coordinate to the given size / board dimension.
     ///
     /// Return coordinate values will always be non-negative.
     ///
     /// # Examples
     /// ```
+    /// use aoc::grid_2d::Coord;
+    ///
     /// let size = (4usize, 4usize);
-    /// let c = Coord(7, -5);
-    /// assert!(c.wrap_to_size(size) == Coord(3, 3));
+    /// assert_eq!(Coord(7, -5).wrap_to_size(size), Coord(3, 3));
     /// ```
     pub fn wrap_to_size<T>(self, size: T) -> Coord
     where
@@ -52,15 +93,46 @@ impl Coord {
     {
         let size = size.into();
 
-        let row = (self.0 % size.0 + size.0) % size.0;
-        let col = (self.1 % size.1 + size.1) % size.1;
-
-        return (row, col).into();
+        Coord(self.0.rem_euclid(size.0), self.1.rem_euclid(size.1))
     }
 
     /// Compute the manhattan distance between two coordinates
     pub fn manhattan_distance(&self, other: &Coord) -> u32 {
-        (self.0.abs_diff(other.0) + self.1.abs_diff(other.1)) as u32
+        self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
+    }
+
+    /// Iterate over the coordinates exactly `radius` away in manhattan distance, clockwise
+    /// starting from the north
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::Coord;
+    ///
+    /// let ring: Vec<Coord> = Coord(0, 0).manhattan_ring(2).collect();
+    /// assert_eq!(ring.len(), 8);
+    /// assert_eq!(ring[..3], [Coord(-2, 0), Coord(-1, 1), Coord(0, 2)]);
+    /// assert!(ring.iter().all(|c| c.l1_norm() == 2));
+    ///
+    /// assert_eq!(Coord(3, 4).manhattan_ring(0).collect::<Vec<_>>(), vec![Coord(3, 4)]);
+    /// ```
+    pub fn manhattan_ring(&self, radius: u32) -> impl Iterator<Item = Coord> {
+        let (center, r) = (*self, radius as i32);
+        let count = if r == 0 { 1 } else { 4 * r };
+
+        (0..count).map(move |i| {
+            if r == 0 {
+                return center;
+            }
+            // Walk each side of the diamond in turn
+            let k = i % r;
+            let offset = match i / r {
+                0 => Coord(k - r, k),
+                1 => Coord(k, r - k),
+                2 => Coord(r - k, -k),
+                _ => Coord(-k, k - r),
+            };
+            center + offset
+        })
     }
 
     /// Compute the L1-norm of the coordinate vector
@@ -74,6 +146,14 @@ impl Coord {
     pub fn l2_norm(&self) -> f64 {
         ((self.0 * self.0 + self.1 * self.1) as f64).sqrt()
     }
+
+    pub fn row(&self) -> Row {
+        Row(self.0)
+    }
+
+    pub fn col(&self) -> Col {
+        Col(self.1)
+    }
 }
 
 impl From<Coord> for (i32, i32) {
@@ -94,6 +174,101 @@ impl From<(usize, usize)> for Coord {
     }
 }
 
+/// A row index, for code that mixes (row, col) and (x, y) conventions. A `Coord` can be built
+/// from a [`Row`] and a [`Col`] in either order, so transposed arguments are caught at compile
+/// time rather than producing a wrong answer.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Col, Coord, Row};
+///
+/// let (x, y) = (2, 1);
+/// assert_eq!(Coord::from((Col(x), Row(y))), Coord(1, 2));
+/// assert_eq!(Coord::from((Row(y), Col(x))), Coord(1, 2));
+///
+/// let mut board = Board::from_str("...\n...");
+/// board[(Col(x), Row(y))] = '#';
+/// assert_eq!(board[(Row(1), Col(2))], '#');
+/// assert_eq!(Coord(1, 2).row(), Row(1));
+/// ```
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct Row(pub i32);
+
+/// A column index, see [`Row`]
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+pub struct Col(pub i32);
+
+impl From<(Row, Col)> for Coord {
+    fn from((row, col): (Row, Col)) -> Self {
+        Coord(row.0, col.0)
+    }
+}
+
+impl From<(Col, Row)> for Coord {
+    fn from((col, row): (Col, Row)) -> Self {
+        Coord(row.0, col.0)
+    }
+}
+
+impl Row {
+    /// How many rows apart two rows are
+    pub fn distance(self, other: Row) -> Distance {
+        Distance(self.0.abs_diff(other.0))
+    }
+}
+
+impl Col {
+    /// How many columns apart two columns are
+    pub fn distance(self, other: Col) -> Distance {
+        Distance(self.0.abs_diff(other.0))
+    }
+}
+
+/// A number of steps between cells, to keep distances apart from the [`Row`] and [`Col`]
+/// indices they're measured along. Distances elsewhere are plain `u32`s, which convert both
+/// ways.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Col, Coord, Distance, Row};
+///
+/// let (a, b) = (Coord(1, 5), Coord(4, 3));
+/// let d = a.row().distance(b.row()) + a.col().distance(b.col());
+/// assert_eq!(d, Distance(5));
+/// assert_eq!(u32::from(d), a.manhattan_distance(&b));
+///
+/// let total: Distance = [3, 4].into_iter().map(Distance::from).sum();
+/// assert_eq!(total, Distance(7));
+/// ```
+#[derive(Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Default)]
+pub struct Distance(pub u32);
+
+impl Add<Distance> for Distance {
+    type Output = Distance;
+
+    fn add(self, rhs: Distance) -> Self::Output {
+        Distance(self.0 + rhs.0)
+    }
+}
+
+impl Sum for Distance {
+    fn sum<I: Iterator<Item = Distance>>(iter: I) -> Self {
+        Distance(iter.map(|d| d.0).sum())
+    }
+}
+
+impl From<u32> for Distance {
+    fn from(d: u32) -> Self {
+        Distance(d)
+    }
+}
+
+impl From<Distance> for u32 {
+    fn from(d: Distance) -> Self {
+        d.0
+    }
+}
+
 impl Add<Coord> for Coord {
     type Output = Coord;
 
@@ -275,7 +450,8 @@ impl Dir {
     ///
     /// # Examples
     /// ```
-    /// use grid_2d::Dir;
+    /// use aoc::grid_2d::Dir;
+    ///
     /// let north = Dir::North;
     /// let east = Dir::East;
     /// let north_east = Dir::NorthEast;
@@ -361,6 +537,100 @@ impl From<Coord> for Dir {
     }
 }
 
+/// Which way is north for a [`Coord`]. `Coord + Dir` always uses [`Orientation::Screen`], so
+/// puzzles where `y` grows upwards should go through an [`Orientation::Math`] instead of adding
+/// directions directly, to avoid getting everything mirrored.
+///
+/// Either way coordinates are `Coord(vertical, horizontal)`, and east increases the second.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Coord, Dir, Orientation};
+///
+/// let math = Orientation::Math;
+/// assert_eq!(math.step(Coord(0, 0), Dir::North), Coord(1, 0));
+/// assert_eq!(math.step(Coord(0, 0), Dir::NorthEast), Coord(1, 1));
+/// assert_eq!(math.dir(Coord(-3, 0)), Dir::South);
+/// assert_eq!(Orientation::Screen.step(Coord(0, 0), Dir::North), Coord(0, 0) + Dir::North);
+///
+/// let ray: Vec<Coord> = math.ray(Coord(0, 0), Dir::West).take(2).collect();
+/// assert_eq!(ray, vec![Coord(0, -1), Coord(0, -2)]);
+///
+/// // The same point with the vertical axis flipped
+/// assert_eq!(math.to(Orientation::Screen, Coord(2, 5)), Coord(-2, 5));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// North decreases the row, like text on a screen
+    #[default]
+    Screen,
+    /// North increases `y`, like a graph
+    Math,
+}
+
+impl Orientation {
+    /// The unit vector of a direction
+    pub fn offset(self, dir: Dir) -> Coord {
+        let Coord(row, col) = Coord(0, 0) + dir;
+        match self {
+            Orientation::Screen => Coord(row, col),
+            Orientation::Math => Coord(-row, col),
+        }
+    }
+
+    /// One step from `c` in `dir`
+    pub fn step(self, c: Coord, dir: Dir) -> Coord {
+        c + self.offset(dir)
+    }
+
+    /// The direction a vector points in
+    ///
+    /// # Panics
+    /// Panics if the vector is not a cardinal or diagonal direction
+    pub fn dir(self, v: Coord) -> Dir {
+        Dir::from(self.to(Orientation::Screen, v))
+    }
+
+    /// Every cell from `from` in a straight line in `dir`, not including `from` itself
+    pub fn ray(self, from: Coord, dir: Dir) -> impl Iterator<Item = Coord> {
+        let offset = self.offset(dir);
+        std::iter::successors(Some(from + offset), move |&c| Some(c + offset))
+    }
+
+    /// Convert a coordinate in this orientation to the same point in `other`
+    pub fn to(self, other: Orientation, c: Coord) -> Coord {
+        if self == other {
+            c
+        } else {
+            Coord(-c.0, c.1)
+        }
+    }
+}
+
+/// Which cells count as adjacent when filling regions of a board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only the cardinal neighbours
+    Four,
+    /// The cardinal and diagonal neighbours
+    Eight,
+}
+
+impl Connectivity {
+    /// The neighbours of a coordinate, sorted clockwise starting from the north
+    pub fn neighbours(self, c: &Coord) -> Vec<Coord> {
+        match self {
+            Connectivity::Four => c.cardinal_neighbours().to_vec(),
+            Connectivity::Eight => c.all_neighbours().to_vec(),
+        }
+    }
+}
+
+/// Lines of a board, ignoring any blank lines at the end of the input
+fn board_lines(input: &str) -> std::str::Lines<'_> {
+    input.trim_end_matches(['\n', '\r']).lines()
+}
+
 #[derive(Debug, Clone)]
 pub struct Board<T>
 where
@@ -381,14 +651,15 @@ where
     /// element using the provided transformation function.
     ///
     /// # Arguments
-    /// * `input` - A string representing the board, with rows separated by newlines
+    /// * `input` - A string representing the board, with rows separated by newlines. Blank lines
+    ///   at the end are ignored.
     /// * `transform` - A function that converts each character to the board's element type
     ///
     /// # Examples
     /// ```
-    /// use grid_2d::Board;
+    /// use aoc::grid_2d::{Board, Coord};
     ///
-    /// #[derive(Debug, Clone, Hash)]
+    /// #[derive(Debug, Clone, PartialEq)]
     /// enum Cell {
     ///     Empty,
     ///     Rock,
@@ -407,22 +678,60 @@ where
     ///     _ => panic!("unexpected character"),
     /// });
     ///
-    /// assert_eq!(board.get(&Coord(0, 0)), Some(Cell::Start));
-    /// assert_eq!(board.get(&Coord(0, 1)), Some(Cell::Rock));
-    /// assert_eq!(board.get(&Coord(2, 2)), Some(Cell::Rock));
+    /// assert_eq!(board.get(&Coord(0, 0)), Some(&Cell::Sand));
+    /// assert_eq!(board.get(&Coord(0, 1)), Some(&Cell::Rock));
+    /// assert_eq!(board.get(&Coord(2, 2)), Some(&Cell::Rock));
     /// ```
     pub fn transform_from_str<F>(input: &str, transform: F) -> Self
     where
         F: Fn(char) -> T,
     {
-        let matrix: Vec<Vec<T>> = input
-            .lines()
-            .map(|line| line.chars().map(|c| transform(c)).collect())
+        let matrix: Vec<Vec<T>> = board_lines(input)
+            .map(|line| line.chars().map(&transform).collect())
             .collect();
 
         Self::new(matrix)
     }
 
+    /// Like [`Board::transform_from_str`], but fails on rows of different lengths instead of
+    /// making a board that panics later
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::Board;
+    ///
+    /// let board = Board::try_transform_from_str("12\n34\n\n", |c| c.to_digit(10)).unwrap();
+    /// assert_eq!(board.size(), (2, 2));
+    ///
+    /// assert_eq!(
+    ///     Board::try_transform_from_str("12\n3", |c| c).unwrap_err().to_string(),
+    ///     "failed to parse line 2: \"3\" (expected 2 columns, found 1)"
+    /// );
+    /// ```
+    pub fn try_transform_from_str<F>(input: &str, transform: F) -> Result<Self, ParseError>
+    where
+        F: Fn(char) -> T,
+    {
+        let mut lines = board_lines(input);
+        let Some(first) = lines.next() else {
+            return Ok(Self::new(Vec::new()));
+        };
+        let cols = first.chars().count();
+
+        for (i, line) in lines.enumerate() {
+            let found = line.chars().count();
+            if found != cols {
+                return Err(ParseError::new(
+                    line,
+                    format!("expected {} columns, found {}", cols, found),
+                )
+                .at_line(i + 2));
+            }
+        }
+
+        Ok(Self::transform_from_str(input, transform))
+    }
+
     /// Construct a board with the given size, and fill all elements with the
     /// given item
     pub fn from_size<S>(size: S, item: T) -> Self
@@ -438,26 +747,75 @@ where
         Self::new(matrix)
     }
 
+    /// Number of rows and columns, going by the first row. An empty board is `(0, 0)`, and a
+    /// board of empty rows has no columns.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// assert_eq!(Board::from_str("").size(), (0, 0));
+    /// assert_eq!(Board::from_str("abc\n").size(), (1, 3));
+    /// assert_eq!(Board::<u8>::new(vec![vec![], vec![]]).size(), (2, 0));
+    ///
+    /// let empty = Board::from_str("\n\n");
+    /// assert!(empty.is_empty());
+    /// assert_eq!(empty.get(&Coord(0, 0)), None);
+    /// assert!(empty.positions().is_empty());
+    /// ```
     pub fn size(&self) -> (usize, usize) {
-        (self.matrix.len(), self.matrix[0].len())
+        (self.matrix.len(), self.matrix.first().map_or(0, Vec::len))
+    }
+
+    /// Whether the board has no cells at all
+    pub fn is_empty(&self) -> bool {
+        let (rows, cols) = self.size();
+        rows == 0 || cols == 0
     }
 
-    pub fn get(&self, c: &Coord) -> Option<T> {
+    fn in_bounds(&self, c: &Coord) -> bool {
         let (rows, cols) = self.size();
+        c.0 >= 0 && (c.0 as usize) < rows && c.1 >= 0 && (c.1 as usize) < cols
+    }
 
-        if c.0 < 0 || c.0 as usize >= rows || c.1 < 0 || c.1 as usize >= cols {
+    /// Get a reference to the value at a coordinate, or None if it's outside of the board
+    pub fn get(&self, c: &Coord) -> Option<&T> {
+        self.in_bounds(c)
+            .then(|| &self.matrix[c.0 as usize][c.1 as usize])
+    }
+
+    /// Get a mutable reference to the value at a coordinate, or None if it's outside of the
+    /// board
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// let mut board = Board::new(vec![vec![vec![1], vec![]]]);
+    /// board.get_mut(&Coord(0, 1)).unwrap().push(2);
+    ///
+    /// assert_eq!(board.get(&Coord(0, 1)), Some(&vec![2]));
+    /// assert_eq!(board.get_mut(&Coord(1, 0)), None);
+    /// ```
+    pub fn get_mut(&mut self, c: &Coord) -> Option<&mut T> {
+        if !self.in_bounds(c) {
             return None;
         }
 
-        Some(self.matrix[c.0 as usize][c.1 as usize].clone())
+        Some(&mut self.matrix[c.0 as usize][c.1 as usize])
     }
 
-    /// Get the value at a coordinate without checking for bounds
+    /// Get a copy of the value at a coordinate, or None if it's outside of the board
+    pub fn get_cloned(&self, c: &Coord) -> Option<T> {
+        self.get(c).cloned()
+    }
+
+    /// Get a copy of the value at a coordinate without checking for bounds
     ///
     /// # Panics
     /// Panics if the coordinate is outside of the board
     pub fn get_unchecked(&self, c: &Coord) -> T {
-        self.get(c).unwrap()
+        self.get(c).unwrap().clone()
     }
 
     /// Find the position of all occurrences of `elem` on the board.
@@ -470,7 +828,7 @@ where
         self.matrix
             .iter()
             .enumerate()
-            .map(|(i, row)| {
+            .flat_map(|(i, row)| {
                 row.iter().enumerate().filter_map(move |(j, e)| {
                     if e == elem {
                         Some((i, j).into())
@@ -479,7 +837,6 @@ where
                     }
                 })
             })
-            .flatten()
             .collect()
     }
 
@@ -494,7 +851,7 @@ where
     ///
     /// # Examples
     /// ```
-    /// use grid_2d::{Board, Coord};
+    /// use aoc::grid_2d::{Board, Coord};
     ///
     /// // Using with a char board - collecting all non-empty spaces
     /// let board = Board::from_str(
@@ -560,6 +917,349 @@ where
             .collect()
     }
 
+    /// Iterate over every element by reference, row by row
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.matrix.iter().flatten()
+    }
+
+    /// Iterate over every element by mutable reference, row by row
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.matrix.iter_mut().flatten()
+    }
+
+    /// Iterate over every element along with its coordinate, row by row
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// let board = Board::from_str("#.\n.#");
+    /// let walls: Vec<Coord> = board
+    ///     .enumerate_coords()
+    ///     .filter(|(_, &cell)| cell == '#')
+    ///     .map(|(c, _)| c)
+    ///     .collect();
+    ///
+    /// assert_eq!(walls, vec![Coord(0, 0), Coord(1, 1)]);
+    /// ```
+    pub fn enumerate_coords(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.matrix.iter().enumerate().flat_map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(c, item)| (Coord(r as i32, c as i32), item))
+        })
+    }
+
+    /// The cardinal neighbours of a coordinate that are on the board, with their values. Sorted
+    /// clockwise starting from the north.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// let board = Board::from_str("ab\ncd");
+    ///
+    /// let neighbours: Vec<_> = board.cardinal_neighbours(&Coord(0, 0)).collect();
+    /// assert_eq!(neighbours, vec![(Coord(0, 1), &'b'), (Coord(1, 0), &'c')]);
+    /// assert_eq!(board.all_neighbours(&Coord(0, 0)).count(), 3);
+    /// ```
+    pub fn cardinal_neighbours(&self, c: &Coord) -> impl Iterator<Item = (Coord, &T)> {
+        c.cardinal_neighbours()
+            .into_iter()
+            .filter_map(move |n| Some((n, self.get(&n)?)))
+    }
+
+    /// Like [`Board::cardinal_neighbours`], including the diagonals
+    pub fn all_neighbours(&self, c: &Coord) -> impl Iterator<Item = (Coord, &T)> {
+        c.all_neighbours()
+            .into_iter()
+            .filter_map(move |n| Some((n, self.get(&n)?)))
+    }
+
+    /// Every cell reachable from `start` by moving between neighbours, where `passable(from,
+    /// to)` says whether a step between two cells' values is allowed
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Connectivity, Coord};
+    ///
+    /// // Climbing at most one step at a time
+    /// let board = Board::from_str("0123\n9992\n5431").map(|c| c.to_digit(10).unwrap());
+    /// let climb = |&from: &u32, &to: &u32| to <= from + 1;
+    ///
+    /// let reachable = board.flood_fill(&Coord(0, 0), Connectivity::Four, climb);
+    /// assert_eq!(reachable.len(), 6);
+    /// assert!(!reachable.contains(&Coord(1, 0)));
+    ///
+    /// // The rest of the bottom row can only be reached diagonally, from the 2 above its end
+    /// let reachable = board.flood_fill(&Coord(0, 0), Connectivity::Eight, climb);
+    /// assert_eq!(reachable.len(), 9);
+    /// ```
+    pub fn flood_fill<P>(
+        &self,
+        start: &Coord,
+        connectivity: Connectivity,
+        passable: P,
+    ) -> HashSet<Coord>
+    where
+        P: Fn(&T, &T) -> bool,
+    {
+        let mut seen = self.map(|_| false);
+        self.fill(*start, connectivity, &passable, &mut seen)
+            .into_iter()
+            .collect()
+    }
+
+    /// Breadth first fill from `start`, marking cells in `seen` and skipping any already marked
+    fn fill<P>(
+        &self,
+        start: Coord,
+        connectivity: Connectivity,
+        passable: &P,
+        seen: &mut Board<bool>,
+    ) -> Vec<Coord>
+    where
+        P: Fn(&T, &T) -> bool,
+    {
+        if seen.get(&start) != Some(&false) {
+            return Vec::new();
+        }
+        seen[start] = true;
+
+        let mut cells = vec![start];
+        let mut i = 0;
+        while let Some(&c) = cells.get(i) {
+            i += 1;
+            for next in connectivity.neighbours(&c) {
+                if seen.get(&next) == Some(&false) && passable(&self[c], &self[next]) {
+                    seen[next] = true;
+                    cells.push(next);
+                }
+            }
+        }
+
+        cells
+    }
+
+    /// Split the board into connected regions of equal values. Regions are ordered by their
+    /// first cell in row order, which also comes first in each region, so a region's index
+    /// can be used as its label.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Connectivity};
+    ///
+    /// // 2024 day 12
+    /// let board = Board::from_str("AAAA\nBBCD\nBBCC\nEEEC");
+    /// let regions = board.regions(Connectivity::Four);
+    /// assert_eq!(regions.len(), 5);
+    ///
+    /// let price: usize = regions
+    ///     .iter()
+    ///     .map(|region| {
+    ///         let perimeter: usize = region
+    ///             .iter()
+    ///             .map(|c| 4 - board.cardinal_neighbours(c).filter(|(_, &n)| n == board[*c]).count())
+    ///             .sum();
+    ///         region.len() * perimeter
+    ///     })
+    ///     .sum();
+    /// assert_eq!(price, 140);
+    ///
+    /// let board = Board::from_str("X.\n.X");
+    /// assert_eq!(board.regions(Connectivity::Four).len(), 4);
+    /// assert_eq!(board.regions(Connectivity::Eight).len(), 2);
+    /// ```
+    pub fn regions(&self, connectivity: Connectivity) -> Vec<Vec<Coord>>
+    where
+        T: PartialEq,
+    {
+        let mut seen = self.map(|_| false);
+        self.positions()
+            .into_iter()
+            .map(|c| self.fill(c, connectivity, &|a, b| a == b, &mut seen))
+            .filter(|region| !region.is_empty())
+            .collect()
+    }
+
+    /// Build a new board by applying a function to every element
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// let board = Board::from_str("#.\n.#");
+    /// let walls = board.map(|&tile| tile == '#');
+    /// assert!(walls[Coord(1, 1)]);
+    ///
+    /// let labels = board.map_with_coord(|c, &tile| format!("{}{}{}", tile, c.0, c.1));
+    /// assert_eq!(labels[Coord(0, 1)], ".01");
+    /// ```
+    pub fn map<U, F>(&self, mut f: F) -> Board<U>
+    where
+        U: Clone,
+        F: FnMut(&T) -> U,
+    {
+        self.map_with_coord(|_, item| f(item))
+    }
+
+    /// Like [`Board::map`], also passing each element's coordinate
+    pub fn map_with_coord<U, F>(&self, mut f: F) -> Board<U>
+    where
+        U: Clone,
+        F: FnMut(Coord, &T) -> U,
+    {
+        Board::new(
+            self.matrix
+                .iter()
+                .enumerate()
+                .map(|(r, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(c, item)| f(Coord(r as i32, c as i32), item))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// A row of the board, left to right
+    ///
+    /// # Panics
+    /// Panics if `i` is past the last row
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.matrix[i]
+    }
+
+    /// A column of the board from top to bottom
+    ///
+    /// # Panics
+    /// Panics if `j` is past the last column
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::Board;
+    ///
+    /// let board = Board::from_str("abc\ndef");
+    ///
+    /// assert_eq!(board.row(1), &['d', 'e', 'f']);
+    /// assert_eq!(board.col(2).collect::<String>(), "cf");
+    /// assert_eq!(board.cols().map(|col| col.count()).sum::<usize>(), 6);
+    /// ```
+    pub fn col(&self, j: usize) -> impl Iterator<Item = &T> + Clone {
+        assert!(j < self.size().1, "column {} is out of bounds", j);
+        self.matrix.iter().map(move |row| &row[j])
+    }
+
+    /// Iterate over the rows of the board, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.matrix.iter().map(Vec::as_slice)
+    }
+
+    /// Iterate over the columns of the board, left to right. See [`Board::col`].
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + Clone> {
+        (0..self.size().1).map(|j| self.col(j))
+    }
+
+    /// A copy of the board with rows and columns swapped
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::Board;
+    ///
+    /// let board = Board::from_str("abc\ndef");
+    ///
+    /// assert_eq!(board.transpose().matrix, Board::from_str("ad\nbe\ncf").matrix);
+    /// ```
+    pub fn transpose(&self) -> Self {
+        Self::new(
+            (0..self.size().1)
+                .map(|j| self.col(j).cloned().collect())
+                .collect(),
+        )
+    }
+
+    /// A copy of the board rotated 90 degrees clockwise
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::Board;
+    ///
+    /// let board = Board::from_str("abc\ndef");
+    ///
+    /// assert_eq!(board.rotate_right().matrix, Board::from_str("da\neb\nfc").matrix);
+    /// assert_eq!(board.rotate_left().matrix, Board::from_str("cf\nbe\nad").matrix);
+    /// assert_eq!(board.rotate_180().matrix, Board::from_str("fed\ncba").matrix);
+    /// assert_eq!(
+    ///     board.rotate_right().rotate_right().rotate_right().matrix,
+    ///     board.rotate_left().matrix
+    /// );
+    /// ```
+    pub fn rotate_right(&self) -> Self {
+        let mut board = self.transpose();
+        board.flip_horizontal_in_place();
+        board
+    }
+
+    /// A copy of the board rotated 90 degrees counter-clockwise, see [`Board::rotate_right`]
+    pub fn rotate_left(&self) -> Self {
+        let mut board = self.transpose();
+        board.flip_vertical_in_place();
+        board
+    }
+
+    /// A copy of the board rotated 180 degrees, see [`Board::rotate_right`]
+    pub fn rotate_180(&self) -> Self {
+        let mut board = self.clone();
+        board.rotate_180_in_place();
+        board
+    }
+
+    /// Rotate the board 180 degrees without copying it
+    pub fn rotate_180_in_place(&mut self) {
+        self.flip_horizontal_in_place();
+        self.flip_vertical_in_place();
+    }
+
+    /// A copy of the board mirrored left to right
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::Board;
+    ///
+    /// let mut board = Board::from_str("abc\ndef");
+    ///
+    /// assert_eq!(board.flip_horizontal().matrix, Board::from_str("cba\nfed").matrix);
+    /// assert_eq!(board.flip_vertical().matrix, Board::from_str("def\nabc").matrix);
+    ///
+    /// board.flip_vertical_in_place();
+    /// assert_eq!(board.row(0), &['d', 'e', 'f']);
+    /// ```
+    pub fn flip_horizontal(&self) -> Self {
+        let mut board = self.clone();
+        board.flip_horizontal_in_place();
+        board
+    }
+
+    /// A copy of the board mirrored top to bottom, see [`Board::flip_horizontal`]
+    pub fn flip_vertical(&self) -> Self {
+        let mut board = self.clone();
+        board.flip_vertical_in_place();
+        board
+    }
+
+    /// Mirror the board left to right without copying it
+    pub fn flip_horizontal_in_place(&mut self) {
+        for row in &mut self.matrix {
+            row.reverse();
+        }
+    }
+
+    /// Mirror the board top to bottom without copying it
+    pub fn flip_vertical_in_place(&mut self) {
+        self.matrix.reverse();
+    }
+
     /// Print the board to the terminal
     pub fn print(&self)
     where
@@ -578,6 +1278,10 @@ where
     where
         T: Display,
     {
+        if self.is_empty() {
+            return;
+        }
+
         let (rows, cols) = self.size();
         let row_space = (rows - 1).to_string().len();
         let col_space = (cols - 1).to_string().len();
@@ -597,9 +1301,8 @@ where
                 print!(" ")
             }
 
-            for j in 0..cols {
-                let e = &col_labels[j][i..=i];
-                print!("{}", e);
+            for label in &col_labels {
+                print!("{}", &label[i..=i]);
             }
             println!();
         }
@@ -618,10 +1321,380 @@ where
     }
 }
 
+// Indexing panics outside of the board, use `get` to check bounds
+macro_rules! impl_board_index {
+    ($($t:ty),*) => {
+        $(
+            impl<T: Clone> Index<$t> for Board<T> {
+                type Output = T;
+
+                fn index(&self, index: $t) -> &T {
+                    let c: Coord = index.into();
+                    &self.matrix[c.0 as usize][c.1 as usize]
+                }
+            }
+
+            impl<T: Clone> IndexMut<$t> for Board<T> {
+                fn index_mut(&mut self, index: $t) -> &mut T {
+                    let c: Coord = index.into();
+                    &mut self.matrix[c.0 as usize][c.1 as usize]
+                }
+            }
+        )*
+    };
+}
+
+impl_board_index!(Coord, (Row, Col), (Col, Row));
+
 impl Board<char> {
+    // Not `FromStr`, since parsing a board can't fail
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &str) -> Self {
-        let matrix: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        Self::transform_from_str(input, |c| c)
+    }
 
-        Self::new(matrix)
+    /// See [`Board::try_transform_from_str`]
+    pub fn try_from_str(input: &str) -> Result<Self, ParseError> {
+        Self::try_transform_from_str(input, |c| c)
+    }
+
+    /// Parse a board, also finding every position of each of `markers` (eg. `S` and `E`).
+    /// Markers that aren't on the board map to an empty list.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// let (board, markers) = Board::from_str_with_markers("S.#\n..E", &['S', 'E']);
+    /// assert_eq!(markers[&'S'], vec![Coord(0, 0)]);
+    /// assert_eq!(markers[&'E'], vec![Coord(1, 2)]);
+    /// assert_eq!(board.get(&Coord(0, 0)), Some(&'S'));
+    /// ```
+    pub fn from_str_with_markers(
+        input: &str,
+        markers: &[char],
+    ) -> (Self, HashMap<char, Vec<Coord>>) {
+        let board = Self::from_str(input);
+        let mut found: HashMap<char, Vec<Coord>> = markers.iter().map(|&m| (m, vec![])).collect();
+
+        for (c, cell) in board.enumerate_coords() {
+            if let Some(positions) = found.get_mut(cell) {
+                positions.push(c);
+            }
+        }
+
+        (board, found)
+    }
+
+    /// [`Board::from_str_with_markers`], replacing the markers on the board with `floor`, for
+    /// when they're just ordinary cells once found
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// let (board, markers) = Board::from_str_with_markers_replaced("S.#\n..E", &['S', 'E'], '.');
+    /// assert_eq!(markers[&'E'], vec![Coord(1, 2)]);
+    /// assert_eq!(board.find(&'.').len(), 5);
+    /// ```
+    pub fn from_str_with_markers_replaced(
+        input: &str,
+        markers: &[char],
+        floor: char,
+    ) -> (Self, HashMap<char, Vec<Coord>>) {
+        let (mut board, found) = Self::from_str_with_markers(input, markers);
+        for c in found.values().flatten() {
+            board.set(c, floor);
+        }
+
+        (board, found)
+    }
+
+    /// Every horizontal run of digits on the board, in row order, see
+    /// [`crate::bytes::number_spans`]
+    ///
+    /// # Panics
+    /// Panics if a number doesn't fit in a `u32`
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Board, Coord};
+    ///
+    /// // 2023 day 3
+    /// let board = Board::from_str(
+    ///     "467..114..\n...*......\n..35..633.\n......#...\n617*......\n\
+    ///      .....+.58.\n..592.....\n......755.\n...$.*....\n.664.598..",
+    /// );
+    /// let numbers = board.number_spans();
+    /// assert_eq!(numbers[0].value, 467);
+    /// assert_eq!(numbers[0].cols, 0..3);
+    ///
+    /// let symbols: Vec<Coord> = board
+    ///     .enumerate_coords()
+    ///     .filter(|(_, &cell)| cell != '.' && !cell.is_ascii_digit())
+    ///     .map(|(c, _)| c)
+    ///     .collect();
+    /// let part_numbers: u32 = numbers
+    ///     .iter()
+    ///     .filter(|n| n.touches_any(&symbols))
+    ///     .map(|n| n.value)
+    ///     .sum();
+    /// assert_eq!(part_numbers, 4361);
+    ///
+    /// let gear_ratios: u32 = board
+    ///     .find(&'*')
+    ///     .iter()
+    ///     .map(|gear| numbers.iter().filter(|n| n.is_adjacent(gear)).collect::<Vec<_>>())
+    ///     .filter(|parts| parts.len() == 2)
+    ///     .map(|parts| parts[0].value * parts[1].value)
+    ///     .sum();
+    /// assert_eq!(gear_ratios, 467835);
+    /// ```
+    pub fn number_spans(&self) -> Vec<NumberSpan> {
+        let mut spans = Vec::new();
+
+        for (r, row) in self.matrix.iter().enumerate() {
+            // One byte per cell, so that columns line up. Anything non-ASCII isn't a digit.
+            let line: Vec<u8> = row
+                .iter()
+                .map(|&c| if c.is_ascii() { c as u8 } else { 0 })
+                .collect();
+
+            spans.extend(
+                bytes::number_spans(&line)
+                    .into_iter()
+                    .map(|(cols, value)| NumberSpan {
+                        row: r as i32,
+                        cols: cols.start as i32..cols.end as i32,
+                        value,
+                    }),
+            );
+        }
+
+        spans
+    }
+}
+
+/// A number written horizontally on a board, see [`Board::number_spans`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumberSpan {
+    pub row: i32,
+    /// Columns the digits are in
+    pub cols: Range<i32>,
+    pub value: u32,
+}
+
+impl NumberSpan {
+    /// The cells the digits are in
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.cols.clone().map(|col| Coord(self.row, col))
+    }
+
+    /// Whether a cell is next to one of the digits, including diagonally, but isn't a digit
+    pub fn is_adjacent(&self, c: &Coord) -> bool {
+        let near_row = (c.0 - self.row).abs() <= 1;
+        let near_cols = c.1 >= self.cols.start - 1 && c.1 <= self.cols.end;
+        let on_digit = c.0 == self.row && self.cols.contains(&c.1);
+
+        near_row && near_cols && !on_digit
+    }
+
+    /// Whether any of a set of cells is adjacent, see [`NumberSpan::is_adjacent`]
+    pub fn touches_any<'a>(&self, cells: impl IntoIterator<Item = &'a Coord>) -> bool {
+        cells.into_iter().any(|c| self.is_adjacent(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn random_coords(seed: u64) -> impl Iterator<Item = Coord> {
+        let mut rng = Rng::new(seed);
+        std::iter::repeat_with(move || {
+            Coord(rng.range(-1000, 1000) as i32, rng.range(-1000, 1000) as i32)
+        })
+        .take(500)
+    }
+
+    #[test]
+    fn test_number_spans() {
+        // Columns count cells, not bytes
+        let board = Board::from_str("é12\n..3");
+        let spans: Vec<(i32, Range<i32>, u32)> = board
+            .number_spans()
+            .into_iter()
+            .map(|n| (n.row, n.cols, n.value))
+            .collect();
+        assert_eq!(spans, vec![(0, 1..3, 12), (1, 2..3, 3)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in u32")]
+    fn test_number_spans_overflow() {
+        Board::from_str(".99999999999.").number_spans();
+    }
+
+    #[test]
+    fn test_rotations() {
+        for dir in Dir::all() {
+            let turns = [
+                Dir::rotate_right,
+                Dir::rotate_left,
+                Dir::rotate_right_45,
+                Dir::rotate_left_45,
+                Dir::rotate_180,
+            ];
+            let cycle = [4, 4, 8, 8, 2];
+            for (turn, n) in turns.into_iter().zip(cycle) {
+                let mut d = dir;
+                for i in 1..=n {
+                    d = turn(d);
+                    assert_eq!(d == dir, i == n, "{:?} after {} turns", dir, i);
+                }
+            }
+
+            assert_eq!(dir.rotate_right().rotate_left(), dir);
+            assert_eq!(dir.rotate_right_45().rotate_left_45(), dir);
+            assert_eq!(dir.rotate_right().rotate_right(), dir.rotate_180());
+            assert_eq!(dir.rotate_right_45().rotate_right_45(), dir.rotate_right());
+            assert_eq!(
+                dir.rotate_right().to_degrees(),
+                (dir.to_degrees() + 90) % 360
+            );
+        }
+    }
+
+    #[test]
+    fn test_offsets() {
+        for a in Dir::all() {
+            assert_eq!(a.offset_from(&a), 0);
+            assert_eq!(a.rotate_right().offset_from(&a), 90);
+            assert_eq!(a.rotate_left_45().offset_from(&a), -45);
+
+            for b in Dir::all() {
+                let offset = a.offset_from(&b);
+                assert!(-180 < offset && offset <= 180);
+                if offset != 180 {
+                    assert_eq!(b.offset_from(&a), -offset, "{:?} and {:?}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dir_vectors() {
+        for dir in Dir::all() {
+            let v = Coord(0, 0) + dir;
+            assert_eq!(Dir::from(v), dir);
+            assert_eq!(Dir::from(v * 7), dir);
+            assert_eq!(Coord(0, 0) + dir.rotate_180(), v * -1);
+            assert_eq!(Orientation::Math.dir(Orientation::Math.offset(dir)), dir);
+        }
+
+        let cardinal: Vec<Coord> = Dir::cardinal().map(|d| Coord(0, 0) + d).to_vec();
+        assert_eq!(cardinal, Coord(0, 0).cardinal_neighbours());
+    }
+
+    #[test]
+    fn test_wrap_to_size() {
+        for (i, c) in random_coords(1).enumerate() {
+            let size = Coord(i as i32 % 7 + 1, i as i32 % 13 + 1);
+            let wrapped = c.wrap_to_size(size);
+
+            assert!((0..size.0).contains(&wrapped.0) && (0..size.1).contains(&wrapped.1));
+            assert_eq!(wrapped.wrap_to_size(size), wrapped);
+            assert_eq!((c - wrapped).0 % size.0, 0);
+            assert_eq!((c - wrapped).1 % size.1, 0);
+            assert_eq!((c + size * 3).wrap_to_size(size), wrapped);
+        }
+    }
+
+    #[test]
+    fn test_simplify() {
+        for c in random_coords(2) {
+            let s = c.simplify();
+            assert_eq!(s.simplify(), s);
+            if c != Coord(0, 0) {
+                let k = c.0.gcd(&c.1);
+                assert_eq!(s * k, c);
+                assert_eq!(s.0.gcd(&s.1), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_neighbours() {
+        for c in random_coords(3) {
+            let all = c.all_neighbours();
+            assert!(all.iter().all(|n| n != &c && (*n - c).l1_norm() <= 2));
+            assert_eq!(all.iter().collect::<HashSet<_>>().len(), 8);
+
+            for n in c.cardinal_neighbours() {
+                assert_eq!(n.manhattan_distance(&c), 1);
+                assert!(all.contains(&n));
+            }
+            for n in c.diagonal_neighbours() {
+                assert_eq!(n.manhattan_distance(&c), 2);
+                assert!(all.contains(&n));
+            }
+
+            let radius = (c.0.unsigned_abs() % 5) + 1;
+            let ring: Vec<Coord> = c.manhattan_ring(radius).collect();
+            assert_eq!(ring.len() as u32, 4 * radius);
+            assert!(ring.iter().all(|n| n.manhattan_distance(&c) == radius));
+        }
+    }
+
+    #[test]
+    fn test_iter() {
+        let board = Board::from_str("abc\ndef");
+        assert_eq!(board.iter().collect::<String>(), "abcdef");
+
+        // Same order as the coordinates
+        let coords: Vec<Coord> = board.enumerate_coords().map(|(c, _)| c).collect();
+        assert_eq!(coords, board.positions());
+        assert_eq!(
+            coords[..4],
+            [Coord(0, 0), Coord(0, 1), Coord(0, 2), Coord(1, 0)]
+        );
+        assert!(board.enumerate_coords().all(|(c, &cell)| board[c] == cell));
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut board = Board::new(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        for (i, cell) in board.iter_mut().enumerate() {
+            *cell *= 10;
+            *cell += i;
+        }
+
+        assert_eq!(board.matrix, vec![vec![10, 21, 32], vec![43, 54, 65]]);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut board = Board::from_str("ab\ncd");
+        assert_eq!(board[Coord(0, 1)], 'b');
+        assert_eq!(board[Coord(1, 0)], 'c');
+
+        board[Coord(1, 1)] = '#';
+        assert_eq!(board.get(&Coord(1, 1)), Some(&'#'));
+        assert_eq!(board[(Row(1), Col(1))], '#');
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let board = Board::from_str("ab\ncd");
+        let _ = board[Coord(0, 2)];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_mut_out_of_bounds() {
+        let mut board = Board::from_str("ab\ncd");
+        board[Coord(-1, 0)] = '#';
     }
 }
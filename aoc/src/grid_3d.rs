@@ -0,0 +1,71 @@
+use std::ops::{Add, Mul, Sub};
+
+/// An (x, y, z) coordinate or vector
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy, Ord, PartialOrd)]
+pub struct Coord3(pub i32, pub i32, pub i32);
+
+impl Coord3 {
+    /// The 6 neighbours that share a face with this coordinate
+    pub fn face_neighbours(&self) -> [Coord3; 6] {
+        [
+            Coord3(self.0 + 1, self.1, self.2),
+            Coord3(self.0 - 1, self.1, self.2),
+            Coord3(self.0, self.1 + 1, self.2),
+            Coord3(self.0, self.1 - 1, self.2),
+            Coord3(self.0, self.1, self.2 + 1),
+            Coord3(self.0, self.1, self.2 - 1),
+        ]
+    }
+
+    /// Compute the manhattan distance between two coordinates
+    pub fn manhattan_distance(&self, other: &Coord3) -> u32 {
+        self.0.abs_diff(other.0) + self.1.abs_diff(other.1) + self.2.abs_diff(other.2)
+    }
+
+    /// Compute the L1-norm of the coordinate vector
+    pub fn l1_norm(&self) -> u32 {
+        self.0.unsigned_abs() + self.1.unsigned_abs() + self.2.unsigned_abs()
+    }
+
+    /// Compute the L2-norm of the coordinate vector
+    pub fn l2_norm(&self) -> f64 {
+        let (x, y, z) = (self.0 as f64, self.1 as f64, self.2 as f64);
+        (x * x + y * y + z * z).sqrt()
+    }
+}
+
+impl From<(i32, i32, i32)> for Coord3 {
+    fn from(value: (i32, i32, i32)) -> Self {
+        Coord3(value.0, value.1, value.2)
+    }
+}
+
+impl From<Coord3> for (i32, i32, i32) {
+    fn from(value: Coord3) -> Self {
+        (value.0, value.1, value.2)
+    }
+}
+
+impl Add<Coord3> for Coord3 {
+    type Output = Coord3;
+
+    fn add(self, rhs: Coord3) -> Self::Output {
+        Coord3(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl Sub<Coord3> for Coord3 {
+    type Output = Coord3;
+
+    fn sub(self, rhs: Coord3) -> Self::Output {
+        Coord3(self.0 - rhs.0, self.1 - rhs.1, self.2 - rhs.2)
+    }
+}
+
+impl Mul<i32> for Coord3 {
+    type Output = Coord3;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Coord3(self.0 * rhs, self.1 * rhs, self.2 * rhs)
+    }
+}
@@ -0,0 +1,527 @@
+use crate::grid_2d::Coord;
+use std::collections::HashMap;
+
+/// A birth/survival rule for a two state automaton on the 8 neighbour grid, like `B3/S23` for
+/// Conway's Game of Life
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    /// Bit `n` is set if a dead cell with `n` live neighbours comes alive
+    born: u16,
+    /// Bit `n` is set if a live cell with `n` live neighbours stays alive
+    survive: u16,
+}
+
+impl Rule {
+    pub const CONWAY: Rule = Rule {
+        born: 1 << 3,
+        survive: 1 << 2 | 1 << 3,
+    };
+
+    /// Parse a rule in `B3/S23` notation. Rules where a dead cell with no live neighbours comes
+    /// alive (`B0`) aren't supported, since the infinite background would flash.
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::hashlife::Rule;
+    ///
+    /// assert_eq!(Rule::parse("B3/S23"), Some(Rule::CONWAY));
+    /// assert!(Rule::parse("B36/S23").is_some());
+    /// assert_eq!(Rule::parse("B03/S23"), None);
+    /// ```
+    pub fn parse(s: &str) -> Option<Rule> {
+        let (born, survive) = s.split_once('/')?;
+        let mask = |digits: &str| -> Option<u16> {
+            digits.chars().try_fold(0, |mask, c| match c.to_digit(10) {
+                Some(n) if n <= 8 => Some(mask | 1 << n),
+                _ => None,
+            })
+        };
+
+        let rule = Rule {
+            born: mask(born.strip_prefix('B')?)?,
+            survive: mask(survive.strip_prefix('S')?)?,
+        };
+        (rule.born & 1 == 0).then_some(rule)
+    }
+
+    fn next(&self, alive: bool, neighbours: u32) -> bool {
+        let mask = if alive { self.survive } else { self.born };
+        mask & (1 << neighbours) != 0
+    }
+}
+
+/// A square of `2^level` cells, made of four quadrants one level down. Level 0 nodes are
+/// single cells.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    /// Northwest, northeast, southwest and southeast quadrants
+    children: [u32; 4],
+    population: u64,
+}
+
+const DEAD: u32 = 0;
+const ALIVE: u32 = 1;
+
+/// Highest level the universe can grow to, keeping coordinates across it well within an `i64`
+const MAX_LEVEL: u8 = 62;
+
+/// Memoized quadtree ("HashLife") simulation of a two state automaton, for running huge or
+/// sparse patterns for astronomically many generations. Identical squares anywhere in space or
+/// time are stored once, and how each evolves is cached, so repetitive patterns can jump
+/// exponentially far ahead.
+///
+/// Nothing is ever evicted, so memory use grows with the variety of squares seen.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::Coord;
+/// use aoc::hashlife::{HashLife, Rule};
+///
+/// // A glider moves one cell diagonally every 4 generations
+/// let glider = [Coord(0, 1), Coord(1, 2), Coord(2, 0), Coord(2, 1), Coord(2, 2)];
+/// let mut life = HashLife::new(Rule::CONWAY, glider);
+/// life.step(4_000_000);
+/// assert!(life.alive().contains(&Coord(1_000_002, 1_000_002)));
+///
+/// life.step(4_000_000_000_000);
+/// assert_eq!(life.population(), 5);
+/// ```
+pub struct HashLife {
+    rule: Rule,
+    nodes: Vec<Node>,
+    index: HashMap<[u32; 4], u32>,
+    /// The middle half of a node after `2^j` generations, keyed by node and `j`
+    results: HashMap<(u32, u8), u32>,
+    /// An empty node of each level
+    empty: Vec<u32>,
+    /// The whole universe, centered on the origin
+    root: u32,
+    generation: u64,
+}
+
+impl HashLife {
+    /// Start from the given live cells
+    pub fn new(rule: Rule, alive: impl IntoIterator<Item = Coord>) -> Self {
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+
+        let mut life = Self {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            generation: 0,
+        };
+        life.root = life.empty(3);
+
+        for c in alive {
+            let reach = c.0.unsigned_abs().max(c.1.unsigned_abs()) as u64 + 1;
+            while life.half_width() < reach {
+                life.root = life.expand(life.root);
+            }
+            let half = life.half_width() as i64;
+            life.root = life.set(life.root, c.0 as i64 + half, c.1 as i64 + half);
+        }
+
+        life
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    /// Run for `generations` steps
+    ///
+    /// # Panics
+    /// Panics if the universe would have to grow past `2^62` cells across. Jumping `2^j`
+    /// generations needs a universe `2^(j + 3)` across, so this happens for `generations` of
+    /// `2^60` or more, or sooner for patterns that have spread that far.
+    pub fn step(&mut self, generations: u64) {
+        for j in 0..u64::BITS as u8 {
+            if generations & (1 << j) == 0 {
+                continue;
+            }
+
+            // Pad the pattern so it can't spread outside the part of the root that's kept
+            while self.level(self.root) < j + 3 || !self.padded(self.root) {
+                assert!(
+                    self.level(self.root) < MAX_LEVEL,
+                    "universe can't grow past 2^{} cells across",
+                    MAX_LEVEL
+                );
+                self.root = self.expand(self.root);
+            }
+            self.root = self.successor(self.root, j);
+            self.generation = self
+                .generation
+                .checked_add(1 << j)
+                .expect("generation overflowed a u64");
+        }
+    }
+
+    /// Coordinates of the live cells, row by row. Since coordinates are `i32`, this panics if
+    /// the pattern has moved out of range.
+    pub fn alive(&self) -> Vec<Coord> {
+        let mut cells = Vec::new();
+        let half = self.half_width() as i64;
+        self.collect(self.root, -half, -half, &mut cells);
+
+        let mut alive: Vec<Coord> = cells
+            .into_iter()
+            .map(|(r, c)| {
+                let (Ok(r), Ok(c)) = (i32::try_from(r), i32::try_from(c)) else {
+                    panic!("cell ({}, {}) doesn't fit in a Coord", r, c);
+                };
+                Coord(r, c)
+            })
+            .collect();
+        alive.sort_by_key(|c| (c.0, c.1));
+        alive
+    }
+
+    fn level(&self, node: u32) -> u8 {
+        self.nodes[node as usize].level
+    }
+
+    fn children(&self, node: u32) -> [u32; 4] {
+        self.nodes[node as usize].children
+    }
+
+    /// Distance from the center of the universe to its edges
+    fn half_width(&self) -> u64 {
+        1 << (self.level(self.root) - 1)
+    }
+
+    /// The node made of the given quadrants
+    fn join(&mut self, children: [u32; 4]) -> u32 {
+        if let Some(&node) = self.index.get(&children) {
+            return node;
+        }
+
+        let node = Node {
+            level: self.level(children[0]) + 1,
+            children,
+            population: children
+                .iter()
+                .map(|&c| self.nodes[c as usize].population)
+                .sum(),
+        };
+        self.nodes.push(node);
+        let id = (self.nodes.len() - 1) as u32;
+        self.index.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> u32 {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().unwrap();
+            let node = self.join([below; 4]);
+            self.empty.push(node);
+        }
+        self.empty[level as usize]
+    }
+
+    /// The node one level up with `node` in the middle
+    fn expand(&mut self, node: u32) -> u32 {
+        let [nw, ne, sw, se] = self.children(node);
+        let border = self.empty(self.level(node) - 1);
+
+        let nw = self.join([border, border, border, nw]);
+        let ne = self.join([border, border, ne, border]);
+        let sw = self.join([border, sw, border, border]);
+        let se = self.join([se, border, border, border]);
+        self.join([nw, ne, sw, se])
+    }
+
+    /// Whether all of a node's live cells are in its middle quarter
+    fn padded(&self, node: u32) -> bool {
+        let [nw, ne, sw, se] = self.children(node);
+        let inner = [(nw, 3), (ne, 2), (sw, 1), (se, 0)]
+            .into_iter()
+            .map(|(quadrant, toward_middle)| {
+                let child = self.children(quadrant)[toward_middle];
+                let grandchild = self.children(child)[toward_middle];
+                self.nodes[grandchild as usize].population
+            })
+            .sum::<u64>();
+
+        inner == self.nodes[node as usize].population
+    }
+
+    /// `node` with the cell at `(row, col)` from its top left set alive
+    fn set(&mut self, node: u32, row: i64, col: i64) -> u32 {
+        let level = self.level(node);
+        if level == 0 {
+            return ALIVE;
+        }
+
+        let half = 1 << (level - 1);
+        let quadrant = (row >= half) as usize * 2 + (col >= half) as usize;
+        let mut children = self.children(node);
+        children[quadrant] = self.set(children[quadrant], row % half, col % half);
+        self.join(children)
+    }
+
+    fn collect(&self, node: u32, row: i64, col: i64, cells: &mut Vec<(i64, i64)>) {
+        let n = self.nodes[node as usize];
+        if n.population == 0 {
+            return;
+        }
+        if n.level == 0 {
+            cells.push((row, col));
+            return;
+        }
+
+        let half = 1 << (n.level - 1);
+        let [nw, ne, sw, se] = n.children;
+        self.collect(nw, row, col, cells);
+        self.collect(ne, row, col + half, cells);
+        self.collect(sw, row + half, col, cells);
+        self.collect(se, row + half, col + half, cells);
+    }
+
+    /// The middle quarter of a node, one level down
+    fn center(&mut self, node: u32) -> u32 {
+        let [nw, ne, sw, se] = self.children(node);
+        self.join([
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ])
+    }
+
+    /// The node straddling the boundary between two side by side nodes
+    fn between_horizontal(&mut self, west: u32, east: u32) -> u32 {
+        let [_, w_ne, _, w_se] = self.children(west);
+        let [e_nw, _, e_sw, _] = self.children(east);
+        self.join([w_ne, e_nw, w_se, e_sw])
+    }
+
+    /// The node straddling the boundary between two stacked nodes
+    fn between_vertical(&mut self, north: u32, south: u32) -> u32 {
+        let [_, _, n_sw, n_se] = self.children(north);
+        let [s_nw, s_ne, _, _] = self.children(south);
+        self.join([n_sw, n_se, s_nw, s_ne])
+    }
+
+    /// The middle half of `node` after `2^j` generations, where `j <= level - 2`
+    fn successor(&mut self, node: u32, j: u8) -> u32 {
+        let n = self.nodes[node as usize];
+        if n.population == 0 {
+            return self.empty(n.level - 1);
+        }
+        if let Some(&result) = self.results.get(&(node, j)) {
+            return result;
+        }
+
+        let result = if n.level == 2 {
+            self.step_4x4(node)
+        } else {
+            let [nw, ne, sw, se] = n.children;
+            let center = self.center(node);
+            let subnodes = [
+                nw,
+                self.between_horizontal(nw, ne),
+                ne,
+                self.between_vertical(nw, sw),
+                center,
+                self.between_vertical(ne, se),
+                sw,
+                self.between_horizontal(sw, se),
+                se,
+            ];
+
+            // At full speed both halves of the jump step forward, otherwise the first half
+            // just takes the middle of each subnode
+            let full_speed = j == n.level - 2;
+            let second = if full_speed { j - 1 } else { j };
+            let mut r = [DEAD; 9];
+            for (i, &subnode) in subnodes.iter().enumerate() {
+                r[i] = if full_speed {
+                    self.successor(subnode, j - 1)
+                } else {
+                    self.center(subnode)
+                };
+            }
+
+            let quadrants = [
+                [r[0], r[1], r[3], r[4]],
+                [r[1], r[2], r[4], r[5]],
+                [r[3], r[4], r[6], r[7]],
+                [r[4], r[5], r[7], r[8]],
+            ];
+            let mut stepped = [DEAD; 4];
+            for (i, quadrant) in quadrants.into_iter().enumerate() {
+                let joined = self.join(quadrant);
+                stepped[i] = self.successor(joined, second);
+            }
+            self.join(stepped)
+        };
+
+        self.results.insert((node, j), result);
+        result
+    }
+
+    /// The middle 2x2 of a 4x4 node after one generation
+    fn step_4x4(&mut self, node: u32) -> u32 {
+        let mut grid = [[false; 4]; 4];
+        for (q, &quadrant) in self.children(node).iter().enumerate() {
+            for (c, &cell) in self.children(quadrant).iter().enumerate() {
+                grid[q / 2 * 2 + c / 2][q % 2 * 2 + c % 2] = cell == ALIVE;
+            }
+        }
+
+        let mut cells = [DEAD; 4];
+        for (i, cell) in cells.iter_mut().enumerate() {
+            let (row, col) = (1 + i / 2, 1 + i % 2);
+            let neighbours = (row - 1..=row + 1)
+                .flat_map(|r| (col - 1..=col + 1).map(move |c| (r, c)))
+                .filter(|&(r, c)| (r, c) != (row, col) && grid[r][c])
+                .count() as u32;
+
+            if self.rule.next(grid[row][col], neighbours) {
+                *cell = ALIVE;
+            }
+        }
+        self.join(cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn naive_step(rule: Rule, alive: &HashSet<Coord>) -> HashSet<Coord> {
+        let mut counts: HashMap<Coord, u32> = HashMap::new();
+        for c in alive {
+            for dr in -1..=1 {
+                for dc in -1..=1 {
+                    if (dr, dc) != (0, 0) {
+                        *counts.entry(Coord(c.0 + dr, c.1 + dc)).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        counts
+            .into_iter()
+            .filter(|(c, n)| rule.next(alive.contains(c), *n))
+            .map(|(c, _)| c)
+            .collect()
+    }
+
+    fn sorted(cells: &HashSet<Coord>) -> Vec<Coord> {
+        let mut cells: Vec<Coord> = cells.iter().copied().collect();
+        cells.sort_by_key(|c| (c.0, c.1));
+        cells
+    }
+
+    fn check_against_naive(rule: Rule, pattern: &[Coord], jumps: &[u64]) {
+        let mut life = HashLife::new(rule, pattern.iter().copied());
+        let mut naive: HashSet<Coord> = pattern.iter().copied().collect();
+
+        for &jump in jumps {
+            life.step(jump);
+            for _ in 0..jump {
+                naive = naive_step(rule, &naive);
+            }
+            assert_eq!(
+                life.alive(),
+                sorted(&naive),
+                "generation {}",
+                life.generation()
+            );
+        }
+    }
+
+    #[test]
+    fn test_r_pentomino() {
+        let r_pentomino = [
+            Coord(0, 1),
+            Coord(0, 2),
+            Coord(1, 0),
+            Coord(1, 1),
+            Coord(2, 1),
+        ];
+        check_against_naive(Rule::CONWAY, &r_pentomino, &[1, 1, 2, 5, 8, 13, 64, 100]);
+    }
+
+    #[test]
+    fn test_highlife_replicator() {
+        let replicator = [
+            Coord(-2, 0),
+            Coord(-2, 1),
+            Coord(-2, 2),
+            Coord(-1, -1),
+            Coord(-1, 2),
+            Coord(0, -2),
+            Coord(0, 2),
+            Coord(1, -2),
+            Coord(1, 1),
+            Coord(2, -2),
+            Coord(2, -1),
+            Coord(2, 0),
+        ];
+        let highlife = Rule::parse("B36/S23").unwrap();
+        check_against_naive(highlife, &replicator, &[3, 12, 48, 33]);
+    }
+
+    #[test]
+    fn test_far_apart_cells() {
+        // A blinker far from the origin, and a lone cell that dies
+        let cells = [
+            Coord(-5000, 7000),
+            Coord(-5000, 7001),
+            Coord(-5000, 7002),
+            Coord(3, 3),
+        ];
+        let mut life = HashLife::new(Rule::CONWAY, cells);
+
+        life.step(1);
+        assert_eq!(
+            life.alive(),
+            vec![Coord(-5001, 7001), Coord(-5000, 7001), Coord(-4999, 7001)]
+        );
+
+        life.step(1_000_001);
+        assert_eq!(life.population(), 3);
+        assert_eq!(life.generation(), 1_000_002);
+        assert_eq!(life.alive()[0], Coord(-5000, 7000));
+    }
+
+    #[test]
+    fn test_longest_step() {
+        let block = [Coord(0, 0), Coord(0, 1), Coord(1, 0), Coord(1, 1)];
+        let mut life = HashLife::new(Rule::CONWAY, block);
+        life.step((1 << 60) - 1);
+        assert_eq!(life.alive(), block);
+        assert_eq!(life.generation(), (1 << 60) - 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "universe can't grow past 2^62 cells across")]
+    fn test_step_too_far() {
+        let mut life = HashLife::new(Rule::CONWAY, [Coord(0, 0)]);
+        life.step(1 << 60);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut life = HashLife::new(Rule::CONWAY, []);
+        life.step(1 << 50);
+        assert_eq!(life.population(), 0);
+        assert!(life.alive().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+/// Where the puzzle input for a run should be read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    Path(PathBuf),
+}
+
+impl InputSource {
+    /// Interpret a command line input argument for the given part.
+    ///
+    /// * `-` reads from stdin
+    /// * `example` picks the example for the part, see [`example_path`]
+    /// * `input`, and other example names like `example2` or `example_part2`, are
+    ///   shorthands for the matching `.txt` file in the current directory, falling back to
+    ///   the crate directory when run through `cargo run` from elsewhere (eg. the workspace root)
+    /// * Anything else is treated as a path
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::input::InputSource;
+    /// use std::path::PathBuf;
+    ///
+    /// assert_eq!(InputSource::from_arg("-", "1"), InputSource::Stdin);
+    /// assert_eq!(
+    ///     InputSource::from_arg("/tmp/stress.txt", "1"),
+    ///     InputSource::Path(PathBuf::from("/tmp/stress.txt"))
+    /// );
+    /// ```
+    pub fn from_arg(arg: &str, part: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            "example" => InputSource::Path(example_path(part)),
+            _ if arg == "input" || is_example_name(arg) => {
+                InputSource::Path(local_file(&format!("{}.txt", arg)))
+            }
+            _ => InputSource::Path(PathBuf::from(arg)),
+        }
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+            InputSource::Path(path) => std::fs::read_to_string(path),
+        }
+    }
+}
+
+/// Whether `name` looks like an alternate example name, eg. `example2` or `example_part2`
+fn is_example_name(name: &str) -> bool {
+    name.strip_prefix("example")
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+/// Pick the example file for a part. Puzzles often give a different example for part 2,
+/// so this looks for `example_part{part}.txt` then `example{part}.txt`, falling back to
+/// `example.txt`.
+pub fn example_path(part: &str) -> PathBuf {
+    [
+        format!("example_part{}.txt", part),
+        format!("example{}.txt", part),
+    ]
+    .iter()
+    .map(|name| local_file(name))
+    .find(|path| path.exists())
+    .unwrap_or_else(|| local_file("example.txt"))
+}
+
+/// Resolve a file name relative to the current directory, or to the crate being run
+/// if it doesn't exist there.
+fn local_file(name: &str) -> PathBuf {
+    let path = PathBuf::from(name);
+    if path.exists() {
+        return path;
+    }
+
+    // Set by `cargo run`, so `cargo run -p day01 -- 1 input` works from the workspace root
+    match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) if PathBuf::from(&dir).join(name).exists() => PathBuf::from(dir).join(name),
+        _ => path,
+    }
+}
+
+/// Read the input named by a command line argument for a part. See [`InputSource::from_arg`].
+pub fn read_input(arg: &str, part: &str) -> io::Result<String> {
+    InputSource::from_arg(arg, part).read()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_names() {
+        assert!(is_example_name("example2"));
+        assert!(is_example_name("example_part2"));
+        assert!(!is_example_name("example.txt"));
+        assert!(!is_example_name("examples/big"));
+
+        assert_eq!(
+            InputSource::from_arg("example.txt", "1"),
+            InputSource::Path(PathBuf::from("example.txt"))
+        );
+    }
+}
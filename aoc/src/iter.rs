@@ -0,0 +1,205 @@
+/// Every pair of items at different positions, in both orders
+///
+/// # Examples
+/// ```
+/// use aoc::iter::pairs;
+///
+/// let pairs: Vec<_> = pairs(&['a', 'b', 'c']).collect();
+/// assert_eq!(
+///     pairs,
+///     vec![(&'a', &'b'), (&'a', &'c'), (&'b', &'a'), (&'b', &'c'), (&'c', &'a'), (&'c', &'b')]
+/// );
+/// ```
+pub fn pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items.iter().enumerate().flat_map(move |(i, a)| {
+        items
+            .iter()
+            .enumerate()
+            .filter(move |&(j, _)| i != j)
+            .map(move |(_, b)| (a, b))
+    })
+}
+
+/// Every pair of items at different positions, each pair once with the earlier item first
+///
+/// # Examples
+/// ```
+/// use aoc::iter::unordered_pairs;
+///
+/// // Two entries summing to 2020
+/// let entries = [1721, 979, 366, 299, 675, 1456];
+/// let (a, b) = unordered_pairs(&entries).find(|(a, b)| *a + *b == 2020).unwrap();
+///
+/// assert_eq!(a * b, 514579);
+/// assert_eq!(unordered_pairs(&entries).count(), 15);
+/// ```
+pub fn unordered_pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, a)| items[i + 1..].iter().map(move |b| (a, b)))
+}
+
+/// Every way of choosing `k` items, keeping them in their original order. Combinations come in
+/// lexicographic order of the positions chosen.
+///
+/// # Examples
+/// ```
+/// use aoc::iter::combinations;
+///
+/// // Three entries summing to 2020
+/// let entries = [1721, 979, 366, 299, 675, 1456];
+/// let three = combinations(&entries, 3)
+///     .find(|c| c.iter().copied().sum::<i32>() == 2020)
+///     .unwrap();
+///
+/// assert_eq!(three, vec![&979, &366, &675]);
+/// assert_eq!(combinations(&entries, 0).count(), 1);
+/// assert_eq!(combinations(&entries, 7).count(), 0);
+/// ```
+pub fn combinations<T>(items: &[T], k: usize) -> impl Iterator<Item = Vec<&T>> {
+    let n = items.len();
+    let mut indices: Option<Vec<usize>> = (k <= n).then(|| (0..k).collect());
+
+    std::iter::from_fn(move || {
+        let current = indices.as_mut()?;
+        let combination = current.iter().map(|&i| &items[i]).collect();
+
+        // Advance the rightmost position that still has room, and reset the ones after it
+        match (0..k).rev().find(|&i| current[i] < n - k + i) {
+            Some(i) => {
+                current[i] += 1;
+                for j in i + 1..k {
+                    current[j] = current[j - 1] + 1;
+                }
+            }
+            None => indices = None,
+        }
+
+        Some(combination)
+    })
+}
+
+/// Every ordering of `k` different items, in lexicographic order of their positions. Items
+/// are told apart by position, so equal items give repeated permutations.
+///
+/// # Examples
+/// ```
+/// use aoc::iter::permutations;
+///
+/// let orders: Vec<String> = permutations(&['a', 'b', 'c'], 3)
+///     .map(|p| p.into_iter().collect())
+///     .collect();
+/// assert_eq!(orders, vec!["abc", "acb", "bac", "bca", "cab", "cba"]);
+///
+/// assert_eq!(permutations(&[1, 2, 3, 4], 2).count(), 12);
+/// ```
+pub fn permutations<T>(items: &[T], k: usize) -> impl Iterator<Item = Vec<&T>> {
+    let n = items.len();
+    let mut indices: Option<Vec<usize>> = (k <= n).then(|| (0..n).collect());
+
+    std::iter::from_fn(move || {
+        let current = indices.as_mut()?;
+        let permutation = current[..k].iter().map(|&i| &items[i]).collect();
+
+        // Reversing the unused tail makes the next full permutation also the next one of the
+        // first k positions
+        current[k..].reverse();
+        if !next_permutation(current) {
+            indices = None;
+        }
+
+        Some(permutation)
+    })
+}
+
+/// Rearrange into the next permutation in lexicographic order, returning false if it was
+/// already the last one
+fn next_permutation(indices: &mut [usize]) -> bool {
+    let Some(i) = (1..indices.len())
+        .rev()
+        .find(|&i| indices[i - 1] < indices[i])
+    else {
+        return false;
+    };
+    let j = (i..indices.len())
+        .rev()
+        .find(|&j| indices[j] > indices[i - 1])
+        .unwrap();
+    indices.swap(i - 1, j);
+    indices[i..].reverse();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binomial(n: usize, k: usize) -> usize {
+        if k > n {
+            return 0;
+        }
+        (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    #[test]
+    fn test_counts() {
+        for n in 0..=6 {
+            let items: Vec<usize> = (0..n).collect();
+            assert_eq!(pairs(&items).count(), n * n.saturating_sub(1));
+            assert_eq!(unordered_pairs(&items).count(), binomial(n, 2));
+
+            for k in 0..=n + 1 {
+                let falling = (0..k).fold(1, |acc, i| acc * n.saturating_sub(i));
+                assert_eq!(
+                    combinations(&items, k).count(),
+                    binomial(n, k),
+                    "{} {}",
+                    n,
+                    k
+                );
+                assert_eq!(permutations(&items, k).count(), falling, "{} {}", n, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_order() {
+        let items = [0, 1, 2, 3, 4];
+        for k in 0..=items.len() {
+            let combos: Vec<Vec<&i32>> = combinations(&items, k).collect();
+            assert!(combos.windows(2).all(|w| w[0] < w[1]));
+            assert!(combos.iter().all(|c| c.windows(2).all(|w| w[0] < w[1])));
+
+            let perms: Vec<Vec<&i32>> = permutations(&items, k).collect();
+            assert!(perms.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn test_seating() {
+        // 2015 day 13, the happiness of everyone sitting next to each other around a table
+        let happiness = |a: char, b: char| match (a.min(b), a.max(b)) {
+            ('A', 'B') => 54 + 83,
+            ('A', 'C') => -79 - 62,
+            ('A', 'D') => -2 + 46,
+            ('B', 'C') => -7 + 60,
+            ('B', 'D') => -63 - 7,
+            ('C', 'D') => 55 + 41,
+            _ => unreachable!(),
+        };
+
+        // Fixing the first seat, since rotations of the table are the same arrangement
+        let best = permutations(&['B', 'C', 'D'], 3)
+            .map(|rest| {
+                let table: Vec<char> = std::iter::once('A')
+                    .chain(rest.into_iter().copied())
+                    .collect();
+                (0..table.len())
+                    .map(|i| happiness(table[i], table[(i + 1) % table.len()]))
+                    .sum::<i32>()
+            })
+            .max();
+        assert_eq!(best, Some(330));
+    }
+}
@@ -1,2 +1,47 @@
+pub mod algo;
+pub mod answers;
+pub mod beam;
+pub mod bitmask;
+pub mod bytes;
+pub mod cards;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod config;
+pub mod debug_view;
+pub mod distances;
+pub mod error;
+pub mod exact_cover;
+pub mod font;
+pub mod geninput;
+pub mod geom;
+pub mod graph;
 pub mod grid_2d;
+pub mod grid_3d;
+pub mod hashlife;
+pub mod input;
+pub mod iter;
+pub mod math;
+pub mod memo;
+pub mod memory;
+pub mod modular;
+pub mod optimize;
+pub mod pack;
+pub mod parse;
+pub mod patrol;
+pub mod polyomino;
+pub mod progress;
+pub mod range;
+pub mod rng;
+pub mod runner;
+pub mod schedule;
+pub mod search;
+pub mod terrain;
+pub mod testing;
+pub mod timing;
 pub mod visualize;
+pub mod water;
+
+pub use error::{Error, Result};
+
+#[doc(hidden)]
+pub use inventory;
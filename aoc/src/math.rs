@@ -0,0 +1,520 @@
+use num::traits::CheckedNeg;
+use num::{BigInt, BigRational, CheckedMul, Integer, Zero};
+use std::cmp::Ordering;
+
+/// Greatest common divisor of all the values, which is 0 if there aren't any
+///
+/// # Examples
+/// ```
+/// use aoc::math::gcd_all;
+///
+/// assert_eq!(gcd_all([12, 18, -30]), 6);
+/// assert_eq!(gcd_all(Vec::<u32>::new()), 0);
+/// ```
+pub fn gcd_all<T>(values: impl IntoIterator<Item = T>) -> T
+where
+    T: Integer + Clone,
+{
+    values
+        .into_iter()
+        .fold(T::zero(), |gcd, value| gcd.gcd(&value))
+}
+
+/// Least common multiple of all the values, which is 1 if there aren't any, or `None` if it
+/// doesn't fit in `T`
+///
+/// # Examples
+/// ```
+/// use aoc::math::checked_lcm_all;
+///
+/// assert_eq!(checked_lcm_all([4u8, 6, 10]), Some(60));
+/// assert_eq!(checked_lcm_all([16u8, 17]), None);
+/// ```
+pub fn checked_lcm_all<T>(values: impl IntoIterator<Item = T>) -> Option<T>
+where
+    T: Integer + CheckedMul + CheckedNeg + Clone,
+{
+    values.into_iter().try_fold(T::one(), |lcm, value| {
+        // Once it's 0 it stays 0, and the gcd of 0 and `T::MIN` would overflow
+        if value.is_zero() || lcm.is_zero() {
+            return Some(T::zero());
+        }
+        let gcd = lcm.gcd(&value);
+        // Non-negative like `Integer::lcm`, and dividing first keeps the product smaller
+        let step = (value / gcd).checked_mul(&lcm)?;
+        if step < T::zero() {
+            step.checked_neg()
+        } else {
+            Some(step)
+        }
+    })
+}
+
+/// Least common multiple of all the values, which is 1 if there aren't any. This is when
+/// cycles of each length all line up, see [`lcm_all_wide`] if that doesn't fit in `T`.
+///
+/// # Panics
+/// Panics if the result doesn't fit in `T`, rather than wrapping around
+///
+/// # Examples
+/// ```
+/// use aoc::math::lcm_all;
+///
+/// // Ghosts with cycles of 2, 3, and 4 steps all reach the end together after 12
+/// assert_eq!(lcm_all([2, 3, 4]), 12);
+/// ```
+pub fn lcm_all<T>(values: impl IntoIterator<Item = T>) -> T
+where
+    T: Integer + CheckedMul + CheckedNeg + Clone,
+{
+    checked_lcm_all(values).expect("least common multiple overflowed, try lcm_all_wide")
+}
+
+/// [`lcm_all`] computed as an `i128`, for when the inputs fit in 64 bits but the answer
+/// might not
+///
+/// # Examples
+/// ```
+/// use aoc::math::lcm_all_wide;
+///
+/// let primes = [4_294_967_291u32, 4_294_967_279, 4_294_967_231];
+/// assert_eq!(
+///     lcm_all_wide(primes),
+///     4_294_967_291 * 4_294_967_279 * 4_294_967_231i128
+/// );
+/// ```
+pub fn lcm_all_wide<T>(values: impl IntoIterator<Item = T>) -> i128
+where
+    T: Into<i128>,
+{
+    lcm_all(values.into_iter().map(Into::into))
+}
+
+/// Solve the square system of linear equations `coefficients * x = constants` exactly, using
+/// Gaussian elimination over rationals. Returns None if the system is singular, meaning it has
+/// no solution or infinitely many.
+///
+/// # Panics
+/// Panics if `coefficients` isn't square with a row for each constant
+///
+/// # Examples
+/// ```
+/// use aoc::math::solve_linear_system;
+/// use num::BigRational;
+///
+/// // 2x + y = 5, x - 3y = -1
+/// let solution = solve_linear_system(&[vec![2, 1], vec![1, -3]], &[5, -1]).unwrap();
+/// assert_eq!(
+///     solution,
+///     vec![BigRational::from_integer(2.into()), BigRational::from_integer(1.into())]
+/// );
+///
+/// // Not whole numbers, but still exact
+/// let solution = solve_linear_system(&[vec![3]], &[1]).unwrap();
+/// assert_eq!(solution[0], BigRational::new(1.into(), 3.into()));
+///
+/// // The same line twice
+/// assert_eq!(solve_linear_system(&[vec![1, 1], vec![2, 2]], &[1, 2]), None);
+/// ```
+pub fn solve_linear_system<T>(coefficients: &[Vec<T>], constants: &[T]) -> Option<Vec<BigRational>>
+where
+    T: Clone + Into<BigInt>,
+{
+    let n = constants.len();
+    assert!(
+        coefficients.len() == n && coefficients.iter().all(|row| row.len() == n),
+        "expected {} equations of {} unknowns",
+        n,
+        n
+    );
+
+    // Augmented matrix, with the constants as the last column
+    let rational = |value: &T| BigRational::from_integer(value.clone().into());
+    let mut rows: Vec<Vec<BigRational>> = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, constant)| row.iter().chain([constant]).map(rational).collect())
+        .collect();
+
+    for col in 0..n {
+        // Any non-zero pivot will do, since there's no rounding error to worry about
+        let pivot = (col..n).find(|&r| !rows[r][col].is_zero())?;
+        rows.swap(col, pivot);
+
+        let pivot_row = std::mem::take(&mut rows[col]);
+        let pivot_value = pivot_row[col].clone();
+        let pivot_row: Vec<BigRational> = pivot_row.iter().map(|x| x / &pivot_value).collect();
+
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == col || row[col].is_zero() {
+                continue;
+            }
+            let factor = row[col].clone();
+            for (x, p) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *x -= &factor * p;
+            }
+        }
+        rows[col] = pivot_row;
+    }
+
+    Some(rows.into_iter().map(|mut row| row.pop().unwrap()).collect())
+}
+
+/// `gcd(a, b)` as a non-negative number along with `x` and `y` such that `a*x + b*y = gcd(a, b)`
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Every integer solution of `a*x + b*y = c`, which are `(x + k*step_x, y + k*step_y)` for any
+/// integer `k`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Diophantine {
+    pub x: i128,
+    pub y: i128,
+    pub step_x: i128,
+    pub step_y: i128,
+}
+
+impl Diophantine {
+    /// The `k`th solution
+    pub fn at(&self, k: i128) -> (i128, i128) {
+        (self.x + k * self.step_x, self.y + k * self.step_y)
+    }
+
+    /// The solution with `x` and `y` both non-negative that minimizes `cost_x*x + cost_y*y`,
+    /// preferring the smallest `x` on ties. Returns None if there isn't a non-negative solution,
+    /// or if there's no cheapest one because the cost is unbounded below.
+    pub fn cheapest(&self, cost_x: i128, cost_y: i128) -> Option<(i128, i128)> {
+        // The range of k where both are non-negative, with None for unbounded
+        let (mut lo, mut hi): (Option<i128>, Option<i128>) = (None, None);
+        for (start, step) in [(self.x, self.step_x), (self.y, self.step_y)] {
+            match step.cmp(&0) {
+                Ordering::Greater => {
+                    let bound = Integer::div_ceil(&-start, &step);
+                    lo = Some(lo.map_or(bound, |lo| lo.max(bound)));
+                }
+                Ordering::Less => {
+                    let bound = Integer::div_floor(&start, &-step);
+                    hi = Some(hi.map_or(bound, |hi| hi.min(bound)));
+                }
+                Ordering::Equal if start < 0 => return None,
+                Ordering::Equal => {}
+            }
+        }
+        if let (Some(lo), Some(hi)) = (lo, hi) {
+            if lo > hi {
+                return None;
+            }
+        }
+
+        // The cost changes linearly with k, so the cheapest is at one end of the range
+        let k = match (cost_x * self.step_x + cost_y * self.step_y).cmp(&0) {
+            Ordering::Greater => lo?,
+            Ordering::Less => hi?,
+            Ordering::Equal if self.step_x < 0 => hi.or(lo).unwrap_or(0),
+            Ordering::Equal => lo.or(hi).unwrap_or(0),
+        };
+        Some(self.at(k))
+    }
+}
+
+/// Solve `a*x + b*y = c` in integers. Returns None if there's no solution, or if `a` and `b`
+/// are both 0 so that the solutions aren't a line. Uses `i128` throughout so the solutions and
+/// steps can't overflow.
+///
+/// # Examples
+/// ```
+/// use aoc::math::solve_diophantine;
+///
+/// let solutions = solve_diophantine(6, 10, 28).unwrap();
+/// for k in -5..5 {
+///     let (x, y) = solutions.at(k);
+///     assert_eq!(6 * x + 10 * y, 28);
+/// }
+///
+/// // Fewest coins making 28 out of 6s and 10s
+/// assert_eq!(solutions.cheapest(1, 1), Some((3, 1)));
+///
+/// // Everything on the left is even
+/// assert_eq!(solve_diophantine(6, 10, 7), None);
+/// ```
+pub fn solve_diophantine(a: i64, b: i64, c: i64) -> Option<Diophantine> {
+    let (a, b, c) = (a as i128, b as i128, c as i128);
+    if a == 0 && b == 0 {
+        return None;
+    }
+
+    let (gcd, x, y) = extended_gcd(a, b);
+    if c % gcd != 0 {
+        return None;
+    }
+    let scale = c / gcd;
+    Some(Diophantine {
+        x: x * scale,
+        y: y * scale,
+        step_x: b / gcd,
+        step_y: -a / gcd,
+    })
+}
+
+/// The cheapest non-negative number of times to add vectors `a` and `b` to reach `target`,
+/// where each costs `cost[0]` and `cost[1]` respectively, as in the claw machines of 2024
+/// day 13. Returns None if the target can't be reached.
+///
+/// When `a` and `b` aren't parallel there's at most one way to do it. When they are, this
+/// falls back to [`solve_diophantine`] along their shared direction, preferring fewer presses
+/// of `a` on ties.
+///
+/// # Examples
+/// ```
+/// use aoc::math::cheapest_combination;
+///
+/// // A costs 3 tokens and B costs 1
+/// assert_eq!(
+///     cheapest_combination([94, 34], [22, 67], [8400, 5400], [3, 1]),
+///     Some((80, 40))
+/// );
+/// assert_eq!(cheapest_combination([26, 66], [67, 21], [12748, 12176], [3, 1]), None);
+///
+/// // Parallel buttons, where B is cheaper per step
+/// assert_eq!(cheapest_combination([1, 1], [3, 3], [10, 10], [1, 2]), Some((1, 3)));
+/// ```
+pub fn cheapest_combination(
+    a: [i64; 2],
+    b: [i64; 2],
+    target: [i64; 2],
+    cost: [i64; 2],
+) -> Option<(i128, i128)> {
+    let [a0, a1, b0, b1, t0, t1] = [a[0], a[1], b[0], b[1], target[0], target[1]].map(i128::from);
+
+    let det = a0 * b1 - a1 * b0;
+    if det != 0 {
+        // Cramer's rule
+        let n = t0 * b1 - t1 * b0;
+        let m = a0 * t1 - a1 * t0;
+        if n % det != 0 || m % det != 0 {
+            return None;
+        }
+        let (n, m) = (n / det, m / det);
+        return (n >= 0 && m >= 0).then_some((n, m));
+    }
+
+    // The buttons are parallel, so the target has to be along the same line
+    if t0 * a1 - t1 * a0 != 0 || t0 * b1 - t1 * b0 != 0 {
+        return None;
+    }
+    // Then either equation implies the other, as long as it isn't 0 = 0
+    let row = if a[0] != 0 || b[0] != 0 { 0 } else { 1 };
+    if a[row] == 0 && b[row] == 0 {
+        return (target == [0, 0]).then_some((0, 0));
+    }
+    solve_diophantine(a[row], b[row], target[row])?.cheapest(cost[0].into(), cost[1].into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_matches_pairwise() {
+        let values = [-6i64, 10, 15, 35, -49];
+        for n in 0..=values.len() {
+            let values = &values[..n];
+            let lcm = values.iter().fold(1, |lcm, v| num::integer::lcm(lcm, *v));
+            let gcd = values.iter().fold(0, |gcd, v| num::integer::gcd(gcd, *v));
+
+            assert_eq!(lcm_all(values.iter().copied()), lcm);
+            assert_eq!(gcd_all(values.iter().copied()), gcd);
+        }
+
+        assert_eq!(lcm_all([3, 0, 5]), 0);
+        assert_eq!(checked_lcm_all([i64::MAX, 2]), None);
+        assert_eq!(checked_lcm_all([i64::MIN]), None);
+        assert_eq!(checked_lcm_all([3, i64::MIN]), None);
+        assert_eq!(checked_lcm_all([0, i64::MIN]), Some(0));
+        assert_eq!(checked_lcm_all([i8::MIN + 1, 2]), None);
+        assert_eq!(lcm_all_wide([i64::MAX, 2]), i64::MAX as i128 * 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn test_overflow() {
+        lcm_all([u64::MAX, u64::MAX - 1]);
+    }
+
+    #[test]
+    fn test_random_systems() {
+        let mut rng = Rng::new(1072);
+        for n in 1..=6 {
+            for _ in 0..20 {
+                let coefficients: Vec<Vec<i64>> = (0..n)
+                    .map(|_| (0..n).map(|_| rng.range(-5, 5)).collect())
+                    .collect();
+                let x: Vec<i64> = (0..n).map(|_| rng.range(-100, 100)).collect();
+                let constants: Vec<i64> = coefficients
+                    .iter()
+                    .map(|row| row.iter().zip(&x).map(|(a, b)| a * b).sum())
+                    .collect();
+
+                // Singular random matrices give None, the rest have to give back x
+                if let Some(solution) = solve_linear_system(&coefficients, &constants) {
+                    let expected: Vec<BigRational> = x
+                        .iter()
+                        .map(|&v| BigRational::from_integer(v.into()))
+                        .collect();
+                    assert_eq!(solution, expected);
+                }
+
+                // Repeating an equation always makes it singular
+                let mut repeated = coefficients.clone();
+                repeated[n - 1] = repeated[0].clone();
+                let mut constants = constants;
+                constants[n - 1] = constants[0];
+                if n > 1 {
+                    assert_eq!(solve_linear_system(&repeated, &constants), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hailstones() {
+        // 2023 day 24 part 2. For the rock at P with velocity V to hit hailstone i,
+        // (P - p_i) x (V - v_i) = 0. The P x V term is the same for every hailstone, so taking
+        // the difference for two hailstones leaves 3 equations that are linear in P and V:
+        // P x (v_j - v_i) + (p_j - p_i) x V = p_j x v_j - p_i x v_i
+        let hail: [([i64; 3], [i64; 3]); 3] = [
+            ([19, 13, 30], [-2, 1, -2]),
+            ([18, 19, 22], [-1, -1, -2]),
+            ([20, 25, 34], [-2, -2, -4]),
+        ];
+        let cross = |a: [i64; 3], b: [i64; 3]| {
+            [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]
+        };
+
+        let mut coefficients = Vec::new();
+        let mut constants = Vec::new();
+        let (p0, v0) = hail[0];
+        for &(p, v) in &hail[1..] {
+            let w = [v[0] - v0[0], v[1] - v0[1], v[2] - v0[2]];
+            let u = [p[0] - p0[0], p[1] - p0[1], p[2] - p0[2]];
+            coefficients.push(vec![0, w[2], -w[1], 0, -u[2], u[1]]);
+            coefficients.push(vec![-w[2], 0, w[0], u[2], 0, -u[0]]);
+            coefficients.push(vec![w[1], -w[0], 0, -u[1], u[0], 0]);
+
+            let (a, b) = (cross(p, v), cross(p0, v0));
+            constants.extend((0..3).map(|k| a[k] - b[k]));
+        }
+
+        let solution = solve_linear_system(&coefficients, &constants).unwrap();
+        let solution: Vec<i64> = solution
+            .iter()
+            .map(|x| {
+                assert!(x.is_integer());
+                x.to_integer().try_into().unwrap()
+            })
+            .collect();
+        assert_eq!(solution, vec![24, 13, 10, -3, 1, 2]);
+        assert_eq!(solution[..3].iter().sum::<i64>(), 47);
+    }
+
+    #[test]
+    fn test_diophantine_solutions() {
+        let mut rng = Rng::new(1073);
+        for _ in 0..2000 {
+            let (a, b, c) = (rng.range(-12, 12), rng.range(-12, 12), rng.range(-50, 50));
+            let Some(solutions) = solve_diophantine(a, b, c) else {
+                let gcd = num::integer::gcd(a, b);
+                assert!(gcd == 0 || c % gcd != 0, "{} {} {}", a, b, c);
+                continue;
+            };
+
+            for k in -3..=3 {
+                let (x, y) = solutions.at(k);
+                assert_eq!(a as i128 * x + b as i128 * y, c as i128);
+            }
+
+            // Consecutive solutions are as close together as they can be
+            let gcd = num::integer::gcd(a, b) as i128;
+            assert_eq!(solutions.step_x, b as i128 / gcd);
+            assert_eq!(solutions.step_y, -a as i128 / gcd);
+        }
+    }
+
+    #[test]
+    fn test_cheapest_combination_matches_brute_force() {
+        let mut rng = Rng::new(13);
+        for _ in 0..2000 {
+            let a = [rng.range(0, 4), rng.range(0, 4)];
+            let b = [rng.range(0, 4), rng.range(0, 4)];
+            // Small multiples of a direction, to hit the parallel cases often
+            let target = if rng.chance(0.5) {
+                [rng.range(0, 40), rng.range(0, 40)]
+            } else {
+                let scale = rng.range(0, 12);
+                [a[0] * scale, a[1] * scale]
+            };
+            let cost = [rng.range(1, 4), rng.range(1, 4)];
+
+            // Presses beyond 40 can only help if a button does nothing, in which case they
+            // just cost more
+            let best = (0..=40)
+                .flat_map(|n| (0..=40).map(move |m| (n, m)))
+                .filter(|&(n, m)| (0..2).all(|i| n * a[i] + m * b[i] == target[i]))
+                .map(|(n, m)| n * cost[0] + m * cost[1])
+                .min();
+
+            let found = cheapest_combination(a, b, target, cost);
+            let found_cost = found.map(|(n, m)| {
+                assert!(n >= 0 && m >= 0);
+                for i in 0..2 {
+                    assert_eq!(n * a[i] as i128 + m * b[i] as i128, target[i] as i128);
+                }
+                (n * cost[0] as i128 + m * cost[1] as i128) as i64
+            });
+            assert_eq!(found_cost, best, "{:?} {:?} {:?} {:?}", a, b, target, cost);
+        }
+    }
+
+    #[test]
+    fn test_claw_machines() {
+        // 2024 day 13
+        let machines = [
+            ([94, 34], [22, 67], [8400, 5400]),
+            ([26, 66], [67, 21], [12748, 12176]),
+            ([17, 86], [84, 37], [7870, 6450]),
+            ([69, 23], [27, 71], [18641, 10279]),
+        ];
+        let tokens = |offset: i64| -> i128 {
+            machines
+                .iter()
+                .filter_map(|&(a, b, prize)| {
+                    let prize = prize.map(|p| p + offset);
+                    cheapest_combination(a, b, prize, [3, 1])
+                })
+                .map(|(n, m)| 3 * n + m)
+                .sum()
+        };
+
+        assert_eq!(tokens(0), 480);
+        assert_eq!(tokens(10_000_000_000_000), 875318608908);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cache of results for memoized recursion. The function computing a value gets the memo
+/// back, so it can look up the smaller cases it depends on.
+///
+/// # Examples
+/// ```
+/// use aoc::memo::Memo;
+///
+/// // 2024 day 19, the number of ways to make a design out of towels
+/// fn arrangements(memo: &mut Memo<String, u64>, towels: &[&str], design: &str) -> u64 {
+///     if design.is_empty() {
+///         return 1;
+///     }
+///     memo.get_or_compute(design.to_string(), |memo, _| {
+///         towels
+///             .iter()
+///             .filter_map(|towel| design.strip_prefix(towel))
+///             .map(|rest| arrangements(memo, towels, rest))
+///             .sum()
+///     })
+/// }
+///
+/// let towels = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+/// let mut memo = Memo::new();
+/// assert_eq!(arrangements(&mut memo, &towels, "gbbr"), 4);
+/// assert_eq!(arrangements(&mut memo, &towels, "rrbgbr"), 6);
+/// assert_eq!(arrangements(&mut memo, &towels, "ubwu"), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The value for `key`, calling `f` to compute it the first time. `f` is given the memo so
+    /// it can recurse; nothing is borrowed while it runs.
+    pub fn get_or_compute(&mut self, key: K, f: impl FnOnce(&mut Self, &K) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+        let value = f(self, &key);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// The value for `key`, if it's already been computed
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Number of values computed so far
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_computes_once() {
+        fn fibonacci(memo: &mut Memo<u64, u64>, calls: &mut u64, n: u64) -> u64 {
+            if n < 2 {
+                return n;
+            }
+            memo.get_or_compute(n, |memo, &n| {
+                *calls += 1;
+                fibonacci(memo, calls, n - 1) + fibonacci(memo, calls, n - 2)
+            })
+        }
+
+        let mut memo = Memo::new();
+        let mut calls = 0;
+        assert_eq!(fibonacci(&mut memo, &mut calls, 90), 2880067194370816120);
+        assert_eq!(calls, 89);
+        assert_eq!(memo.len(), 89);
+        assert_eq!(memo.get(&10), Some(&55));
+
+        // Already known, so nothing new is computed
+        assert_eq!(fibonacci(&mut memo, &mut calls, 80), 23416728348467685);
+        assert_eq!(calls, 89);
+    }
+
+    #[test]
+    fn test_stones() {
+        // 2024 day 11, the number of stones one stone turns into after some blinks
+        fn stones(memo: &mut Memo<(u64, u32), u64>, stone: u64, blinks: u32) -> u64 {
+            if blinks == 0 {
+                return 1;
+            }
+            memo.get_or_compute((stone, blinks), |memo, _| {
+                let digits = stone.checked_ilog10().unwrap_or(0) + 1;
+                if stone == 0 {
+                    stones(memo, 1, blinks - 1)
+                } else if digits.is_multiple_of(2) {
+                    let half = 10u64.pow(digits / 2);
+                    stones(memo, stone / half, blinks - 1) + stones(memo, stone % half, blinks - 1)
+                } else {
+                    stones(memo, stone * 2024, blinks - 1)
+                }
+            })
+        }
+
+        let mut memo = Memo::new();
+        let count = |memo: &mut Memo<_, _>, blinks| -> u64 {
+            [125, 17].iter().map(|&s| stones(memo, s, blinks)).sum()
+        };
+        assert_eq!(count(&mut memo, 6), 22);
+        assert_eq!(count(&mut memo, 25), 55312);
+
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Memory usage measurement for solutions.
+//!
+//! Peak RSS is read from `/proc` on Linux, and reset before each measurement so that it covers
+//! just the code being measured. On other platforms it isn't available.
+//!
+//! With the `track-alloc` feature, a counting global allocator is installed as well, which also
+//! reports the number of heap allocations and the peak heap size.
+
+#[cfg(feature = "track-alloc")]
+pub use tracking::{TrackingAllocator, ALLOCATOR};
+
+/// Memory used while running a piece of code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Peak resident set size of the process, in bytes
+    pub peak_rss: Option<u64>,
+    /// Number of heap allocations made, with the `track-alloc` feature
+    pub allocations: Option<u64>,
+    /// Peak heap usage in bytes, with the `track-alloc` feature
+    pub peak_heap: Option<u64>,
+}
+
+/// Reset the kernel's peak RSS counter for this process, returning whether it worked
+fn reset_peak_rss() -> bool {
+    // Writing 5 to clear_refs resets VmHWM, supported since Linux 4.0
+    std::fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// Read a size in bytes from `/proc/self/status`, eg. `VmHWM`
+fn status_bytes(field: &str) -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kb: u64 = status
+        .lines()
+        .find_map(|line| line.strip_prefix(field)?.strip_prefix(':'))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+
+    Some(kb * 1024)
+}
+
+/// Peak resident set size of the process in bytes, if the platform supports it
+pub fn peak_rss() -> Option<u64> {
+    status_bytes("VmHWM")
+}
+
+/// Current resident set size of the process in bytes, if the platform supports it
+pub fn current_rss() -> Option<u64> {
+    status_bytes("VmRSS")
+}
+
+/// Run `f`, measuring the memory it uses
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, MemoryUsage) {
+    let rss_reset = reset_peak_rss();
+    #[cfg(feature = "track-alloc")]
+    ALLOCATOR.reset();
+
+    let result = f();
+
+    let usage = MemoryUsage {
+        // Without a reset this would include anything that ran before `f`
+        peak_rss: if rss_reset { peak_rss() } else { None },
+        #[cfg(feature = "track-alloc")]
+        allocations: Some(ALLOCATOR.allocations()),
+        #[cfg(feature = "track-alloc")]
+        peak_heap: Some(ALLOCATOR.peak()),
+        #[cfg(not(feature = "track-alloc"))]
+        allocations: None,
+        #[cfg(not(feature = "track-alloc"))]
+        peak_heap: None,
+    };
+
+    (result, usage)
+}
+
+/// Format a number of bytes for humans, eg. `12.3 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(feature = "track-alloc")]
+mod tracking {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Wraps the system allocator, counting allocations and tracking the peak heap size
+    pub struct TrackingAllocator {
+        allocations: AtomicU64,
+        current: AtomicU64,
+        peak: AtomicU64,
+    }
+
+    #[global_allocator]
+    pub static ALLOCATOR: TrackingAllocator = TrackingAllocator {
+        allocations: AtomicU64::new(0),
+        current: AtomicU64::new(0),
+        peak: AtomicU64::new(0),
+    };
+
+    impl TrackingAllocator {
+        /// Reset the allocation count, and the peak to the current heap size
+        pub fn reset(&self) {
+            self.allocations.store(0, Ordering::Relaxed);
+            self.peak
+                .store(self.current.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+
+        pub fn allocations(&self) -> u64 {
+            self.allocations.load(Ordering::Relaxed)
+        }
+
+        pub fn peak(&self) -> u64 {
+            self.peak.load(Ordering::Relaxed)
+        }
+
+        fn grow(&self, bytes: u64) {
+            let current = self.current.fetch_add(bytes, Ordering::Relaxed) + bytes;
+            self.peak.fetch_max(current, Ordering::Relaxed);
+        }
+
+        fn shrink(&self, bytes: u64) {
+            self.current.fetch_sub(bytes, Ordering::Relaxed);
+        }
+    }
+
+    unsafe impl GlobalAlloc for TrackingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                self.allocations.fetch_add(1, Ordering::Relaxed);
+                self.grow(layout.size() as u64);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            self.shrink(layout.size() as u64);
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc_zeroed(layout) };
+            if !ptr.is_null() {
+                self.allocations.fetch_add(1, Ordering::Relaxed);
+                self.grow(layout.size() as u64);
+            }
+            ptr
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+            if !new_ptr.is_null() {
+                self.allocations.fetch_add(1, Ordering::Relaxed);
+                self.shrink(layout.size() as u64);
+                self.grow(new_size as u64);
+            }
+            new_ptr
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(2048), "2.0 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 + 300 * 1024), "5.3 MiB");
+    }
+
+    #[test]
+    fn test_measure() {
+        let (sum, usage) = measure(|| {
+            let v: Vec<u64> = (0..1_000_000).collect();
+            v.iter().sum::<u64>()
+        });
+
+        assert_eq!(sum, 499999500000);
+        if let Some(peak) = usage.peak_rss {
+            assert!(peak >= 8_000_000);
+        }
+        if let Some(peak) = usage.peak_heap {
+            assert!(peak >= 8_000_000);
+        }
+    }
+
+    #[test]
+    fn test_current_rss() {
+        // The peak can be reset by other tests at any time, so there's not much to compare it to
+        if let Some(current) = current_rss() {
+            assert!(current > 0);
+        }
+    }
+}
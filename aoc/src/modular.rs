@@ -0,0 +1,141 @@
+/// A position on a dial of `modulus` positions, `0..modulus`, which wraps around when turned
+/// past either end. Keeps count of how often it passes through 0.
+///
+/// # Examples
+/// ```
+/// use aoc::modular::ModularCounter;
+///
+/// let mut dial = ModularCounter::new(100, 50);
+///
+/// assert_eq!(dial.advance(-68), 1);
+/// assert_eq!(dial.position(), 82);
+/// assert_eq!(dial.advance(318), 4);
+/// assert_eq!(dial.position(), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModularCounter {
+    modulus: i64,
+    position: i64,
+}
+
+impl ModularCounter {
+    /// A counter starting at `start`, which is wrapped into range
+    ///
+    /// # Panics
+    /// Panics if `modulus` isn't positive
+    pub fn new(modulus: i64, start: i64) -> Self {
+        assert!(modulus > 0, "Modulus must be positive, got {}", modulus);
+
+        Self {
+            modulus,
+            position: start.rem_euclid(modulus),
+        }
+    }
+
+    pub fn modulus(&self) -> i64 {
+        self.modulus
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Turn by `delta` steps, positive upwards and negative downwards, one step at a time.
+    /// Returns how many of those steps landed on 0, including the last but not the starting
+    /// position.
+    pub fn advance(&mut self, delta: i64) -> u64 {
+        // Steps needed to first reach 0 in the direction of travel. Already being at 0 doesn't
+        // count, so from 0 that's a full turn.
+        let to_zero = if delta >= 0 {
+            self.modulus - self.position
+        } else if self.position == 0 {
+            self.modulus
+        } else {
+            self.position
+        };
+
+        let steps = delta.unsigned_abs();
+        let crossings = if steps >= to_zero as u64 {
+            (steps - to_zero as u64) / self.modulus as u64 + 1
+        } else {
+            0
+        };
+
+        self.position = (self.position + delta.rem_euclid(self.modulus)) % self.modulus;
+        crossings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    /// Turn one step at a time, counting zeros
+    fn naive(counter: &mut ModularCounter, delta: i64) -> u64 {
+        let mut crossings = 0;
+        for _ in 0..delta.abs() {
+            counter.position = (counter.position + delta.signum()).rem_euclid(counter.modulus);
+            crossings += (counter.position == 0) as u64;
+        }
+        crossings
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let mut dial = ModularCounter::new(100, 0);
+        assert_eq!(dial.advance(0), 0);
+        assert_eq!(dial.advance(-5), 0);
+        assert_eq!(dial.advance(5), 1);
+        assert_eq!(dial.advance(100), 1);
+        assert_eq!(dial.advance(-100), 1);
+        assert_eq!(dial.advance(-250), 2);
+        assert_eq!(dial.position(), 50);
+        assert_eq!(dial.advance(-50), 1);
+        assert_eq!(dial.advance(99), 0);
+        assert_eq!(dial.advance(1), 1);
+    }
+
+    #[test]
+    fn test_wraps_start() {
+        assert_eq!(ModularCounter::new(100, -1).position(), 99);
+        assert_eq!(ModularCounter::new(100, 250).position(), 50);
+    }
+
+    #[test]
+    fn test_modulus_one() {
+        // Every step lands on 0
+        let mut counter = ModularCounter::new(1, 0);
+        assert_eq!(counter.advance(7), 7);
+        assert_eq!(counter.advance(-3), 3);
+    }
+
+    #[test]
+    fn test_matches_naive() {
+        let mut rng = Rng::new(1032);
+        for _ in 0..2000 {
+            let modulus = rng.range(1, 20);
+            let start = rng.range(0, modulus - 1);
+            let delta = rng.range(-60, 60);
+
+            let mut counter = ModularCounter::new(modulus, start);
+            let mut expected = counter;
+
+            assert_eq!(
+                counter.advance(delta),
+                naive(&mut expected, delta),
+                "{} from {} by {}",
+                modulus,
+                start,
+                delta
+            );
+            assert_eq!(counter, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Modulus must be positive")]
+    fn test_zero_modulus() {
+        ModularCounter::new(0, 0);
+    }
+}
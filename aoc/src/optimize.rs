@@ -0,0 +1,240 @@
+//! Heuristic optimization for puzzles where exact search is infeasible and a good-enough
+//! answer is acceptable. All functions minimize `energy`, and are reproducible for a given seed.
+
+use crate::rng::Rng;
+
+/// Temperature schedule for simulated annealing, cooling geometrically from `start_temp`
+/// down to `end_temp` over `iterations` steps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Schedule {
+    pub start_temp: f64,
+    pub end_temp: f64,
+    pub iterations: usize,
+}
+
+impl Schedule {
+    pub fn new(start_temp: f64, end_temp: f64, iterations: usize) -> Self {
+        Self {
+            start_temp,
+            end_temp,
+            iterations,
+        }
+    }
+
+    /// Temperature at a given iteration
+    pub fn temperature(&self, iteration: usize) -> f64 {
+        if self.iterations <= 1 {
+            return self.end_temp;
+        }
+
+        let progress = iteration as f64 / (self.iterations - 1) as f64;
+        self.start_temp * (self.end_temp / self.start_temp).powf(progress)
+    }
+}
+
+/// Simulated annealing. Starting from `initial`, repeatedly proposes a neighbouring state and
+/// moves to it if it's better, or with probability `exp(-delta / temperature)` if it's worse.
+///
+/// Returns the best state seen and its energy.
+///
+/// # Examples
+/// ```
+/// use aoc::optimize::{anneal, Schedule};
+///
+/// let (best, energy) = anneal(
+///     0i64,
+///     |x, rng| x + rng.range(-10, 10),
+///     |x| ((x - 1234) * (x - 1234)) as f64,
+///     Schedule::new(1000.0, 0.01, 20_000),
+///     7,
+/// );
+///
+/// assert_eq!(best, 1234);
+/// assert_eq!(energy, 0.0);
+/// ```
+pub fn anneal<S, N, E>(
+    initial: S,
+    mut neighbour: N,
+    energy: E,
+    schedule: Schedule,
+    seed: u64,
+) -> (S, f64)
+where
+    S: Clone,
+    N: FnMut(&S, &mut Rng) -> S,
+    E: Fn(&S) -> f64,
+{
+    let mut rng = Rng::new(seed);
+
+    let mut current_energy = energy(&initial);
+    let mut current = initial;
+    let mut best = (current.clone(), current_energy);
+
+    for i in 0..schedule.iterations {
+        let candidate = neighbour(&current, &mut rng);
+        let candidate_energy = energy(&candidate);
+        let delta = candidate_energy - current_energy;
+
+        let temp = schedule.temperature(i);
+        let accept = delta <= 0.0 || (temp > 0.0 && rng.chance((-delta / temp).exp()));
+
+        if accept {
+            current = candidate;
+            current_energy = candidate_energy;
+
+            if current_energy < best.1 {
+                best = (current.clone(), current_energy);
+            }
+        }
+    }
+
+    best
+}
+
+/// Hill climbing: only ever moves to a neighbour that is at least as good. Gives up after
+/// `patience` consecutive proposals fail to improve, or after `iterations` proposals total.
+///
+/// Returns the final state and its energy.
+pub fn hill_climb<S, N, E>(
+    initial: S,
+    mut neighbour: N,
+    energy: E,
+    iterations: usize,
+    patience: usize,
+    seed: u64,
+) -> (S, f64)
+where
+    N: FnMut(&S, &mut Rng) -> S,
+    E: Fn(&S) -> f64,
+{
+    let mut rng = Rng::new(seed);
+
+    let mut current_energy = energy(&initial);
+    let mut current = initial;
+    let mut stale = 0;
+
+    for _ in 0..iterations {
+        let candidate = neighbour(&current, &mut rng);
+        let candidate_energy = energy(&candidate);
+
+        if candidate_energy < current_energy {
+            stale = 0;
+        } else {
+            stale += 1;
+            if stale >= patience {
+                break;
+            }
+        }
+
+        if candidate_energy <= current_energy {
+            current = candidate;
+            current_energy = candidate_energy;
+        }
+    }
+
+    (current, current_energy)
+}
+
+/// Run [`hill_climb`] from several random starting points, keeping the best result. Each
+/// restart uses its own seed derived from `seed`.
+pub fn hill_climb_restarts<S, I, N, E>(
+    mut initial: I,
+    mut neighbour: N,
+    energy: E,
+    restarts: usize,
+    iterations: usize,
+    patience: usize,
+    seed: u64,
+) -> Option<(S, f64)>
+where
+    I: FnMut(&mut Rng) -> S,
+    N: FnMut(&S, &mut Rng) -> S,
+    E: Fn(&S) -> f64,
+{
+    let mut seeds = Rng::new(seed);
+
+    (0..restarts)
+        .map(|_| {
+            let restart_seed = seeds.next_u64();
+            let start = initial(&mut Rng::new(restart_seed));
+            hill_climb(
+                start,
+                &mut neighbour,
+                &energy,
+                iterations,
+                patience,
+                restart_seed,
+            )
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A bumpy function with a local minimum at every multiple of 7, and a global minimum of
+    /// 0 at x = 500
+    fn bumpy(x: &i64) -> f64 {
+        let d = (x - 500).abs() as f64;
+        let bump: f64 = if x % 7 == 0 { 0.0 } else { 5.0 };
+        d + bump.min(d)
+    }
+
+    fn smooth(x: &i64) -> f64 {
+        ((x - 500) * (x - 500)) as f64
+    }
+
+    #[test]
+    fn test_schedule() {
+        let schedule = Schedule::new(100.0, 1.0, 3);
+
+        assert_eq!(schedule.temperature(0), 100.0);
+        assert!((schedule.temperature(1) - 10.0).abs() < 1e-9);
+        assert_eq!(schedule.temperature(2), 1.0);
+    }
+
+    #[test]
+    fn test_anneal_is_reproducible() {
+        let run = |seed| {
+            anneal(
+                0i64,
+                |x, rng| x + rng.range(-3, 3),
+                bumpy,
+                Schedule::new(50.0, 0.1, 5000),
+                seed,
+            )
+        };
+
+        assert_eq!(run(3), run(3));
+        assert_eq!(run(3).1, 0.0);
+    }
+
+    #[test]
+    fn test_hill_climb() {
+        let (x, energy) = hill_climb(0i64, |x, rng| x + rng.range(-1, 1), smooth, 10_000, 100, 1);
+
+        assert_eq!(x, 500);
+        assert_eq!(energy, 0.0);
+
+        // Small steps get stuck in the first local minimum of a bumpy function
+        let (x, _) = hill_climb(0i64, |x, rng| x + rng.range(-1, 1), bumpy, 10_000, 100, 1);
+        assert_eq!(x, 0);
+    }
+
+    #[test]
+    fn test_hill_climb_restarts() {
+        let (x, _) = hill_climb_restarts(
+            |rng| rng.range(-1000, 1000),
+            |x, rng| x + rng.range(-1, 1),
+            smooth,
+            5,
+            10_000,
+            100,
+            1,
+        )
+        .unwrap();
+
+        assert_eq!(x, 500);
+    }
+}
@@ -0,0 +1,233 @@
+//! Bit-packing of small search states into a single integer key, for use in visited sets.
+//!
+//! Fields are pushed into a [`Packer`] and popped back off an [`Unpacker`] in the same order.
+//! Debug builds check that every value fits in the bits allotted to it. Fields can be up to 64
+//! bits wide and keys up to 128 bits, which is checked in every build.
+//!
+//! # Examples
+//! ```
+//! use aoc::grid_2d::{Coord, Dir};
+//! use aoc::pack::{Packer, Unpacker};
+//!
+//! let size = (141, 141);
+//! let key = Packer::new()
+//!     .push_coord(Coord(12, 140), size)
+//!     .push_state(&Dir::West)
+//!     .push(7, 4)
+//!     .finish_u64();
+//!
+//! let mut unpacker = Unpacker::new(key);
+//! assert_eq!(unpacker.pop_coord(size), Coord(12, 140));
+//! assert_eq!(unpacker.pop_state::<Dir>(), Dir::West);
+//! assert_eq!(unpacker.pop(4), 7);
+//! ```
+
+use crate::grid_2d::{Coord, Dir};
+
+/// Number of bits needed to represent every value in `0..n_values`
+pub fn bits_for(n_values: usize) -> u32 {
+    if n_values <= 1 {
+        0
+    } else {
+        usize::BITS - (n_values - 1).leading_zeros()
+    }
+}
+
+/// A type with a fixed-width bit representation that doesn't depend on any context
+pub trait PackState: Sized {
+    const BITS: u32;
+
+    fn to_bits(&self) -> u64;
+
+    fn from_bits(bits: u64) -> Self;
+}
+
+impl PackState for bool {
+    const BITS: u32 = 1;
+
+    fn to_bits(&self) -> u64 {
+        *self as u64
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        bits != 0
+    }
+}
+
+impl PackState for Dir {
+    const BITS: u32 = 3;
+
+    fn to_bits(&self) -> u64 {
+        Dir::all().iter().position(|d| d == self).unwrap() as u64
+    }
+
+    fn from_bits(bits: u64) -> Self {
+        Dir::all()[bits as usize]
+    }
+}
+
+/// Builder that packs fields into a key, starting from the least significant bits
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Packer {
+    key: u128,
+    bits: u32,
+}
+
+impl Packer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the lowest `bits` bits of `value`
+    ///
+    /// # Panics
+    /// Panics if `bits` is over 64, or if the key would be more than 128 bits
+    pub fn push(mut self, value: u64, bits: u32) -> Self {
+        assert!(bits <= 64, "fields can be at most 64 bits, not {}", bits);
+        assert!(self.bits + bits <= 128, "packed state exceeds 128 bits");
+        debug_assert!(
+            bits == 64 || value >> bits == 0,
+            "value {} does not fit in {} bits",
+            value,
+            bits
+        );
+
+        if bits > 0 {
+            self.key |= (value as u128) << self.bits;
+            self.bits += bits;
+        }
+        self
+    }
+
+    /// Append a coordinate bounded by a board of the given (rows, cols) size
+    pub fn push_coord(self, c: Coord, size: (usize, usize)) -> Self {
+        debug_assert!(
+            c.0 >= 0 && c.1 >= 0 && (c.0 as usize) < size.0 && (c.1 as usize) < size.1,
+            "coordinate {:?} is outside of size {:?}",
+            c,
+            size
+        );
+
+        self.push(c.0 as u64, bits_for(size.0))
+            .push(c.1 as u64, bits_for(size.1))
+    }
+
+    pub fn push_state<S: PackState>(self, state: &S) -> Self {
+        self.push(state.to_bits(), S::BITS)
+    }
+
+    /// Total number of bits used so far
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    pub fn finish(self) -> u128 {
+        self.key
+    }
+
+    /// # Panics
+    /// Panics if more than 64 bits were pushed
+    pub fn finish_u64(self) -> u64 {
+        assert!(self.bits <= 64, "packed state uses {} bits", self.bits);
+        self.key as u64
+    }
+}
+
+/// Reads fields back out of a key built by [`Packer`], in the order they were pushed
+#[derive(Debug, Clone, Copy)]
+pub struct Unpacker {
+    key: u128,
+}
+
+impl Unpacker {
+    pub fn new<K: Into<u128>>(key: K) -> Self {
+        Self { key: key.into() }
+    }
+
+    /// # Panics
+    /// Panics if `bits` is over 64
+    pub fn pop(&mut self, bits: u32) -> u64 {
+        assert!(bits <= 64, "fields can be at most 64 bits, not {}", bits);
+        if bits == 0 {
+            return 0;
+        }
+
+        let value = self.key & ((1u128 << bits) - 1);
+        self.key >>= bits;
+        value as u64
+    }
+
+    pub fn pop_coord(&mut self, size: (usize, usize)) -> Coord {
+        let row = self.pop(bits_for(size.0));
+        let col = self.pop(bits_for(size.1));
+        Coord(row as i32, col as i32)
+    }
+
+    pub fn pop_state<S: PackState>(&mut self) -> S {
+        S::from_bits(self.pop(S::BITS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_for() {
+        assert_eq!(bits_for(0), 0);
+        assert_eq!(bits_for(1), 0);
+        assert_eq!(bits_for(2), 1);
+        assert_eq!(bits_for(8), 3);
+        assert_eq!(bits_for(9), 4);
+        assert_eq!(bits_for(141), 8);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let size = (5, 300);
+
+        for (coord, dir, flag) in [
+            (Coord(0, 0), Dir::North, false),
+            (Coord(4, 299), Dir::NorthWest, true),
+            (Coord(2, 17), Dir::South, true),
+        ] {
+            let key = Packer::new()
+                .push_state(&flag)
+                .push_coord(coord, size)
+                .push_state(&dir)
+                .finish();
+
+            let mut unpacker = Unpacker::new(key);
+            assert_eq!(unpacker.pop_state::<bool>(), flag);
+            assert_eq!(unpacker.pop_coord(size), coord);
+            assert_eq!(unpacker.pop_state::<Dir>(), dir);
+        }
+    }
+
+    #[test]
+    fn test_full_width() {
+        let key = Packer::new().push(u64::MAX, 64).push(3, 2).finish();
+
+        let mut unpacker = Unpacker::new(key);
+        assert_eq!(unpacker.pop(64), u64::MAX);
+        assert_eq!(unpacker.pop(2), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_finish_u64_overflow() {
+        Packer::new().push(1, 40).push(1, 40).finish_u64();
+    }
+
+    #[test]
+    #[should_panic(expected = "fields can be at most 64 bits, not 65")]
+    fn test_field_too_wide() {
+        Packer::new().push(1, 65);
+    }
+
+    #[test]
+    #[should_panic(expected = "packed state exceeds 128 bits")]
+    fn test_key_too_wide() {
+        Packer::new().push(0, 64).push(0, 64).push(0, 1);
+    }
+}
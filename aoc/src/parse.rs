@@ -0,0 +1,724 @@
+use std::any::type_name;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[cfg(feature = "regex")]
+pub use regex::{Captures, Regex};
+
+/// A line of input that couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number, when known
+    pub line: Option<usize>,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(text: &str, reason: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            text: text.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(
+                f,
+                "failed to parse line {}: {:?} ({})",
+                line, self.text, self.reason
+            ),
+            None => write!(f, "failed to parse {:?} ({})", self.text, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Split an instruction like `R42` or `U-3` into its leading letter and number
+///
+/// # Examples
+/// ```
+/// use aoc::parse::prefixed_int;
+///
+/// assert_eq!(prefixed_int("L68").unwrap(), ('L', 68));
+/// assert_eq!(prefixed_int("U-3").unwrap(), ('U', -3));
+/// assert!(prefixed_int("12").is_err());
+/// ```
+pub fn prefixed_int(line: &str) -> Result<(char, i64), ParseError> {
+    let line = line.trim();
+    let mut chars = line.chars();
+
+    let prefix = chars
+        .next()
+        .filter(|c| c.is_alphabetic())
+        .ok_or_else(|| ParseError::new(line, "expected a letter"))?;
+    let num =
+        chars.as_str().trim_start().parse().map_err(|e| {
+            ParseError::new(line, format!("expected a number after {}: {}", prefix, e))
+        })?;
+
+    Ok((prefix, num))
+}
+
+/// [`prefixed_int`] for every non-empty line of `input`, with errors reporting the line number
+pub fn prefixed_ints(input: &str) -> impl Iterator<Item = Result<(char, i64), ParseError>> + '_ {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| prefixed_int(line).map_err(|e| e.at_line(i + 1)))
+}
+
+/// [`prefixed_ints`] where the letter has to be one of `prefixes`, so that an unknown
+/// instruction is reported with its line like any other parse error
+///
+/// # Examples
+/// ```
+/// use aoc::parse::prefixed_ints_with;
+///
+/// let err = prefixed_ints_with("L5\nX12", &['L', 'R'])
+///     .find_map(Result::err)
+///     .unwrap();
+/// assert_eq!(err.to_string(), "failed to parse line 2: \"X12\" (expected one of L, R)");
+/// ```
+pub fn prefixed_ints_with<'a>(
+    input: &'a str,
+    prefixes: &'a [char],
+) -> impl Iterator<Item = Result<(char, i64), ParseError>> + 'a {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(move |(i, line)| {
+            let (prefix, num) = prefixed_int(line).map_err(|e| e.at_line(i + 1))?;
+            if !prefixes.contains(&prefix) {
+                let expected: Vec<String> = prefixes.iter().map(char::to_string).collect();
+                let reason = format!("expected one of {}", expected.join(", "));
+                return Err(ParseError::new(line.trim(), reason).at_line(i + 1));
+            }
+            Ok((prefix, num))
+        })
+}
+
+/// Split `input` into blocks separated by one or more blank lines, for either `\n` or `\r\n`
+/// line endings. Blocks keep their inner line breaks, and leading or trailing blank lines are
+/// dropped.
+///
+/// # Examples
+/// ```
+/// use aoc::parse::sections;
+///
+/// let input = "seeds: 79 14\r\n\r\nseed-to-soil map:\r\n50 98 2\r\n52 50 48\r\n";
+/// assert_eq!(
+///     sections(input),
+///     vec!["seeds: 79 14", "seed-to-soil map:\r\n50 98 2\r\n52 50 48"]
+/// );
+/// ```
+pub fn sections(input: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    // Byte range of the section being built
+    let mut current: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let start = offset;
+        offset += line.len();
+
+        if content.trim().is_empty() {
+            if let Some((from, to)) = current.take() {
+                sections.push(&input[from..to]);
+            }
+        } else {
+            let end = start + content.len();
+            current = Some(current.map_or((start, end), |(from, _)| (from, end)));
+        }
+    }
+
+    if let Some((from, to)) = current {
+        sections.push(&input[from..to]);
+    }
+    sections
+}
+
+/// Parse each of the blank line separated [`sections`] of `input`
+///
+/// # Examples
+/// ```
+/// use aoc::parse::{sections_map, uints};
+///
+/// let elves = sections_map("1000\n2000\n\n4000\n", |s| uints::<u32>(s).iter().sum::<u32>());
+/// assert_eq!(elves, vec![3000, 4000]);
+/// ```
+pub fn sections_map<T>(input: &str, parse: impl FnMut(&str) -> T) -> Vec<T> {
+    sections(input).into_iter().map(parse).collect()
+}
+
+/// Spans of `s` that look like numbers. A `-` directly before the digits is a sign unless it
+/// follows another digit, so ranges like `2-4` are two numbers rather than `2` and `-4`.
+fn number_spans(s: &str, signed: bool, fractional: bool) -> impl Iterator<Item = &str> + '_ {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() && !bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return None;
+        }
+
+        let mut start = i;
+        if signed && start > 0 && bytes[start - 1] == b'-' {
+            let after_digit = start > 1 && bytes[start - 2].is_ascii_digit();
+            if !after_digit {
+                start -= 1;
+            }
+        }
+
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if fractional && i + 1 < bytes.len() && bytes[i] == b'.' && bytes[i + 1].is_ascii_digit() {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+        }
+
+        Some(&s[start..i])
+    })
+}
+
+fn parse_all<T: FromStr>(spans: impl Iterator<Item = impl AsRef<str>>) -> Vec<T> {
+    spans
+        .map(|span| {
+            let span = span.as_ref();
+            span.parse()
+                .unwrap_or_else(|_| panic!("number {} doesn't fit in {}", span, type_name::<T>()))
+        })
+        .collect()
+}
+
+/// Every integer in `s`, ignoring whatever is between them. A leading `-` makes a number
+/// negative, except directly after another number (`2-4` is `[2, 4]`). Panics if a number
+/// doesn't fit in `T`.
+///
+/// # Examples
+/// ```
+/// use aoc::parse::ints;
+///
+/// assert_eq!(ints::<i64>("p=0,4 v=3,-3"), vec![0, 4, 3, -3]);
+/// assert_eq!(ints::<i32>("Button A: X+94, Y+34"), vec![94, 34]);
+/// assert_eq!(ints::<u8>("2-4,6-8"), vec![2, 4, 6, 8]);
+/// ```
+pub fn ints<T: FromStr>(s: &str) -> Vec<T> {
+    parse_all(number_spans(s, true, false))
+}
+
+/// Every run of digits in `s`, treating `-` as a separator
+///
+/// # Examples
+/// ```
+/// use aoc::parse::uints;
+///
+/// assert_eq!(uints::<u64>("#1 @ 3,-2: 5x4"), vec![1, 3, 2, 5, 4]);
+/// ```
+pub fn uints<T: FromStr>(s: &str) -> Vec<T> {
+    parse_all(number_spans(s, false, false))
+}
+
+/// Every decimal number in `s`, with an optional sign and fractional part
+///
+/// # Examples
+/// ```
+/// use aoc::parse::floats;
+///
+/// assert_eq!(floats::<f64>("at -1.5, 2 going 0.25."), vec![-1.5, 2.0, 0.25]);
+/// ```
+pub fn floats<T: FromStr>(s: &str) -> Vec<T> {
+    parse_all(number_spans(s, true, true))
+}
+
+/// A piece of a [`from_line!`](crate::from_line) template
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece<'a> {
+    Literal(String),
+    Field(&'a str),
+}
+
+/// Split a template like `#{id} @ {x},{y}` into literals and `{field}`s. `{{` and `}}` are
+/// literal braces.
+///
+/// # Panics
+/// Panics on unbalanced braces, or two fields in a row, which can't be told apart
+fn template_pieces(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' | '}' if chars.peek().map(|&(_, next)| next) == Some(c) => {
+                chars.next();
+                literal.push(c);
+            }
+            '{' => {
+                let len = template[i..]
+                    .find('}')
+                    .unwrap_or_else(|| panic!("unclosed {{ in template {:?}", template));
+                if literal.is_empty() && matches!(pieces.last(), Some(Piece::Field(_))) {
+                    panic!(
+                        "fields need a literal between them in template {:?}",
+                        template
+                    );
+                }
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(Piece::Field(&template[i + 1..i + len]));
+                while chars.next_if(|&(j, _)| j < i + len + 1).is_some() {}
+            }
+            '}' => panic!("unmatched }} in template {:?}", template),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    pieces
+}
+
+/// A [`from_line!`](crate::from_line) template split into its literals and fields, so it only
+/// has to be parsed once however many lines are matched against it
+///
+/// # Examples
+/// ```
+/// use aoc::parse::Template;
+///
+/// let template = Template::new("{name} -> {targets}");
+/// for line in ["a -> b", "broadcaster -> a, b"] {
+///     let fields = template.matches(line).unwrap();
+///     assert_eq!(fields[0].0, "name");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template<'t> {
+    text: &'t str,
+    pieces: Vec<Piece<'t>>,
+}
+
+impl<'t> Template<'t> {
+    /// # Panics
+    /// Panics on unbalanced braces, or two fields in a row, which can't be told apart
+    pub fn new(text: &'t str) -> Self {
+        Self {
+            text,
+            pieces: template_pieces(text),
+        }
+    }
+
+    /// Match `line` against the template, returning the text of each field by name. A field
+    /// runs until the first occurrence of the literal after it.
+    pub fn matches<'l>(&self, line: &'l str) -> Result<Vec<(&'t str, &'l str)>, ParseError> {
+        let template = self.text;
+        let mut fields = Vec::new();
+        let mut rest = line;
+
+        for (i, piece) in self.pieces.iter().enumerate() {
+            match piece {
+                Piece::Literal(literal) => {
+                    rest = rest.strip_prefix(literal.as_str()).ok_or_else(|| {
+                        let column = line.len() - rest.len() + 1;
+                        ParseError::new(
+                            line,
+                            format!(
+                                "expected {:?} at column {} for {:?}",
+                                literal, column, template
+                            ),
+                        )
+                    })?;
+                }
+                Piece::Field(name) => {
+                    let end = match self.pieces.get(i + 1) {
+                        Some(Piece::Literal(next)) => {
+                            rest.find(next.as_str()).ok_or_else(|| {
+                                ParseError::new(
+                                    line,
+                                    format!(
+                                        "expected {:?} after {} for {:?}",
+                                        next, name, template
+                                    ),
+                                )
+                            })?
+                        }
+                        _ => rest.len(),
+                    };
+                    fields.push((*name, &rest[..end]));
+                    rest = &rest[end..];
+                }
+            }
+        }
+
+        if !rest.is_empty() {
+            return Err(ParseError::new(
+                line,
+                format!("unexpected {:?} at the end for {:?}", rest, template),
+            ));
+        }
+
+        Ok(fields)
+    }
+}
+
+/// Match `line` against a [`from_line!`](crate::from_line) template, returning the text of
+/// each field by name. This parses the template every time, use a [`Template`] to match many
+/// lines against the same one.
+pub fn match_template<'t, 'l>(
+    template: &'t str,
+    line: &'l str,
+) -> Result<Vec<(&'t str, &'l str)>, ParseError> {
+    Template::new(template).matches(line)
+}
+
+/// Parse the field `name` out of the result of [`match_template`]
+pub fn template_field<T>(fields: &[(&str, &str)], name: &str, line: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let (_, value) = fields
+        .iter()
+        .find(|(field, _)| *field == name)
+        .ok_or_else(|| ParseError::new(line, format!("template has no {{{}}}", name)))?;
+
+    value.parse().map_err(|e| {
+        ParseError::new(
+            line,
+            format!("{} = {:?} isn't a {}: {}", name, value, type_name::<T>(), e),
+        )
+    })
+}
+
+/// Implement [`FromStr`] for a struct by matching lines against a template, where each
+/// `{field}` is parsed with the field's own [`FromStr`]. Each field extends up to the text
+/// that follows it in the template, and `{{` and `}}` stand for literal braces.
+///
+/// Errors are [`ParseError`]s naming the line and what didn't match.
+///
+/// # Examples
+/// ```
+/// use aoc::from_line;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Claim {
+///     id: u32,
+///     x: i32,
+///     y: i32,
+///     w: u32,
+///     h: u32,
+/// }
+///
+/// from_line!(Claim { id, x, y, w, h } = "#{id} @ {x},{y}: {w}x{h}");
+///
+/// let claim: Claim = "#1 @ 338,764: 20x24".parse().unwrap();
+/// assert_eq!(claim, Claim { id: 1, x: 338, y: 764, w: 20, h: 24 });
+///
+/// let err = "#1 @ 338;764: 20x24".parse::<Claim>().unwrap_err();
+/// assert!(err.to_string().contains("expected \",\" after x"));
+/// ```
+#[macro_export]
+macro_rules! from_line {
+    ($name:ident { $($field:ident),+ $(,)? } = $template:literal) => {
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::parse::ParseError;
+
+            fn from_str(line: &str) -> ::std::result::Result<Self, Self::Err> {
+                // Parsed on first use rather than for every line
+                static TEMPLATE: ::std::sync::OnceLock<$crate::parse::Template<'static>> =
+                    ::std::sync::OnceLock::new();
+                let fields = TEMPLATE
+                    .get_or_init(|| $crate::parse::Template::new($template))
+                    .matches(line)?;
+                Ok($name {
+                    $($field: $crate::parse::template_field(&fields, stringify!($field), line)?),+
+                })
+            }
+        }
+    };
+}
+
+/// Match `re` anywhere in `line`, see [`captures!`](crate::captures)
+#[cfg(feature = "regex")]
+pub fn match_line<'a>(re: &Regex, line: &'a str) -> Result<Captures<'a>, ParseError> {
+    re.captures(line)
+        .ok_or_else(|| ParseError::new(line, format!("doesn't match /{}/", re.as_str())))
+}
+
+/// Parse the capture group `name` of a [`match_line`] on `line`, see
+/// [`captures!`](crate::captures). Errors name the whole line, not just the part that matched.
+#[cfg(feature = "regex")]
+pub fn capture<T>(re: &Regex, caps: &Captures, line: &str, name: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let group = caps
+        .name(name)
+        .ok_or_else(|| ParseError::new(line, format!("no group {} in /{}/", name, re.as_str())))?;
+
+    group.as_str().parse().map_err(|e| {
+        ParseError::new(
+            line,
+            format!(
+                "group {} = {:?} of /{}/ isn't a {}: {}",
+                name,
+                group.as_str(),
+                re.as_str(),
+                type_name::<T>(),
+                e
+            ),
+        )
+    })
+}
+
+/// Match a line against a regex and parse its named capture groups with [`FromStr`], into
+/// either a tuple or a struct with fields named after the groups. Returns a [`ParseError`]
+/// naming the line and the pattern if the line doesn't match or a group doesn't parse.
+///
+/// The pattern must be a literal. It's compiled once per call site and reused.
+///
+/// Needs the `regex` feature.
+///
+/// # Examples
+/// ```
+/// use aoc::captures;
+///
+/// let line = "#1 @ 338,764: 20x24";
+/// let (id, x, y) = captures!(r"#(?<id>\d+) @ (?<x>\d+),(?<y>\d+)", line, id: u32, x: i32, y: i32)
+///     .unwrap();
+/// assert_eq!((id, x, y), (1, 338, 764));
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Size {
+///     w: usize,
+///     h: usize,
+/// }
+///
+/// let size = captures!(r"(?<w>\d+)x(?<h>\d+)$", line, Size { w: usize, h: usize }).unwrap();
+/// assert_eq!(size, Size { w: 20, h: 24 });
+///
+/// let err = captures!(r"^x=(?<x>-?\d+)$", "x=abc", x: i32).unwrap_err();
+/// assert_eq!(err.to_string(), r#"failed to parse "x=abc" (doesn't match /^x=(?<x>-?\d+)$/)"#);
+/// ```
+#[cfg(feature = "regex")]
+#[macro_export]
+macro_rules! captures {
+    ($re:literal, $line:expr, $name:ident { $($field:ident: $ty:ty),+ $(,)? }) => {
+        $crate::captures!(@match $re, $line, |re, caps, line| $name {
+            $($field: $crate::parse::capture::<$ty>(re, &caps, line, stringify!($field))?),+
+        })
+    };
+    ($re:literal, $line:expr, $($field:ident: $ty:ty),+ $(,)?) => {
+        $crate::captures!(@match $re, $line, |re, caps, line| (
+            $($crate::parse::capture::<$ty>(re, &caps, line, stringify!($field))?,)+
+        ))
+    };
+    (@match $re:literal, $line:expr, |$regex:ident, $caps:ident, $text:ident| $build:expr) => {{
+        static RE: ::std::sync::OnceLock<$crate::parse::Regex> = ::std::sync::OnceLock::new();
+        let $regex = RE.get_or_init(|| $crate::parse::Regex::new($re).expect("invalid regex"));
+        let $text: &str = $line;
+
+        $crate::parse::match_line($regex, $text).and_then(|$caps| Ok($build))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefixed_int() {
+        assert_eq!(prefixed_int("R0").unwrap(), ('R', 0));
+        assert_eq!(prefixed_int(" F 10\r").unwrap(), ('F', 10));
+
+        assert!(prefixed_int("").is_err());
+        assert!(prefixed_int("L").is_err());
+        assert!(prefixed_int("LR5").is_err());
+    }
+
+    #[test]
+    fn test_prefixed_ints() {
+        let parsed: Result<Vec<_>, _> = prefixed_ints("L68\nR48\n\nL5\n").collect();
+        assert_eq!(parsed.unwrap(), vec![('L', 68), ('R', 48), ('L', 5)]);
+
+        let err = prefixed_ints("L68\nR48\nX\n")
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(
+            err.to_string(),
+            "failed to parse line 3: \"X\" (expected a number after X: cannot parse integer from empty string)"
+        );
+        let parsed: Result<Vec<_>, _> = prefixed_ints_with("L68\n\nR48\n", &['L', 'R']).collect();
+        assert_eq!(parsed.unwrap(), vec![('L', 68), ('R', 48)]);
+        let err = prefixed_ints_with("L68\n\nU3\n", &['L', 'R'])
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.line, Some(3));
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(ints::<i64>(""), Vec::<i64>::new());
+        assert_eq!(ints::<i64>("-"), Vec::<i64>::new());
+        assert_eq!(ints::<i64>("-5"), vec![-5]);
+        assert_eq!(ints::<i64>("x=-5..-2, y=10..12"), vec![-5, -2, 10, 12]);
+        assert_eq!(ints::<i64>("1 - -2"), vec![1, -2]);
+        assert_eq!(ints::<i64>("a--3"), vec![-3]);
+        assert_eq!(ints::<u32>("Game 12: 3 blue"), vec![12, 3]);
+    }
+
+    #[test]
+    fn test_uints_and_floats() {
+        assert_eq!(uints::<usize>("x=-5..-2"), vec![5, 2]);
+        assert_eq!(floats::<f64>("1.5.2"), vec![1.5, 2.0]);
+        assert_eq!(floats::<f64>("v -3. then 4"), vec![-3.0, 4.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "number -3 doesn't fit in u32")]
+    fn test_ints_out_of_range() {
+        ints::<u32>("4 -3");
+    }
+
+    #[test]
+    fn test_sections() {
+        assert_eq!(sections(""), Vec::<&str>::new());
+        assert_eq!(sections("\n\n"), Vec::<&str>::new());
+        assert_eq!(sections("a\nb"), vec!["a\nb"]);
+        assert_eq!(sections("\n\na\n\n\n\nb\nc\n\n"), vec!["a", "b\nc"]);
+        // Whitespace-only lines separate sections too
+        assert_eq!(sections("a\n  \r\nb\r\n"), vec!["a", "b"]);
+        // Indentation at the start of a section is kept, eg. for crate stacks
+        assert_eq!(
+            sections("    [D]\n1 2\n\nmove 1"),
+            vec!["    [D]\n1 2", "move 1"]
+        );
+    }
+
+    #[test]
+    fn test_sections_map_with_errors() {
+        let parse = |s: &str| s.trim().parse::<u32>();
+
+        let parsed: Result<Vec<u32>, _> = sections_map("1\n\n2\n", parse).into_iter().collect();
+        assert_eq!(parsed.unwrap(), vec![1, 2]);
+
+        let parsed: Result<Vec<u32>, _> = sections_map("1\n\nx\n", parse).into_iter().collect();
+        assert!(parsed.is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_captures() {
+        let parse = |line: &str| {
+            crate::captures!(
+                r"^(?<name>\w+) can fly (?<speed>\d+) km/s",
+                line,
+                name: String,
+                speed: u32,
+            )
+        };
+
+        assert_eq!(
+            parse("Comet can fly 14 km/s for 10 seconds").unwrap(),
+            ("Comet".to_string(), 14)
+        );
+        assert!(parse("Comet can swim 14 km/s").is_err());
+
+        // The error names the whole line, not just the part the pattern matched
+        let err = parse("Comet can fly 99999999999 km/s for 10 seconds").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse \"Comet can fly 99999999999 km/s for 10 seconds\" (group speed = \
+             \"99999999999\" of /^(?<name>\\w+) can fly (?<speed>\\d+) km/s/ isn't a u32: number \
+             too large to fit in target type)"
+        );
+
+        let err = crate::captures!(r"(?<a>\d)", "5", a: u8, b: u8).unwrap_err();
+        assert_eq!(err.reason, r"no group b in /(?<a>\d)/");
+    }
+
+    #[test]
+    fn test_template_pieces() {
+        assert_eq!(
+            template_pieces("#{id} @ {{x}}"),
+            vec![
+                Piece::Literal("#".to_string()),
+                Piece::Field("id"),
+                Piece::Literal(" @ {x}".to_string())
+            ]
+        );
+        assert_eq!(template_pieces("{a}"), vec![Piece::Field("a")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "fields need a literal between them")]
+    fn test_template_adjacent_fields() {
+        template_pieces("{a}{b}");
+    }
+
+    #[test]
+    fn test_match_template() {
+        let template = "{name} -> {targets}";
+        assert_eq!(
+            match_template(template, "broadcaster -> a, b, c").unwrap(),
+            vec![("name", "broadcaster"), ("targets", "a, b, c")]
+        );
+
+        let err = match_template("Valve {v} has", "Valve AA had").unwrap_err();
+        assert_eq!(
+            err.reason,
+            "expected \" has\" after v for \"Valve {v} has\""
+        );
+        let err = match_template("x={x}", "y=5").unwrap_err();
+        assert_eq!(err.reason, "expected \"x=\" at column 1 for \"x={x}\"");
+        let err = match_template("x={x},", "x=5,6").unwrap_err();
+        assert!(err.reason.starts_with("unexpected \"6\" at the end"));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Move {
+        count: usize,
+        from: usize,
+        to: usize,
+    }
+
+    crate::from_line!(Move { count, from, to } = "move {count} from {from} to {to}");
+
+    #[test]
+    fn test_from_line() {
+        assert_eq!(
+            "move 3 from 1 to 2".parse::<Move>().unwrap(),
+            Move {
+                count: 3,
+                from: 1,
+                to: 2
+            }
+        );
+
+        let err = "move x from 1 to 2".parse::<Move>().unwrap_err();
+        assert!(err.reason.starts_with("count = \"x\" isn't a usize"));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::grid_2d::{Coord, Dir, Orientation};
+use crate::search::PoseSet;
+use std::thread;
+
+/// Where a guard went on patrol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// Every pose the guard was in, including each direction it turned to
+    pub poses: PoseSet,
+    /// Whether the guard ended up walking in a loop, rather than leaving the board
+    pub looped: bool,
+}
+
+impl Route {
+    /// Number of distinct cells the guard stood on
+    pub fn visited(&self) -> usize {
+        self.poses.cells().count()
+    }
+}
+
+/// Walk a guard over a board of the given (rows, cols) size, starting at `start`. The guard
+/// steps forward until the cell ahead is `blocked`, and then turns to face `turn(dir)`. The
+/// patrol ends when the guard leaves the board, or is back in a pose it's been in before.
+///
+/// `orientation` says which way north is on the board, [`Orientation::Screen`] for boards
+/// read from text.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Coord, Dir, Orientation};
+/// use aoc::patrol::patrol;
+///
+/// let board = Board::from_str(".#..\n...#\n#...\n.^#.");
+/// let start = (Coord(3, 1), Dir::North);
+/// let blocked = |c| board[c] == '#';
+///
+/// let route = patrol(board.size(), Orientation::Screen, start, Dir::rotate_right, blocked);
+/// assert!(route.looped);
+/// assert_eq!(route.visited(), 5);
+///
+/// // With north pointing down the rows instead, the guard walks straight off the board
+/// let route = patrol(board.size(), Orientation::Math, start, Dir::rotate_right, blocked);
+/// assert!(!route.looped);
+/// assert_eq!(route.visited(), 1);
+/// ```
+pub fn patrol<T, B>(
+    size: (usize, usize),
+    orientation: Orientation,
+    start: (Coord, Dir),
+    turn: T,
+    blocked: B,
+) -> Route
+where
+    T: Fn(Dir) -> Dir,
+    B: Fn(Coord) -> bool,
+{
+    let mut poses = PoseSet::new(size);
+    let looped = walk(&mut poses, orientation, start, &turn, &blocked);
+    Route { poses, looped }
+}
+
+/// Walk the guard, returning whether it looped. See [`patrol`].
+fn walk<T, B>(
+    poses: &mut PoseSet,
+    orientation: Orientation,
+    start: (Coord, Dir),
+    turn: &T,
+    blocked: &B,
+) -> bool
+where
+    T: Fn(Dir) -> Dir,
+    B: Fn(Coord) -> bool,
+{
+    let (mut c, mut dir) = start;
+    if !poses.insert(c, dir) {
+        return true;
+    }
+
+    loop {
+        let ahead = orientation.step(c, dir);
+        if !poses.in_bounds(ahead) {
+            return false;
+        }
+
+        if blocked(ahead) {
+            dir = turn(dir);
+        } else {
+            c = ahead;
+        }
+
+        if !poses.insert(c, dir) {
+            return true;
+        }
+    }
+}
+
+/// Every cell where placing one extra obstruction would trap the guard in a loop, in row
+/// order. Only cells on the guard's original route can change it, other than where the guard
+/// starts, and each of those is tried in parallel. See [`patrol`].
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Coord, Dir, Orientation};
+/// use aoc::patrol::loop_obstructions;
+///
+/// let board = Board::from_str("....\n.#..\n...#\n....\n.^..\n..#.");
+/// let loops = loop_obstructions(
+///     board.size(),
+///     Orientation::Screen,
+///     (Coord(4, 1), Dir::North),
+///     Dir::rotate_right,
+///     |c| board[c] == '#',
+/// );
+///
+/// // Turns the guard back north, onto the path it started on
+/// assert_eq!(loops, vec![Coord(4, 0)]);
+/// ```
+pub fn loop_obstructions<T, B>(
+    size: (usize, usize),
+    orientation: Orientation,
+    start: (Coord, Dir),
+    turn: T,
+    blocked: B,
+) -> Vec<Coord>
+where
+    T: Fn(Dir) -> Dir + Sync,
+    B: Fn(Coord) -> bool + Sync,
+{
+    let route = patrol(size, orientation, start, &turn, &blocked);
+    let candidates: Vec<Coord> = route.poses.cells().filter(|&c| c != start.0).collect();
+
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = candidates.len().div_ceil(workers).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let (turn, blocked) = (&turn, &blocked);
+                scope.spawn(move || {
+                    // One pose set per worker, cleared between attempts
+                    let mut poses = PoseSet::new(size);
+                    chunk
+                        .iter()
+                        .copied()
+                        .filter(|&obstruction| {
+                            poses.clear();
+                            let blocked = |c| c == obstruction || blocked(c);
+                            walk(&mut poses, orientation, start, turn, &blocked)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid_2d::Board;
+
+    const LAB: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[test]
+    fn test_lab() {
+        // 2024 day 6
+        let board = Board::from_str(LAB);
+        let start = (board.find(&'^')[0], Dir::North);
+        let blocked = |c| board[c] == '#';
+
+        let route = patrol(
+            board.size(),
+            Orientation::Screen,
+            start,
+            Dir::rotate_right,
+            blocked,
+        );
+        assert!(!route.looped);
+        assert_eq!(route.visited(), 41);
+
+        let loops = loop_obstructions(
+            board.size(),
+            Orientation::Screen,
+            start,
+            Dir::rotate_right,
+            blocked,
+        );
+        assert_eq!(loops.len(), 6);
+        assert!(loops.contains(&Coord(6, 3)));
+    }
+
+    #[test]
+    fn test_boxed_in() {
+        let board = Board::from_str(".#.\n#.#\n.#.");
+        let route = patrol(
+            board.size(),
+            Orientation::Screen,
+            (Coord(1, 1), Dir::East),
+            Dir::rotate_right,
+            |c| board[c] == '#',
+        );
+
+        assert!(route.looped);
+        assert_eq!(route.poses.len(), 4);
+        assert_eq!(route.visited(), 1);
+    }
+}
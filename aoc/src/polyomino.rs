@@ -0,0 +1,293 @@
+//! Polyomino pieces for shape fitting puzzles: normalization, rotations and reflections, and
+//! enumerating where a piece fits on a board.
+//!
+//! # Examples
+//! ```
+//! use aoc::grid_2d::Board;
+//! use aoc::polyomino::{tiling_problem, Polyomino};
+//!
+//! let pieces = [Polyomino::parse("##\n#."), Polyomino::parse(".#\n##")];
+//! let board = Board::from_size((2usize, 3usize), '.');
+//!
+//! let (problem, placements) = tiling_problem(&pieces, &board, |c| *c == '.', true);
+//! let solution = problem.solve().unwrap();
+//!
+//! assert_eq!(solution.len(), 2);
+//! assert_eq!(placements[solution[0]].cells.len(), 3);
+//! ```
+
+use crate::exact_cover::ExactCover;
+use crate::grid_2d::{Board, Coord};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+/// A piece made of cells. Always normalized so that the topmost row and leftmost column are
+/// 0, with cells sorted in reading order, so equal shapes compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polyomino {
+    cells: Vec<Coord>,
+}
+
+impl Polyomino {
+    pub fn new<I: IntoIterator<Item = Coord>>(cells: I) -> Self {
+        let mut cells: Vec<Coord> = cells.into_iter().collect();
+
+        let min_row = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let min_col = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        for c in cells.iter_mut() {
+            *c = Coord(c.0 - min_row, c.1 - min_col);
+        }
+
+        cells.sort_by_key(|c| (c.0, c.1));
+        cells.dedup();
+
+        Self { cells }
+    }
+
+    /// Parse a piece drawn with `#` for filled cells, eg. `"##\n#."`
+    pub fn parse(input: &str) -> Self {
+        Self::new(input.lines().enumerate().flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(col, _)| Coord(row as i32, col as i32))
+        }))
+    }
+
+    pub fn cells(&self) -> &[Coord] {
+        &self.cells
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Size of the bounding box, as (rows, cols)
+    pub fn size(&self) -> (usize, usize) {
+        let rows = self.cells.iter().map(|c| c.0 + 1).max().unwrap_or(0);
+        let cols = self.cells.iter().map(|c| c.1 + 1).max().unwrap_or(0);
+        (rows as usize, cols as usize)
+    }
+
+    /// Rotate 90 degrees clockwise
+    pub fn rotate(&self) -> Self {
+        Self::new(self.cells.iter().map(|c| Coord(c.1, -c.0)))
+    }
+
+    /// Mirror left to right
+    pub fn flip(&self) -> Self {
+        Self::new(self.cells.iter().map(|c| Coord(c.0, -c.1)))
+    }
+
+    /// The distinct orientations of the piece under rotation and reflection. There are at most
+    /// 8, fewer for symmetric pieces.
+    pub fn variants(&self) -> Vec<Self> {
+        let mut variants = Vec::with_capacity(8);
+
+        for start in [self.clone(), self.flip()] {
+            let mut piece = start;
+            for _ in 0..4 {
+                if !variants.contains(&piece) {
+                    variants.push(piece.clone());
+                }
+                piece = piece.rotate();
+            }
+        }
+
+        variants
+    }
+
+    /// The distinct orientations of the piece under rotation only
+    pub fn rotations(&self) -> Vec<Self> {
+        let mut rotations: Vec<Self> = Vec::with_capacity(4);
+
+        let mut piece = self.clone();
+        for _ in 0..4 {
+            if !rotations.contains(&piece) {
+                rotations.push(piece.clone());
+            }
+            piece = piece.rotate();
+        }
+
+        rotations
+    }
+
+    /// The piece's cells moved by `offset`
+    pub fn translated(&self, offset: Coord) -> impl Iterator<Item = Coord> + '_ {
+        self.cells.iter().map(move |c| c + &offset)
+    }
+
+    /// Every way to place the piece (in any orientation) on the board such that all of its
+    /// cells land on cells where `allowed` is true. Each placement is the list of board cells
+    /// it covers, and no two placements cover the same set of cells.
+    pub fn placements_on<T, P>(&self, board: &Board<T>, allowed: P) -> Vec<Vec<Coord>>
+    where
+        T: Clone,
+        P: Fn(&T) -> bool,
+    {
+        let (rows, cols) = board.size();
+        let mut placements = Vec::new();
+
+        for variant in self.variants() {
+            let (height, width) = variant.size();
+            if height > rows || width > cols {
+                continue;
+            }
+
+            for row in 0..=(rows - height) {
+                for col in 0..=(cols - width) {
+                    let cells: Vec<Coord> = variant.translated((row, col).into()).collect();
+                    if cells.iter().all(|c| board.get(c).is_some_and(&allowed)) {
+                        placements.push(cells);
+                    }
+                }
+            }
+        }
+
+        placements
+    }
+}
+
+impl Display for Polyomino {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (rows, cols) = self.size();
+        let cells: HashSet<&Coord> = self.cells.iter().collect();
+
+        for row in 0..rows {
+            let line: String = (0..cols)
+                .map(|col| {
+                    if cells.contains(&Coord(row as i32, col as i32)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A piece placed at a position, as a row of an exact cover problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Index into the pieces the problem was built from
+    pub piece: usize,
+    pub cells: Vec<Coord>,
+}
+
+/// Build an exact cover problem for placing every piece on the board, on cells where `allowed`
+/// is true. If `fill` is set every allowed cell must be covered, otherwise cells may be left
+/// empty.
+///
+/// Returns the problem along with the placement that each of its rows stands for. Identical
+/// pieces are interchangeable, so solutions will be repeated once for each way of ordering them.
+pub fn tiling_problem<T, P>(
+    pieces: &[Polyomino],
+    board: &Board<T>,
+    allowed: P,
+    fill: bool,
+) -> (ExactCover, Vec<Placement>)
+where
+    T: Clone,
+    P: Fn(&T) -> bool,
+{
+    // One column per piece, followed by one per allowed cell
+    let cell_columns: HashMap<Coord, usize> = board
+        .positions()
+        .into_iter()
+        .filter(|c| allowed(&board.get_unchecked(c)))
+        .enumerate()
+        .map(|(i, c)| (c, pieces.len() + i))
+        .collect();
+
+    let mut problem = if fill {
+        ExactCover::new(pieces.len() + cell_columns.len(), 0)
+    } else {
+        ExactCover::new(pieces.len(), cell_columns.len())
+    };
+    let mut placements = Vec::new();
+
+    for (i, piece) in pieces.iter().enumerate() {
+        for cells in piece.placements_on(board, &allowed) {
+            let columns: Vec<usize> = std::iter::once(i)
+                .chain(cells.iter().map(|c| cell_columns[c]))
+                .collect();
+
+            problem.add_row(&columns);
+            placements.push(Placement { piece: i, cells });
+        }
+    }
+
+    (problem, placements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let a = Polyomino::new([Coord(5, 5), Coord(5, 6), Coord(6, 5)]);
+        let b = Polyomino::parse("..\n.##\n.#");
+
+        assert_eq!(a, b);
+        assert_eq!(a.cells(), &[Coord(0, 0), Coord(0, 1), Coord(1, 0)]);
+        assert_eq!(a.to_string(), "##\n#.\n");
+    }
+
+    #[test]
+    fn test_variants() {
+        let count = |s: &str| Polyomino::parse(s).variants().len();
+
+        assert_eq!(count("##\n##"), 1);
+        assert_eq!(count("####"), 2);
+        assert_eq!(count("###\n.#."), 4);
+        assert_eq!(count(".##\n##."), 4);
+        assert_eq!(count("##.\n.##\n.#."), 8);
+
+        assert_eq!(Polyomino::parse(".##\n##.").rotations().len(), 2);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let l = Polyomino::parse("#.\n#.\n##");
+        assert_eq!(l.rotate(), Polyomino::parse("###\n#.."));
+        assert_eq!(l.rotate().rotate().rotate().rotate(), l);
+    }
+
+    #[test]
+    fn test_placements_on() {
+        let domino = Polyomino::parse("##");
+        let board = Board::from_str("...\n..#");
+
+        // 3 horizontal (one blocked) and 2 vertical (one blocked)
+        assert_eq!(domino.placements_on(&board, |c| *c == '.').len(), 5);
+    }
+
+    #[test]
+    fn test_tiling_problem() {
+        let t = Polyomino::parse("###\n.#.");
+        let board = Board::from_size((2usize, 4usize), '.');
+
+        // Two T pieces can't tile a 2x4 rectangle
+        let (problem, _) = tiling_problem(&[t.clone(), t.clone()], &board, |_| true, true);
+        assert_eq!(problem.solve(), None);
+
+        // But they do fit if cells can be left empty
+        let board = Board::from_size((3usize, 4usize), '.');
+        let (problem, placements) = tiling_problem(&[t.clone(), t], &board, |_| true, false);
+        let solution = problem.solve().unwrap();
+        let covered: HashSet<Coord> = solution
+            .iter()
+            .flat_map(|&row| placements[row].cells.clone())
+            .collect();
+        assert_eq!(covered.len(), 8);
+    }
+}
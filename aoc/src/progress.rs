@@ -0,0 +1,220 @@
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Width of the bar itself, in characters
+const BAR_WIDTH: usize = 30;
+
+/// Minimum time between redraws
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Silence all progress bars, eg. in tests
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether progress bars are hidden, either by [`set_quiet`] or because stderr isn't a terminal
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed) || !std::io::stderr().is_terminal()
+}
+
+/// A progress bar with an ETA on stderr, for brute force loops that run for millions of
+/// iterations.
+///
+/// Ticking is cheap: the clock is only checked every `total / 1000` ticks, and the bar is only
+/// redrawn every 100ms. The bar is finished when dropped.
+///
+/// # Examples
+/// ```
+/// use aoc::progress::Progress;
+///
+/// let mut progress = Progress::new(1_000_000).with_label("simulating");
+/// for _ in 0..1_000_000 {
+///     progress.tick();
+/// }
+/// progress.finish();
+/// ```
+#[derive(Debug)]
+pub struct Progress {
+    label: String,
+    total: u64,
+    done: u64,
+    /// Tick count at which to next look at the clock
+    next_check: u64,
+    start: Instant,
+    last_draw: Option<Instant>,
+    finished: bool,
+    quiet: bool,
+}
+
+impl Progress {
+    pub fn new(total: u64) -> Self {
+        Self {
+            label: String::new(),
+            total,
+            done: 0,
+            next_check: 0,
+            start: Instant::now(),
+            last_draw: None,
+            finished: false,
+            quiet: is_quiet(),
+        }
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    pub fn done(&self) -> u64 {
+        self.done
+    }
+
+    pub fn tick(&mut self) {
+        self.inc(1);
+    }
+
+    pub fn inc(&mut self, n: u64) {
+        self.done += n;
+
+        if self.done >= self.next_check {
+            self.next_check = self.done + (self.total / 1000).max(1);
+
+            let now = Instant::now();
+            if self
+                .last_draw
+                .is_none_or(|last| now - last >= REDRAW_INTERVAL)
+            {
+                self.last_draw = Some(now);
+                self.draw(now);
+            }
+        }
+    }
+
+    /// Draw the final state and move onto a new line
+    pub fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+
+        self.draw(Instant::now());
+        if !self.quiet {
+            eprintln!();
+        }
+    }
+
+    /// Estimated time remaining, from the average rate so far
+    pub fn eta(&self, now: Instant) -> Option<Duration> {
+        if self.done == 0 {
+            return None;
+        }
+
+        let elapsed = now - self.start;
+        let remaining = self.total.saturating_sub(self.done);
+        Some(elapsed.mul_f64(remaining as f64 / self.done as f64))
+    }
+
+    fn render(&self, now: Instant) -> String {
+        let fraction = if self.total == 0 {
+            1.0
+        } else {
+            (self.done as f64 / self.total as f64).min(1.0)
+        };
+        let filled = (fraction * BAR_WIDTH as f64) as usize;
+
+        let eta = match self.eta(now) {
+            Some(eta) => format_duration(eta),
+            None => "?".to_string(),
+        };
+
+        let label = if self.label.is_empty() {
+            String::new()
+        } else {
+            format!("{} ", self.label)
+        };
+
+        format!(
+            "{}[{}{}] {:5.1}% {}/{} elapsed {} ETA {}",
+            label,
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            fraction * 100.0,
+            self.done,
+            self.total,
+            format_duration(now - self.start),
+            eta
+        )
+    }
+
+    fn draw(&self, now: Instant) {
+        if self.quiet {
+            return;
+        }
+
+        // Clear to the end of the line, in case the previous draw was longer
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(stderr, "\r{}\x1b[K", self.render(now));
+        let _ = stderr.flush();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Format a duration compactly, eg. `0.4s`, `12.3s`, `4m05s` or `2h07m`
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(420)), "0.4s");
+        assert_eq!(format_duration(Duration::from_millis(12_345)), "12.3s");
+        assert_eq!(format_duration(Duration::from_secs(245)), "4m05s");
+        assert_eq!(format_duration(Duration::from_secs(7620)), "2h07m");
+    }
+
+    #[test]
+    fn test_render() {
+        set_quiet(true);
+
+        let mut progress = Progress::new(200).with_label("sim");
+        progress.inc(50);
+        assert_eq!(progress.done(), 50);
+
+        // A quarter done after 1s leaves 3s to go
+        let now = progress.start + Duration::from_secs(1);
+        assert_eq!(progress.eta(now), Some(Duration::from_secs(3)));
+        assert_eq!(
+            progress.render(now),
+            "sim [#######-----------------------]  25.0% 50/200 elapsed 1.0s ETA 3.0s"
+        );
+    }
+
+    #[test]
+    fn test_no_progress() {
+        set_quiet(true);
+
+        let progress = Progress::new(0);
+        assert_eq!(progress.eta(progress.start), None);
+        assert!(progress.render(progress.start).contains("100.0% 0/0"));
+    }
+}
@@ -0,0 +1,446 @@
+use std::ops::Range;
+
+/// A set of integers stored as sorted, disjoint half-open ranges. Touching and overlapping
+/// ranges are merged as they're added, so the ranges are always as few as possible.
+///
+/// # Examples
+/// ```
+/// use aoc::range::RangeSet;
+///
+/// let mut set = RangeSet::from_iter([0..5, 10..15]);
+/// set.insert(4..8);
+/// set.remove(12..13);
+///
+/// assert_eq!(set.ranges(), &[0..8, 10..12, 13..15]);
+/// assert_eq!(set.total_len(), 12);
+/// assert!(set.contains(7));
+/// assert!(!set.contains(12));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sort and merge ranges into the form a set keeps them in
+    fn normalize(mut ranges: Vec<Range<i64>>) -> Self {
+        ranges.retain(|r| !r.is_empty());
+        ranges.sort_unstable_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<i64>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        Self { ranges: merged }
+    }
+
+    /// The ranges making up the set, in order
+    pub fn ranges(&self) -> &[Range<i64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of integers in the set
+    pub fn total_len(&self) -> u64 {
+        self.ranges.iter().map(|r| r.start.abs_diff(r.end)).sum()
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        // The last range starting at or before `x` is the only one it could be in
+        let i = self.ranges.partition_point(|r| r.start <= x);
+        i > 0 && x < self.ranges[i - 1].end
+    }
+
+    /// Add every integer in `range`
+    pub fn insert(&mut self, range: Range<i64>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+        *self = Self::normalize(ranges);
+    }
+
+    /// Take out every integer in `range`
+    pub fn remove(&mut self, range: Range<i64>) {
+        *self = self.subtract(&Self::from_iter([range]));
+    }
+
+    /// Integers in either set
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        Self::normalize(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    /// Integers in both sets
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::range::RangeSet;
+    ///
+    /// let a = RangeSet::from_iter([0..10, 20..30]);
+    /// let b = RangeSet::from_iter([5..25]);
+    ///
+    /// assert_eq!(a.intersect(&b).ranges(), &[5..10, 20..25]);
+    /// assert_eq!(a.subtract(&b).ranges(), &[0..5, 25..30]);
+    /// assert_eq!(a.union(&b).ranges(), &[0..30]);
+    /// ```
+    pub fn intersect(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            // Whichever ends first can't overlap anything else in the other set
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Integers in this set but not in `other`
+    pub fn subtract(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            // Skip ranges of `other` that end before this one starts
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                let hole = &other.ranges[k];
+                if hole.start > start {
+                    ranges.push(start..hole.start);
+                }
+                start = start.max(hole.end);
+                k += 1;
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Split into the integers less than `x`, and those at least `x`
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::range::RangeSet;
+    ///
+    /// let (below, above) = RangeSet::from_iter([0..10, 20..30]).split_at(25);
+    ///
+    /// assert_eq!(below.ranges(), &[0..10, 20..25]);
+    /// assert_eq!(above.ranges(), &[25..30]);
+    /// ```
+    pub fn split_at(&self, x: i64) -> (RangeSet, RangeSet) {
+        let mut below = Vec::new();
+        let mut above = Vec::new();
+        for range in &self.ranges {
+            if range.end <= x {
+                below.push(range.clone());
+            } else if range.start >= x {
+                above.push(range.clone());
+            } else {
+                below.push(range.start..x);
+                above.push(x..range.end);
+            }
+        }
+        (Self { ranges: below }, Self { ranges: above })
+    }
+}
+
+impl FromIterator<Range<i64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        Self::normalize(iter.into_iter().collect())
+    }
+}
+
+/// A piecewise shift of integers, like the almanac maps of 2023 day 5: each rule moves a range
+/// of integers by a fixed offset, and anything not covered by a rule maps to itself.
+///
+/// # Examples
+/// ```
+/// use aoc::range::{RangeMap, RangeSet};
+///
+/// // "50 98 2" and "52 50 48" from a seed-to-soil map
+/// let mut soil = RangeMap::new();
+/// soil.add(50, 98, 2);
+/// soil.add(52, 50, 48);
+///
+/// assert_eq!(soil.apply(79), 81);
+/// assert_eq!(soil.apply(99), 51);
+/// assert_eq!(soil.apply(10), 10);
+/// assert_eq!(soil.apply_range(95..100).ranges(), &[50..52, 97..100]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RangeMap {
+    /// Each rule's source range and how far it moves, sorted and not overlapping
+    rules: Vec<(Range<i64>, i64)>,
+}
+
+impl RangeMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move every integer in `source` by `offset`
+    ///
+    /// # Panics
+    /// Panics if `source` overlaps a range that's already mapped
+    pub fn insert(&mut self, source: Range<i64>, offset: i64) {
+        if source.is_empty() {
+            return;
+        }
+        let i = self.rules.partition_point(|(r, _)| r.start < source.start);
+        let overlaps_prev = i > 0 && self.rules[i - 1].0.end > source.start;
+        let overlaps_next = i < self.rules.len() && self.rules[i].0.start < source.end;
+        assert!(
+            !overlaps_prev && !overlaps_next,
+            "{:?} overlaps a range that's already mapped",
+            source
+        );
+        self.rules.insert(i, (source, offset));
+    }
+
+    /// Map `len` integers starting at `source` to those starting at `destination`, in the
+    /// order the almanac lists them
+    pub fn add(&mut self, destination: i64, source: i64, len: i64) {
+        self.insert(source..source + len, destination - source);
+    }
+
+    /// The rules as source ranges and how far they move, in order
+    pub fn rules(&self) -> &[(Range<i64>, i64)] {
+        &self.rules
+    }
+
+    /// How far `x` is moved
+    fn offset_at(&self, x: i64) -> i64 {
+        let i = self.rules.partition_point(|(r, _)| r.start <= x);
+        match i.checked_sub(1).map(|i| &self.rules[i]) {
+            Some((range, offset)) if x < range.end => *offset,
+            _ => 0,
+        }
+    }
+
+    pub fn apply(&self, x: i64) -> i64 {
+        x + self.offset_at(x)
+    }
+
+    /// Where every integer in `range` ends up
+    pub fn apply_range(&self, range: Range<i64>) -> RangeSet {
+        let mut pieces = Vec::new();
+        let mut start = range.start;
+        let first = self.rules.partition_point(|(r, _)| r.end <= start);
+        for (rule, offset) in &self.rules[first..] {
+            if start >= range.end || rule.start >= range.end {
+                break;
+            }
+            // Unmapped gap before the rule
+            if start < rule.start {
+                pieces.push(start..rule.start);
+                start = rule.start;
+            }
+            let end = rule.end.min(range.end);
+            pieces.push(start + offset..end + offset);
+            start = end;
+        }
+        if start < range.end {
+            pieces.push(start..range.end);
+        }
+        RangeSet::from_iter(pieces)
+    }
+
+    /// Where every integer in `set` ends up
+    pub fn apply_set(&self, set: &RangeSet) -> RangeSet {
+        RangeSet::from_iter(
+            set.ranges()
+                .iter()
+                .flat_map(|range| self.apply_range(range.clone()).ranges().to_vec()),
+        )
+    }
+
+    /// A single map doing this one and then `next`
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::range::RangeMap;
+    ///
+    /// let mut shift = RangeMap::new();
+    /// shift.insert(0..10, 5);
+    /// let mut back = RangeMap::new();
+    /// back.insert(5..8, -5);
+    ///
+    /// let both = shift.then(&back);
+    /// assert_eq!(both.rules(), &[(3..10, 5)]);
+    /// assert!((-5..20).all(|x| both.apply(x) == back.apply(shift.apply(x))));
+    /// ```
+    pub fn then(&self, next: &RangeMap) -> RangeMap {
+        // The combined offset can only change where one of the maps' rules starts or ends,
+        // either here or back through this map's offsets
+        let mut breaks: Vec<i64> = Vec::new();
+        for (range, _) in &self.rules {
+            breaks.extend([range.start, range.end]);
+        }
+        for (range, _) in &next.rules {
+            breaks.extend([range.start, range.end]);
+            for (_, offset) in &self.rules {
+                breaks.extend([range.start - offset, range.end - offset]);
+            }
+        }
+        breaks.sort_unstable();
+        breaks.dedup();
+
+        let mut combined = RangeMap::new();
+        for pair in breaks.windows(2) {
+            let first = self.offset_at(pair[0]);
+            let offset = first + next.offset_at(pair[0] + first);
+            if offset == 0 {
+                continue;
+            }
+            match combined.rules.last_mut() {
+                Some((last, o)) if last.end == pair[0] && *o == offset => last.end = pair[1],
+                _ => combined.rules.push((pair[0]..pair[1], offset)),
+            }
+        }
+        combined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn random_set(rng: &mut Rng) -> RangeSet {
+        (0..rng.range(0, 5))
+            .map(|_| {
+                let start = rng.range(-20, 20);
+                start..start + rng.range(-2, 10)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = Rng::new(67);
+        for _ in 0..500 {
+            let (a, b) = (random_set(&mut rng), random_set(&mut rng));
+            let x = rng.range(-25, 35);
+            let (below, above) = a.split_at(x);
+
+            let union = a.union(&b);
+            let intersection = a.intersect(&b);
+            let difference = a.subtract(&b);
+            for set in [&union, &intersection, &difference, &below, &above] {
+                // Always kept sorted and merged
+                assert!(set.ranges().iter().all(|r| !r.is_empty()));
+                assert!(set.ranges().windows(2).all(|w| w[0].end < w[1].start));
+            }
+
+            for n in -30..40 {
+                let (in_a, in_b) = (a.contains(n), b.contains(n));
+                assert_eq!(union.contains(n), in_a || in_b);
+                assert_eq!(intersection.contains(n), in_a && in_b);
+                assert_eq!(difference.contains(n), in_a && !in_b);
+                assert_eq!(below.contains(n), in_a && n < x);
+                assert_eq!(above.contains(n), in_a && n >= x);
+            }
+
+            let count = (-30..40).filter(|&n| a.contains(n)).count();
+            assert_eq!(a.total_len(), count as u64);
+        }
+    }
+
+    const ALMANAC: [&[(i64, i64, i64)]; 7] = [
+        &[(50, 98, 2), (52, 50, 48)],
+        &[(0, 15, 37), (37, 52, 2), (39, 0, 15)],
+        &[(49, 53, 8), (0, 11, 42), (42, 0, 7), (57, 7, 4)],
+        &[(88, 18, 7), (18, 25, 70)],
+        &[(45, 77, 23), (81, 45, 19), (68, 64, 13)],
+        &[(0, 69, 1), (1, 0, 69)],
+        &[(60, 56, 37), (56, 93, 4)],
+    ];
+
+    #[test]
+    fn test_almanac() {
+        // 2023 day 5
+        let maps: Vec<RangeMap> = ALMANAC
+            .iter()
+            .map(|lines| {
+                let mut map = RangeMap::new();
+                for &(destination, source, len) in *lines {
+                    map.add(destination, source, len);
+                }
+                map
+            })
+            .collect();
+        let seeds = [79, 14, 55, 13];
+
+        let location = |seed| maps.iter().fold(seed, |x, map| map.apply(x));
+        assert_eq!(seeds.iter().map(|&s| location(s)).min(), Some(35));
+
+        let seed_ranges: RangeSet = seeds
+            .chunks(2)
+            .map(|pair| pair[0]..pair[0] + pair[1])
+            .collect();
+        let locations = maps
+            .iter()
+            .fold(seed_ranges.clone(), |set, map| map.apply_set(&set));
+        assert_eq!(locations.ranges()[0].start, 46);
+        assert_eq!(locations.total_len(), seed_ranges.total_len());
+
+        // Composing the maps gives the same answers
+        let composed = maps.iter().fold(RangeMap::new(), |all, map| all.then(map));
+        assert_eq!(composed.apply_set(&seed_ranges), locations);
+        assert!((-10..120).all(|seed| composed.apply(seed) == location(seed)));
+    }
+
+    #[test]
+    fn test_sensor_coverage() {
+        // 2022 day 15, the columns of row 10 each sensor's diamond covers
+        let sensors: [((i64, i64), i64); 14] = [
+            ((2, 18), 7),
+            ((9, 16), 1),
+            ((13, 2), 3),
+            ((12, 14), 4),
+            ((10, 20), 4),
+            ((14, 17), 5),
+            ((8, 7), 9),
+            ((2, 0), 10),
+            ((0, 11), 3),
+            ((20, 14), 8),
+            ((17, 20), 6),
+            ((16, 7), 5),
+            ((14, 3), 1),
+            ((20, 1), 7),
+        ];
+        let row = 10;
+        let covered: RangeSet = sensors
+            .iter()
+            .map(|&((x, y), radius)| {
+                let reach = radius - (y - row).abs();
+                x - reach..x + reach + 1
+            })
+            .collect();
+
+        // Less the beacon on that row
+        let mut empty = covered.clone();
+        empty.remove(2..3);
+        assert_eq!(empty.total_len(), 26);
+    }
+}
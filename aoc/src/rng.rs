@@ -0,0 +1,250 @@
+/// A small, fast, seedable pseudo-random number generator (SplitMix64).
+///
+/// Not suitable for anything cryptographic, but reproducible from a seed, which is what
+/// matters for randomized heuristics and generated inputs.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..n`
+    ///
+    /// # Panics
+    /// Panics if `n` is 0
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Can't pick a number below 0");
+
+        // Multiply-shift rather than modulo to avoid bias towards small numbers
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// Uniform integer in `lo..=hi`
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo <= hi, "Empty range {}..={}", lo, hi);
+
+        let span = (hi as i128 - lo as i128 + 1) as u128;
+        if span > u64::MAX as u128 {
+            return self.next_u64() as i64;
+        }
+
+        // The offset can be past i64::MAX, but adding it in two's complement still lands in range
+        lo.wrapping_add(self.below(span as u64) as i64)
+    }
+
+    /// Uniform float in `[0, 1)`
+    pub fn f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.f64() < p
+    }
+
+    /// A uniformly chosen element of the slice, or None if it's empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        Some(&items[self.below(items.len() as u64) as usize])
+    }
+
+    /// Shuffle the slice in place (Fisher-Yates)
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Pick `k` items uniformly at random from an iterator of unknown length, in one pass
+    /// (reservoir sampling). Returns every item if there are `k` or fewer. The order of the
+    /// sample is not random, shuffle it if that matters.
+    pub fn sample<T, I: IntoIterator<Item = T>>(&mut self, items: I, k: usize) -> Vec<T> {
+        let mut reservoir = Vec::with_capacity(k);
+
+        for (i, item) in items.into_iter().enumerate() {
+            if i < k {
+                reservoir.push(item);
+            } else {
+                let j = self.below(i as u64 + 1) as usize;
+                if j < k {
+                    reservoir[j] = item;
+                }
+            }
+        }
+
+        reservoir
+    }
+
+    /// An element of the slice chosen with probability proportional to its weight. Returns
+    /// None if the slice is empty or every weight is 0.
+    ///
+    /// For drawing many times from the same weights, use [`WeightedIndex`].
+    pub fn choose_weighted<'a, T, F>(&mut self, items: &'a [T], weight: F) -> Option<&'a T>
+    where
+        F: Fn(&T) -> f64,
+    {
+        let index = WeightedIndex::new(items.iter().map(weight))?;
+        Some(&items[index.sample(self)])
+    }
+}
+
+/// Precomputed weights for repeatedly drawing indices with probability proportional to their
+/// weight, in `O(log n)` per draw
+#[derive(Debug, Clone)]
+pub struct WeightedIndex {
+    /// Running totals of the weights
+    cumulative: Vec<f64>,
+}
+
+impl WeightedIndex {
+    /// Returns None if there are no weights or they're all 0
+    ///
+    /// # Panics
+    /// Panics if a weight is negative or not finite
+    pub fn new<I: IntoIterator<Item = f64>>(weights: I) -> Option<Self> {
+        let mut total = 0.0;
+        let cumulative: Vec<f64> = weights
+            .into_iter()
+            .map(|w| {
+                assert!(w >= 0.0 && w.is_finite(), "Invalid weight {}", w);
+                total += w;
+                total
+            })
+            .collect();
+
+        (total > 0.0).then_some(Self { cumulative })
+    }
+
+    pub fn sample(&self, rng: &mut Rng) -> usize {
+        let total = *self.cumulative.last().unwrap();
+        let target = rng.f64() * total;
+
+        // First index whose running total is past the target, which skips zero weights
+        self.cumulative
+            .partition_point(|&c| c <= target)
+            .min(self.cumulative.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let a: Vec<u64> = (0..5)
+            .map({
+                let mut rng = Rng::new(42);
+                move |_| rng.next_u64()
+            })
+            .collect();
+        let b: Vec<u64> = (0..5)
+            .map({
+                let mut rng = Rng::new(42);
+                move |_| rng.next_u64()
+            })
+            .collect();
+
+        assert_eq!(a, b);
+        assert_ne!(a[0], Rng::new(43).next_u64());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Rng::new(1);
+
+        for _ in 0..1000 {
+            assert!(rng.below(7) < 7);
+
+            let n = rng.range(-3, 3);
+            assert!((-3..=3).contains(&n));
+
+            let f = rng.f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+
+        assert_eq!(rng.range(5, 5), 5);
+        assert_eq!(rng.choose::<u8>(&[]), None);
+    }
+
+    #[test]
+    fn test_full_ranges() {
+        let mut rng = Rng::new(3);
+
+        for _ in 0..1000 {
+            assert!(rng.range(i64::MIN, 0) <= 0);
+            assert!(rng.range(0, i64::MAX) >= 0);
+            rng.range(i64::MIN, i64::MAX);
+            rng.range(i64::MIN, i64::MAX - 1);
+        }
+
+        // The top half of the span needs offsets past i64::MAX
+        assert!((0..1000).any(|_| rng.range(i64::MIN, 0) > -(1 << 62)));
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut rng = Rng::new(3);
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut rng = Rng::new(5);
+
+        assert_eq!(rng.sample(0..3, 5), vec![0, 1, 2]);
+
+        // Every item should be picked roughly equally often
+        let mut counts = [0; 10];
+        for _ in 0..10_000 {
+            for i in rng.sample(0..10, 3) {
+                counts[i] += 1;
+            }
+        }
+        assert!(
+            counts.iter().all(|&c| (2700..3300).contains(&c)),
+            "{:?}",
+            counts
+        );
+    }
+
+    #[test]
+    fn test_weighted() {
+        let mut rng = Rng::new(9);
+        let items = [('a', 1.0), ('b', 0.0), ('c', 3.0)];
+
+        let mut counts = [0; 3];
+        let index = WeightedIndex::new(items.iter().map(|(_, w)| *w)).unwrap();
+        for _ in 0..10_000 {
+            counts[index.sample(&mut rng)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!((2200..2800).contains(&counts[0]), "{:?}", counts);
+
+        assert_ne!(rng.choose_weighted(&items, |(_, w)| *w), Some(&('b', 0.0)));
+        assert_eq!(rng.choose_weighted(&items, |_| 0.0), None);
+        assert_eq!(rng.choose_weighted::<u8, _>(&[], |_| 1.0), None);
+    }
+}
@@ -0,0 +1,457 @@
+use crate::error::{Error, Result};
+use crate::memory::{self, MemoryUsage};
+use crate::timing;
+use std::fmt::{self, Display};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A puzzle answer. Solutions return whatever type is natural and convert into this, so
+/// that days with different answer types can be run and reported uniformly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i128),
+    Str(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! impl_answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Str(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Str(value.to_string())
+    }
+}
+
+/// What a solution can return: anything that converts into an [`Answer`], or a [`Result`] of
+/// one for solutions that report errors instead of panicking
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer>;
+}
+
+impl<T: Into<Answer>> IntoAnswer for T {
+    fn into_answer(self) -> Result<Answer> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<Answer>, E: Into<Error>> IntoAnswer for std::result::Result<T, E> {
+    fn into_answer(self) -> Result<Answer> {
+        self.map(Into::into).map_err(Into::into)
+    }
+}
+
+/// The year this repository is for. Its days live at the root of the workspace (`day01`),
+/// while days backfilled from other years live in a directory per year (`2024/day17`).
+pub const MAIN_YEAR: u32 = 2025;
+
+/// A day's solutions with their answers type-erased, for running days from a registry
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub year: u32,
+    pub day: u32,
+    pub part_1: fn(&str) -> Result<Answer>,
+    pub part_2: fn(&str) -> Result<Answer>,
+}
+
+impl Solution {
+    pub fn part(&self, part: u32) -> fn(&str) -> Result<Answer> {
+        match part {
+            1 => self.part_1,
+            2 => self.part_2,
+            _ => panic!("Invalid part {}", part),
+        }
+    }
+}
+
+inventory::collect!(Solution);
+
+/// Register a day's `part_1::solution` and `part_2::solution` so that runners linking the
+/// day's crate discover it automatically. Called from the day's `lib.rs`. The year defaults
+/// to [`MAIN_YEAR`].
+///
+/// ```ignore
+/// pub mod part_1;
+/// pub mod part_2;
+///
+/// aoc::register_solution!(day = 1);
+/// // Or for a day from another year
+/// aoc::register_solution!(year = 2024, day = 17);
+/// ```
+// `crate` intentionally refers to the day crate calling the macro
+#[allow(clippy::crate_in_macro_def)]
+#[macro_export]
+macro_rules! register_solution {
+    (day = $day:literal) => {
+        $crate::register_solution!(year = $crate::runner::MAIN_YEAR, day = $day);
+    };
+    (year = $year:expr, day = $day:literal) => {
+        $crate::inventory::submit! {
+            $crate::runner::Solution {
+                year: $year,
+                day: $day,
+                part_1: |input| {
+                    $crate::runner::IntoAnswer::into_answer(crate::part_1::solution(input))
+                },
+                part_2: |input| {
+                    $crate::runner::IntoAnswer::into_answer(crate::part_2::solution(input))
+                },
+            }
+        }
+    };
+}
+
+/// All registered solutions that have been linked into the binary, sorted by year and day
+pub fn solutions() -> Vec<&'static Solution> {
+    let mut solutions: Vec<&Solution> = inventory::iter::<Solution>.into_iter().collect();
+    solutions.sort_by_key(|s| (s.year, s.day));
+    solutions
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    /// Parse an `--output` value
+    pub fn from_arg(arg: &str) -> Result<Self, String> {
+        match arg {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err("expected text or json".to_string()),
+        }
+    }
+}
+
+/// The answer and timings for a single run of one part
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    pub day: u32,
+    pub part: u32,
+    pub answer: Answer,
+    /// Time spent loading the input. Solutions that parse inside `solution` count
+    /// their parsing towards `solve` instead.
+    pub parse: Duration,
+    pub solve: Duration,
+    /// Memory used while solving
+    pub memory: MemoryUsage,
+}
+
+impl RunResult {
+    /// Format as a single line JSON object, eg.
+    /// `{"day":1,"part":2,"answer":5937,"parse_ms":0.041,"solve_ms":1.522}`
+    ///
+    /// Integer answers are emitted as JSON numbers, anything else as a string. Memory usage is
+    /// added as `peak_rss_bytes`, `allocations` and `peak_heap_bytes` when it was measured.
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            r#"{{"day":{},"part":{},"answer":{},"parse_ms":{:.3},"solve_ms":{:.3}"#,
+            self.day,
+            self.part,
+            json_answer(&self.answer),
+            self.parse.as_secs_f64() * 1000.0,
+            self.solve.as_secs_f64() * 1000.0,
+        );
+
+        for (key, value) in [
+            ("peak_rss_bytes", self.memory.peak_rss),
+            ("allocations", self.memory.allocations),
+            ("peak_heap_bytes", self.memory.peak_heap),
+        ] {
+            if let Some(value) = value {
+                json.push_str(&format!(r#","{}":{}"#, key, value));
+            }
+        }
+
+        json.push('}');
+        json
+    }
+
+    /// Human readable timing and memory, eg. `1.522 ms, 3.1 MiB peak RSS`
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{:.3} ms", self.solve.as_secs_f64() * 1000.0);
+
+        if let Some(rss) = self.memory.peak_rss {
+            summary.push_str(&format!(", {} peak RSS", memory::format_bytes(rss)));
+        }
+        if let (Some(allocations), Some(heap)) = (self.memory.allocations, self.memory.peak_heap) {
+            summary.push_str(&format!(
+                ", {} allocations, {} peak heap",
+                allocations,
+                memory::format_bytes(heap)
+            ));
+        }
+
+        summary
+    }
+}
+
+fn json_answer(answer: &Answer) -> String {
+    let answer = match answer {
+        Answer::Int(n) => return n.to_string(),
+        Answer::Str(s) => s,
+    };
+
+    let mut escaped = String::from("\"");
+    for c in answer.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Get the day number from a package name like `day01`, or `y2024-day17` for other years.
+/// Returns 0 if there isn't one.
+pub fn day_from_package(name: &str) -> u32 {
+    name.rsplit_once("day")
+        .and_then(|(_, day)| day.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Get the year from a package name, see [`day_from_package`]
+pub fn year_from_package(name: &str) -> u32 {
+    name.strip_prefix('y')
+        .and_then(|rest| rest.split_once('-'))
+        .and_then(|(year, _)| year.parse().ok())
+        .unwrap_or(MAIN_YEAR)
+}
+
+/// Name of a day's package: `day01` for [`MAIN_YEAR`], `y2024-day17` for other years
+pub fn package_name(year: u32, day: u32) -> String {
+    if year == MAIN_YEAR {
+        format!("day{:02}", day)
+    } else {
+        format!("y{}-day{:02}", year, day)
+    }
+}
+
+/// Run a single part on an already loaded input, timing the solve. Errors are given the day
+/// and part as context.
+pub fn run_part(
+    day: u32,
+    part: u32,
+    input: &str,
+    solution: fn(&str) -> Result<Answer>,
+) -> Result<RunResult> {
+    let ((answer, solve), memory) = memory::measure(|| {
+        let start = Instant::now();
+        let answer = solution(input);
+        (answer, start.elapsed())
+    });
+
+    Ok(RunResult {
+        day,
+        part,
+        answer: answer.map_err(|e| e.context(format!("day {} part {}", day, part)))?,
+        parse: Duration::ZERO,
+        solve,
+        memory,
+    })
+}
+
+/// A solved part: what the solution returned, how long it took and how much memory it used
+pub type Solved<T = Answer> = (T, Duration, MemoryUsage);
+
+/// A solution that was still running when its time limit ran out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut {
+    pub limit: Duration,
+}
+
+impl Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "did not finish within the time limit of {:?}",
+            self.limit
+        )
+    }
+}
+
+/// Parse a `--time-limit` value in (possibly fractional) seconds
+pub fn parse_time_limit(arg: &str) -> Result<Duration, String> {
+    arg.parse::<f64>()
+        .ok()
+        .filter(|secs| *secs > 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| "expected a positive number of seconds".to_string())
+}
+
+/// Solve on a separate thread, giving up once `limit` has passed. Returns what the solution
+/// returned, how long it took and the memory it used. Without a limit the solution just runs on
+/// the current thread.
+///
+/// A thread can't be killed, so the solution keeps running in the background after a timeout.
+/// Callers should report it and exit.
+///
+/// Phases the solution timed with [`crate::timing`] are left in the calling thread's report.
+pub fn solve_with_limit<F, T>(
+    input: &str,
+    solution: F,
+    limit: Option<Duration>,
+) -> Result<Solved<T>, TimedOut>
+where
+    F: FnOnce(&str) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let solve = move |input: &str| {
+        // Start the phase report afresh for this solve
+        timing::take_report();
+        let ((answer, elapsed), memory) = memory::measure(|| {
+            let start = Instant::now();
+            let answer = solution(input);
+            (answer, start.elapsed())
+        });
+        (answer, elapsed, memory)
+    };
+
+    let Some(limit) = limit else {
+        return Ok(solve(input));
+    };
+
+    let input = input.to_string();
+    let (tx, rx) = mpsc::channel();
+
+    // Match the main thread's stack size, so deeply recursive solutions behave the same
+    let handle = thread::Builder::new()
+        .stack_size(8 * 1024 * 1024)
+        .spawn(move || {
+            let solved = solve(&input);
+            let _ = tx.send((solved, timing::take_report()));
+        })
+        .expect("Failed to spawn solution thread");
+
+    match rx.recv_timeout(limit) {
+        Ok((solved, phases)) => {
+            timing::extend_report(phases);
+            Ok(solved)
+        }
+        Err(RecvTimeoutError::Timeout) => Err(TimedOut { limit }),
+        // The solution panicked, pass it on
+        Err(RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => unreachable!("solution thread exited without sending a result"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_from_package() {
+        assert_eq!(day_from_package("day01"), 1);
+        assert_eq!(day_from_package("day25"), 25);
+        assert_eq!(day_from_package("template"), 0);
+        assert_eq!(day_from_package("y2024-day17"), 17);
+    }
+
+    #[test]
+    fn test_year_from_package() {
+        assert_eq!(year_from_package("day01"), MAIN_YEAR);
+        assert_eq!(year_from_package("y2024-day17"), 2024);
+        assert_eq!(year_from_package(&package_name(2016, 9)), 2016);
+    }
+
+    #[test]
+    fn test_package_name() {
+        assert_eq!(package_name(MAIN_YEAR, 1), "day01");
+        assert_eq!(package_name(2024, 17), "y2024-day17");
+        assert_eq!(day_from_package(&package_name(2016, 9)), 9);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mut result = RunResult {
+            day: 1,
+            part: 2,
+            answer: 5937usize.into(),
+            parse: Duration::from_micros(41),
+            solve: Duration::from_micros(1522),
+            memory: MemoryUsage::default(),
+        };
+
+        assert_eq!(
+            result.to_json(),
+            r#"{"day":1,"part":2,"answer":5937,"parse_ms":0.041,"solve_ms":1.522}"#
+        );
+
+        result.answer = "AB\"C".into();
+        assert!(result.to_json().contains(r#""answer":"AB\"C""#));
+
+        result.memory.peak_rss = Some(4096);
+        assert!(result
+            .to_json()
+            .ends_with(r#""solve_ms":1.522,"peak_rss_bytes":4096}"#));
+    }
+
+    #[test]
+    fn test_solve_with_limit() {
+        let (answer, _, _) =
+            solve_with_limit("12", |i| Answer::from(i.parse::<u32>().unwrap()), None).unwrap();
+        assert_eq!(answer, Answer::Int(12));
+
+        let limit = Some(Duration::from_secs(5));
+        let (answer, _, _) = solve_with_limit("12", |i| Answer::from(i.len()), limit).unwrap();
+        assert_eq!(answer, Answer::Int(2));
+
+        let limit = Some(Duration::from_secs(5));
+        let (answer, _, _) =
+            solve_with_limit("x", |i| i.parse::<u32>().into_answer(), limit).unwrap();
+        assert_eq!(
+            answer.unwrap_err().to_string(),
+            "invalid digit found in string"
+        );
+
+        let limit = Duration::from_millis(20);
+        let slow = |_: &str| {
+            thread::sleep(Duration::from_millis(500));
+            Answer::Int(0)
+        };
+        assert_eq!(
+            solve_with_limit("", slow, Some(limit)),
+            Err(TimedOut { limit })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "bad input")]
+    fn test_solve_with_limit_panic() {
+        let _ = solve_with_limit("", |_| panic!("bad input"), Some(Duration::from_secs(5)));
+    }
+}
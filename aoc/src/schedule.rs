@@ -0,0 +1,158 @@
+use crate::graph::Graph;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// When every task was worked on, see [`schedule`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule<T> {
+    /// Tasks in the order they were finished
+    pub order: Vec<T>,
+    /// Each task with the times it started and finished, in the order they were started
+    pub timeline: Vec<(T, u64, u64)>,
+    /// When the last task finished
+    pub total_time: u64,
+}
+
+/// Work through a set of tasks with `workers` workers, where `dependencies` are pairs of
+/// `(before, after)` tasks, and `after` can't be started until `before` is finished. Whenever a
+/// worker is free it takes the first task in `T`'s order that's ready, and works on it for
+/// `duration(task)`.
+///
+/// Returns None if the dependencies have a cycle, so some tasks can never start. See
+/// [`Graph::topo_sort`] for the order with a single worker and no durations.
+///
+/// # Examples
+/// ```
+/// use aoc::schedule::schedule;
+///
+/// let dependencies = [('a', 'c'), ('b', 'c')];
+/// let plan = schedule(&dependencies, 2, |&task| if task == 'a' { 5 } else { 1 }).unwrap();
+///
+/// assert_eq!(plan.order, vec!['b', 'a', 'c']);
+/// assert_eq!(plan.total_time, 6);
+/// ```
+pub fn schedule<T, D>(dependencies: &[(T, T)], workers: usize, duration: D) -> Option<Schedule<T>>
+where
+    T: Clone + Ord + Hash,
+    D: Fn(&T) -> u64,
+{
+    assert!(workers > 0, "can't schedule without any workers");
+
+    let graph = Graph::from_edges(
+        dependencies
+            .iter()
+            .map(|(before, after)| (before.clone(), after.clone(), ())),
+    );
+    if graph.has_cycle() {
+        return None;
+    }
+
+    let mut waiting_on: HashMap<&T, usize> = graph.nodes().map(|task| (task, 0)).collect();
+    for (_, after, _) in graph.edges() {
+        *waiting_on.get_mut(after).unwrap() += 1;
+    }
+
+    let mut ready: BTreeSet<&T> = waiting_on
+        .iter()
+        .filter(|(_, &count)| count == 0)
+        .map(|(&task, _)| task)
+        .collect();
+    // Tasks in progress, by when they finish and then by task
+    let mut busy: BinaryHeap<Reverse<(u64, &T)>> = BinaryHeap::new();
+
+    let mut schedule = Schedule {
+        order: Vec::new(),
+        timeline: Vec::new(),
+        total_time: 0,
+    };
+    let mut now = 0;
+
+    loop {
+        while busy.len() < workers {
+            let Some(task) = ready.pop_first() else {
+                break;
+            };
+            let end = now + duration(task);
+            schedule.timeline.push((task.clone(), now, end));
+            busy.push(Reverse((end, task)));
+        }
+
+        let Some(&Reverse((end, _))) = busy.peek() else {
+            break;
+        };
+        now = end;
+
+        // Everything finishing now unlocks its tasks before any worker picks the next one, so
+        // a task unlocked by a later finisher can still go first
+        while busy.peek().is_some_and(|Reverse((end, _))| *end == now) {
+            let Reverse((_, task)) = busy.pop().unwrap();
+            for (next, _) in graph.neighbours(task) {
+                let count = waiting_on.get_mut(next).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(next);
+                }
+            }
+            schedule.order.push(task.clone());
+        }
+    }
+
+    schedule.total_time = now;
+    Some(schedule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2018 day 7
+    const STEPS: [(char, char); 7] = [
+        ('C', 'A'),
+        ('C', 'F'),
+        ('A', 'B'),
+        ('A', 'D'),
+        ('B', 'E'),
+        ('D', 'E'),
+        ('F', 'E'),
+    ];
+
+    fn seconds(step: &char) -> u64 {
+        (*step as u8 - b'A' + 1) as u64
+    }
+
+    #[test]
+    fn test_single_worker() {
+        let plan = schedule(&STEPS, 1, seconds).unwrap();
+
+        assert_eq!(plan.order.iter().collect::<String>(), "CABDFE");
+        assert_eq!(plan.total_time, 21);
+    }
+
+    #[test]
+    fn test_two_workers() {
+        let plan = schedule(&STEPS, 2, seconds).unwrap();
+
+        assert_eq!(plan.order.iter().collect::<String>(), "CABFDE");
+        assert_eq!(plan.total_time, 15);
+        assert!(plan.timeline.contains(&('F', 3, 9)));
+    }
+
+    #[test]
+    fn test_simultaneous_finishes() {
+        // Q and P both finish at 1, and unlocking A and B has to happen before R is picked
+        let dependencies = [('Q', 'A'), ('Q', 'B'), ('P', 'Z'), ('R', 'Z')];
+        let plan = schedule(&dependencies, 2, |_| 1).unwrap();
+
+        assert!(plan.timeline.contains(&('A', 1, 2)));
+        assert!(plan.timeline.contains(&('B', 1, 2)));
+        assert!(plan.timeline.contains(&('R', 2, 3)));
+        assert_eq!(plan.order.iter().collect::<String>(), "PQABRZ");
+        assert_eq!(plan.total_time, 4);
+    }
+
+    #[test]
+    fn test_cycle() {
+        assert_eq!(schedule(&[(1, 2), (2, 3), (3, 2)], 4, |_| 1), None);
+    }
+}
@@ -0,0 +1,1996 @@
+use crate::grid_2d::{Coord, Dir};
+use crate::parse::ParseError;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt::{Debug, Write};
+use std::hash::Hash;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// A state seen by [`astar`] or [`bfs`], with the best known cost to reach it
+struct Node<S> {
+    state: S,
+    cost: u64,
+    previous: Option<usize>,
+}
+
+/// Index of a state's node, adding it if it hasn't been seen before
+fn intern<S: Clone + Eq + Hash>(
+    state: S,
+    index: &mut HashMap<S, usize>,
+    nodes: &mut Vec<Node<S>>,
+) -> usize {
+    *index.entry(state.clone()).or_insert_with(|| {
+        nodes.push(Node {
+            state,
+            cost: u64::MAX,
+            previous: None,
+        });
+        nodes.len() - 1
+    })
+}
+
+/// The states along the path to a node, from the start
+fn path_to<S: Clone>(nodes: &[Node<S>], mut i: usize) -> Vec<S> {
+    let mut path = vec![nodes[i].state.clone()];
+    while let Some(prev) = nodes[i].previous {
+        path.push(nodes[prev].state.clone());
+        i = prev;
+    }
+    path.reverse();
+    path
+}
+
+/// Shortest path from any of `starts` to a state satisfying `is_goal`, where every move to one
+/// of a state's `neighbours` costs 1. Returns the number of steps and the states along the
+/// path, including the start and goal.
+///
+/// # Examples
+/// ```
+/// use aoc::search::bfs;
+///
+/// // Reach 10 from 1 by doubling or adding 1
+/// let (steps, path) = bfs([1u32], |&n| [n * 2, n + 1], |&n| n == 10).unwrap();
+///
+/// assert_eq!(steps, 4);
+/// assert_eq!(path, vec![1, 2, 4, 5, 10]);
+/// ```
+pub fn bfs<S, N, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+    mut is_goal: G,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut index: HashMap<S, usize> = HashMap::new();
+    let mut nodes: Vec<Node<S>> = Vec::new();
+    for start in starts {
+        let i = intern(start, &mut index, &mut nodes);
+        nodes[i].cost = 0;
+    }
+
+    // Nodes are added in the order they're reached, so the vector doubles as the queue
+    let mut i = 0;
+    while i < nodes.len() {
+        if is_goal(&nodes[i].state) {
+            return Some((nodes[i].cost, path_to(&nodes, i)));
+        }
+
+        for next in neighbours(&nodes[i].state) {
+            let j = intern(next, &mut index, &mut nodes);
+            if nodes[j].cost == u64::MAX {
+                nodes[j].cost = nodes[i].cost + 1;
+                nodes[j].previous = Some(i);
+            }
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Number of steps from the nearest of `starts` to every reachable state, see [`bfs`]
+///
+/// # Examples
+/// ```
+/// use aoc::search::bfs_distances;
+///
+/// let distances = bfs_distances([0i32], |&n| [n - 1, n + 1].into_iter().filter(|n| n.abs() <= 3));
+///
+/// assert_eq!(distances.len(), 7);
+/// assert_eq!(distances[&-3], 3);
+/// ```
+pub fn bfs_distances<S, N, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+) -> HashMap<S, u64>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances: HashMap<S, u64> = starts.into_iter().map(|s| (s, 0)).collect();
+    let mut frontier: Vec<S> = distances.keys().cloned().collect();
+    let mut steps = 0;
+
+    while !frontier.is_empty() {
+        steps += 1;
+        let mut next_frontier = Vec::new();
+        for state in &frontier {
+            for next in neighbours(state) {
+                if !distances.contains_key(&next) {
+                    distances.insert(next.clone(), steps);
+                    next_frontier.push(next);
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    distances
+}
+
+/// Lowest cost path from any of `starts` to a state satisfying `is_goal`, where `neighbours`
+/// gives the states reachable from a state along with the cost of moving there. Returns the
+/// cost and the states along the path, including the start and goal.
+///
+/// # Examples
+/// ```
+/// use aoc::search::dijkstra;
+///
+/// // Reach 10 from 1 by doubling (cost 1) or adding 1 (cost 2)
+/// let (cost, path) = dijkstra([1u32], |&n| [(n * 2, 1), (n + 1, 2)], |&n| n == 10).unwrap();
+///
+/// assert_eq!(cost, 5);
+/// assert_eq!(path, vec![1, 2, 4, 5, 10]);
+/// ```
+pub fn dijkstra<S, N, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    is_goal: G,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(starts, neighbours, |_| 0, is_goal)
+}
+
+/// Like [`dijkstra`], but among states of equal cost the one with the smallest `tie_break` key
+/// is expanded first, see [`astar_by`]. With strictly positive costs, this means the goal found
+/// is the one with the smallest key among the cheapest.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::Coord;
+/// use aoc::search::dijkstra_by;
+///
+/// // Of the four corners the same distance away, the first in reading order
+/// let goals = [Coord(2, 2), Coord(-2, -2), Coord(2, -2), Coord(-2, 2)];
+/// let (cost, path) = dijkstra_by(
+///     [Coord(0, 0)],
+///     |c| c.cardinal_neighbours().map(|n| (n, 1)),
+///     |c| goals.contains(c),
+///     |c| (c.0, c.1),
+/// )
+/// .unwrap();
+/// assert_eq!(cost, 4);
+/// assert_eq!(path.last(), Some(&Coord(-2, -2)));
+/// ```
+pub fn dijkstra_by<S, N, I, G, T, K>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    is_goal: G,
+    tie_break: T,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    G: FnMut(&S) -> bool,
+    T: FnMut(&S) -> K,
+    K: Ord,
+{
+    astar_by(starts, neighbours, |_| 0, is_goal, tie_break)
+}
+
+/// Lowest cost from the nearest of `starts` to every reachable state, see [`dijkstra`]
+///
+/// # Examples
+/// ```
+/// use aoc::search::dijkstra_all;
+///
+/// let costs = dijkstra_all([1u32], |&n| [(n * 2, 1), (n + 1, 2)].into_iter().filter(|&(n, _)| n <= 10));
+///
+/// assert_eq!(costs.len(), 10);
+/// assert_eq!(costs[&10], 5);
+/// assert_eq!(costs[&7], 6);
+/// ```
+pub fn dijkstra_all<S, N, I>(starts: impl IntoIterator<Item = S>, neighbours: N) -> HashMap<S, u64>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let result = dijkstra_paths(starts, neighbours);
+    result.states.into_iter().zip(result.costs).collect()
+}
+
+/// Every state reached by [`dijkstra_paths`], with its lowest cost and every state it can be
+/// reached from at that cost
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    index: HashMap<S, usize>,
+    states: Vec<S>,
+    costs: Vec<u64>,
+    parents: Vec<Vec<usize>>,
+}
+
+impl<S: Clone + Eq + Hash> SearchResult<S> {
+    /// Lowest cost to reach a state, or None if it wasn't reached
+    pub fn cost(&self, state: &S) -> Option<u64> {
+        self.index.get(state).map(|&i| self.costs[i])
+    }
+
+    /// Every state reached, with its lowest cost
+    pub fn costs(&self) -> impl Iterator<Item = (&S, u64)> {
+        self.states.iter().zip(self.costs.iter().copied())
+    }
+
+    /// A lowest cost path to `goal`, including the start and goal
+    pub fn path_to(&self, goal: &S) -> Option<Vec<S>> {
+        let mut i = *self.index.get(goal)?;
+        let mut path = vec![self.states[i].clone()];
+        while let Some(&prev) = self.parents[i].first() {
+            path.push(self.states[prev].clone());
+            i = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every lowest cost path to `goal`. There can be exponentially many of them, so prefer
+    /// [`SearchResult::on_shortest_paths`] when only the states along them matter.
+    pub fn all_shortest_paths(&self, goal: &S) -> Vec<Vec<S>> {
+        let Some(&goal) = self.index.get(goal) else {
+            return Vec::new();
+        };
+
+        // Partial paths, built backwards from the goal
+        let mut paths = Vec::new();
+        let mut stack = vec![vec![goal]];
+        while let Some(path) = stack.pop() {
+            let last = *path.last().unwrap();
+            if self.parents[last].is_empty() {
+                paths.push(path.iter().rev().map(|&i| self.states[i].clone()).collect());
+                continue;
+            }
+            for &prev in &self.parents[last] {
+                let mut longer = path.clone();
+                longer.push(prev);
+                stack.push(longer);
+            }
+        }
+
+        paths
+    }
+
+    /// Every state on any lowest cost path to the cheapest of `goals` to reach, eg. for
+    /// counting the tiles on any best path when the goal can be reached facing any direction
+    pub fn on_shortest_paths<'a>(&self, goals: impl IntoIterator<Item = &'a S>) -> HashSet<S>
+    where
+        S: 'a,
+    {
+        let goals: Vec<usize> = goals
+            .into_iter()
+            .filter_map(|goal| self.index.get(goal).copied())
+            .collect();
+        let Some(best) = goals.iter().map(|&i| self.costs[i]).min() else {
+            return HashSet::new();
+        };
+
+        let mut seen = vec![false; self.states.len()];
+        let mut stack: Vec<usize> = goals
+            .into_iter()
+            .filter(|&i| self.costs[i] == best)
+            .collect();
+        while let Some(i) = stack.pop() {
+            if !std::mem::replace(&mut seen[i], true) {
+                stack.extend(&self.parents[i]);
+            }
+        }
+
+        seen.iter()
+            .zip(&self.states)
+            .filter(|(&seen, _)| seen)
+            .map(|(_, state)| state.clone())
+            .collect()
+    }
+}
+
+/// Run Dijkstra's algorithm from `starts` to every reachable state, recording every way of
+/// reaching each state at its lowest cost so that paths can be recovered afterwards.
+///
+/// With zero cost moves, a state is only recorded as reachable from states that were settled
+/// before it. Otherwise two states of equal cost could each be the other's parent, and paths
+/// would go round in circles.
+///
+/// # Examples
+/// ```
+/// use aoc::search::dijkstra_paths;
+///
+/// // Two ways round a square from 0 to 2
+/// let edges = [(0, 1), (1, 2), (0, 3), (3, 2)];
+/// let result = dijkstra_paths([0], |&n| {
+///     edges.iter().filter(move |(from, _)| *from == n).map(|&(_, to)| (to, 1))
+/// });
+///
+/// assert_eq!(result.cost(&2), Some(2));
+/// assert_eq!(result.path_to(&2).unwrap().len(), 3);
+/// assert_eq!(result.all_shortest_paths(&2).len(), 2);
+/// assert_eq!(result.on_shortest_paths(&[2]).len(), 4);
+/// ```
+pub fn dijkstra_paths<S, N, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+) -> SearchResult<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    dijkstra_paths_search(starts, neighbours, None)
+}
+
+/// Like [`dijkstra_paths`], also recording everything the search explored into `trace`
+pub fn dijkstra_paths_traced<S, N, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    trace: &mut Trace<S>,
+) -> SearchResult<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    dijkstra_paths_search(starts, neighbours, Some(trace))
+}
+
+fn dijkstra_paths_search<S, N, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+    mut trace: Option<&mut Trace<S>>,
+) -> SearchResult<S>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    if let Some(trace) = trace.as_deref_mut() {
+        trace.clear();
+    }
+
+    let mut index: HashMap<S, usize> = HashMap::new();
+    let mut nodes: Vec<Node<S>> = Vec::new();
+    let mut parents: Vec<Vec<usize>> = Vec::new();
+    let mut settled: Vec<bool> = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        let i = intern(start, &mut index, &mut nodes);
+        nodes[i].cost = 0;
+        heap.push(Reverse((0, i)));
+    }
+
+    while let Some(Reverse((cost, i))) = heap.pop() {
+        settled.resize(nodes.len(), false);
+        if cost > nodes[i].cost || std::mem::replace(&mut settled[i], true) {
+            continue;
+        }
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.events.push(TraceEvent::Expand { state: i, cost });
+        }
+
+        for (next, step) in neighbours(&nodes[i].state) {
+            let j = intern(next, &mut index, &mut nodes);
+            parents.resize(nodes.len(), Vec::new());
+            settled.resize(nodes.len(), false);
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.events.push(TraceEvent::Edge {
+                    from: i,
+                    to: j,
+                    cost: step,
+                });
+            }
+
+            let next_cost = cost + step;
+            if next_cost < nodes[j].cost {
+                nodes[j].cost = next_cost;
+                parents[j] = vec![i];
+                heap.push(Reverse((next_cost, j)));
+            } else if next_cost == nodes[j].cost && !settled[j] && !parents[j].contains(&i) {
+                parents[j].push(i);
+            }
+        }
+    }
+
+    parents.resize(nodes.len(), Vec::new());
+    if let Some(trace) = trace {
+        trace.states = nodes.iter().map(|node| node.state.clone()).collect();
+    }
+    let (states, costs) = nodes
+        .into_iter()
+        .map(|node| (node.state, node.cost))
+        .unzip();
+    SearchResult {
+        index,
+        states,
+        costs,
+        parents,
+    }
+}
+
+/// Like [`dijkstra`], but guided towards the goal by `heuristic`, an estimate of the remaining
+/// cost from a state. The heuristic must never overestimate, or the path found may not be the
+/// cheapest. Debug builds check this along the path that was found, and panic if it did.
+///
+/// # Examples
+/// ```
+/// use aoc::search::astar;
+///
+/// let (cost, path) = astar([0i32], |&n| [(n - 1, 1), (n + 1, 1)], |&n| n.abs_diff(6) as u64, |&n| n == 6)
+///     .unwrap();
+///
+/// assert_eq!(cost, 6);
+/// assert_eq!(path.len(), 7);
+/// ```
+pub fn astar<S, N, I, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    heuristic: H,
+    is_goal: G,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    H: FnMut(&S) -> u64,
+    G: FnMut(&S) -> bool,
+{
+    astar_by(starts, neighbours, heuristic, is_goal, |_| ())
+}
+
+/// Like [`astar`], but among states with the same estimated total cost, the one with the
+/// smallest `tie_break` key is expanded first. This picks a canonical answer when several are
+/// equally cheap, such as the goal first in reading order, or the path with the fewest turns
+/// when the state counts them.
+///
+/// The goal found is the one with the smallest key among the cheapest as long as a state's
+/// predecessors are always expanded before it. That holds with strictly positive costs and no
+/// heuristic, as in [`dijkstra_by`], or with keys that never decrease along a path.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Coord, Dir};
+/// use aoc::search::astar_by;
+///
+/// // Across an open 5x5 grid, counting turns so that the straightest path is found
+/// let goal = Coord(4, 4);
+/// let (cost, path) = astar_by(
+///     [(Coord(0, 0), Dir::East, 0u32)],
+///     |&(c, dir, turns)| {
+///         [Dir::North, Dir::East, Dir::South, Dir::West]
+///             .into_iter()
+///             .map(move |d| (c + d, d, turns + (d != dir) as u32))
+///             .filter(|(c, _, _)| (0..5).contains(&c.0) && (0..5).contains(&c.1))
+///             .map(|state| (state, 1))
+///     },
+///     |(c, _, _)| c.manhattan_distance(&goal) as u64,
+///     |(c, _, _)| *c == goal,
+///     |&(_, _, turns)| turns,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(cost, 8);
+/// assert_eq!(path.last().unwrap().2, 1);
+/// ```
+pub fn astar_by<S, N, I, H, G, T, K>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    heuristic: H,
+    is_goal: G,
+    tie_break: T,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    H: FnMut(&S) -> u64,
+    G: FnMut(&S) -> bool,
+    T: FnMut(&S) -> K,
+    K: Ord,
+{
+    astar_search(starts, neighbours, heuristic, is_goal, tie_break, None)
+}
+
+/// Like [`astar_by`], also recording everything the search explored into `trace`. Pass `|_| 0`
+/// as the heuristic for [`dijkstra`], and `|_| ()` as the tie break for either.
+///
+/// # Examples
+/// ```
+/// use aoc::search::{astar_traced, Trace, TraceEvent};
+///
+/// let mut trace = Trace::new();
+/// let (cost, _) = astar_traced([1u32], |&n| [(n * 2, 1), (n + 1, 2)], |_| 0, |&n| n == 4, |_| (), &mut trace)
+///     .unwrap();
+///
+/// assert_eq!(cost, 2);
+/// assert_eq!(trace.expanded().collect::<Vec<_>>(), vec![(&1, 0), (&2, 1), (&4, 2)]);
+/// assert_eq!(trace.events[1], TraceEvent::Edge { from: 0, to: 1, cost: 1 });
+/// ```
+pub fn astar_traced<S, N, I, H, G, T, K>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: N,
+    heuristic: H,
+    is_goal: G,
+    tie_break: T,
+    trace: &mut Trace<S>,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    H: FnMut(&S) -> u64,
+    G: FnMut(&S) -> bool,
+    T: FnMut(&S) -> K,
+    K: Ord,
+{
+    astar_search(
+        starts,
+        neighbours,
+        heuristic,
+        is_goal,
+        tie_break,
+        Some(trace),
+    )
+}
+
+fn astar_search<S, N, I, H, G, T, K>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: N,
+    mut heuristic: H,
+    mut is_goal: G,
+    mut tie_break: T,
+    mut trace: Option<&mut Trace<S>>,
+) -> Option<(u64, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    H: FnMut(&S) -> u64,
+    G: FnMut(&S) -> bool,
+    T: FnMut(&S) -> K,
+    K: Ord,
+{
+    if let Some(trace) = trace.as_deref_mut() {
+        trace.clear();
+    }
+
+    // States are interned so the heap and predecessors can refer to them by index, which also
+    // means states don't need to be `Ord`
+    let mut index: HashMap<S, usize> = HashMap::new();
+    let mut nodes: Vec<Node<S>> = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        let i = intern(start, &mut index, &mut nodes);
+        nodes[i].cost = 0;
+        let state = &nodes[i].state;
+        heap.push(Reverse((heuristic(state), tie_break(state), Reverse(0), i)));
+    }
+
+    while let Some(Reverse((_, _, Reverse(cost), i))) = heap.pop() {
+        if cost > nodes[i].cost {
+            continue;
+        }
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.events.push(TraceEvent::Expand { state: i, cost });
+        }
+
+        if is_goal(&nodes[i].state) {
+            if let Some(trace) = trace {
+                trace.states = nodes.iter().map(|node| node.state.clone()).collect();
+            }
+            #[cfg(debug_assertions)]
+            check_admissible(&nodes, i, &mut heuristic);
+            return Some((cost, path_to(&nodes, i)));
+        }
+
+        for (next, step) in neighbours(&nodes[i].state) {
+            let j = intern(next, &mut index, &mut nodes);
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.events.push(TraceEvent::Edge {
+                    from: i,
+                    to: j,
+                    cost: step,
+                });
+            }
+            let next_cost = cost + step;
+            if next_cost < nodes[j].cost {
+                nodes[j].cost = next_cost;
+                nodes[j].previous = Some(i);
+                // Among equal estimates and keys, prefer states further along, which are closer
+                // to the goal
+                let state = &nodes[j].state;
+                let estimate = next_cost + heuristic(state);
+                heap.push(Reverse((estimate, tie_break(state), Reverse(next_cost), j)));
+            }
+        }
+    }
+
+    if let Some(trace) = trace {
+        trace.states = nodes.into_iter().map(|node| node.state).collect();
+    }
+    None
+}
+
+/// One thing a search did, referring to states by their index in [`Trace::states`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// A state was taken off the queue at its lowest cost, to look at its neighbours
+    Expand { state: usize, cost: u64 },
+    /// A move from an expanded state was looked at, with the cost of the move alone
+    Edge { from: usize, to: usize, cost: u64 },
+}
+
+/// Everything a search explored, in order: which states it expanded at what cost, and every
+/// move it looked at from them. Record one with [`astar_traced`] or [`dijkstra_paths_traced`],
+/// then [save](Trace::save) it and replay it with `aoc-cli trace <file>` to see where a search
+/// went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace<S> {
+    /// Every state seen, in the order they were first seen
+    pub states: Vec<S>,
+    pub events: Vec<TraceEvent>,
+}
+
+impl<S> Default for Trace<S> {
+    fn default() -> Self {
+        Self {
+            states: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl<S> Trace<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn clear(&mut self) {
+        self.states.clear();
+        self.events.clear();
+    }
+
+    /// The states expanded, in order, with the cost they were expanded at
+    pub fn expanded(&self) -> impl Iterator<Item = (&S, u64)> {
+        self.events.iter().filter_map(|event| match *event {
+            TraceEvent::Expand { state, cost } => Some((&self.states[state], cost)),
+            TraceEvent::Edge { .. } => None,
+        })
+    }
+
+    /// The trace as text, with a tab separated line per state (`state <index> <state:?>`)
+    /// followed by a line per event (`expand <state> <cost>` or `edge <from> <to> <cost>`)
+    pub fn to_text(&self) -> String
+    where
+        S: Debug,
+    {
+        let mut text = String::new();
+        for (i, state) in self.states.iter().enumerate() {
+            writeln!(text, "state\t{}\t{:?}", i, state).unwrap();
+        }
+        for event in &self.events {
+            match *event {
+                TraceEvent::Expand { state, cost } => writeln!(text, "expand\t{}\t{}", state, cost),
+                TraceEvent::Edge { from, to, cost } => {
+                    writeln!(text, "edge\t{}\t{}\t{}", from, to, cost)
+                }
+            }
+            .unwrap();
+        }
+        text
+    }
+
+    /// Write the trace to a file, see [`Trace::to_text`]
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()>
+    where
+        S: Debug,
+    {
+        std::fs::write(path, self.to_text())
+    }
+}
+
+impl Trace<String> {
+    /// Read back a trace written by [`Trace::to_text`], with each state as its `Debug` text
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut trace = Trace::new();
+
+        for (n, line) in text.lines().enumerate() {
+            let error = |reason: &str| ParseError::new(line, reason).at_line(n + 1);
+            let mut fields = line.split('\t');
+            let kind = fields.next().unwrap_or_default();
+            let mut number = || {
+                fields
+                    .next()
+                    .and_then(|field| field.parse::<u64>().ok())
+                    .ok_or_else(|| error("expected a number"))
+            };
+            let mut index = || {
+                let i = number()? as usize;
+                if i < trace.states.len() {
+                    Ok(i)
+                } else {
+                    Err(error("unknown state"))
+                }
+            };
+
+            match kind {
+                "state" => {
+                    let i = number()?;
+                    let state = fields.next().ok_or_else(|| error("expected a state"))?;
+                    if i as usize != trace.states.len() {
+                        return Err(error("states must be numbered in order"));
+                    }
+                    trace.states.push(state.to_string());
+                }
+                "expand" => {
+                    let state = index()?;
+                    let cost = number()?;
+                    trace.events.push(TraceEvent::Expand { state, cost });
+                }
+                "edge" => {
+                    let from = index()?;
+                    let to = index()?;
+                    let cost = number()?;
+                    trace.events.push(TraceEvent::Edge { from, to, cost });
+                }
+                _ => return Err(error("expected state, expand or edge")),
+            }
+        }
+
+        Ok(trace)
+    }
+}
+
+/// Debug builds check that the heuristic didn't overestimate the remaining cost anywhere along
+/// the path that was found, since that silently gives wrong answers rather than failing
+#[cfg(debug_assertions)]
+fn check_admissible<S, H>(nodes: &[Node<S>], goal: usize, heuristic: &mut H)
+where
+    H: FnMut(&S) -> u64,
+{
+    let total = nodes[goal].cost;
+    let mut i = Some(goal);
+    while let Some(j) = i {
+        let (estimate, remaining) = (heuristic(&nodes[j].state), total - nodes[j].cost);
+        assert!(
+            estimate <= remaining,
+            "A* heuristic isn't admissible: estimated {} with {} remaining",
+            estimate,
+            remaining
+        );
+        i = nodes[j].previous;
+    }
+}
+
+/// How [`grid_path`] searches the grid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GridSearch {
+    /// A* over every open cell
+    #[default]
+    AStar,
+    /// A* over jump points only, skipping the straight runs between them. Finds paths of the
+    /// same length, and is much faster when A* would have to flood large open areas, but
+    /// slower on grids full of scattered walls. See `bench_grid_path` in the tests.
+    JumpPoint,
+}
+
+/// Shortest path between two cells of a grid where every step costs 1, moving between cardinal
+/// neighbours. `open` says whether a cell can be entered, and must be false for cells off the
+/// grid. Returns the number of steps and the cells along the path, including the start and goal.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Coord};
+/// use aoc::search::{grid_path, GridSearch};
+///
+/// let board = Board::from_str("S....\n####.\n.....\n.####\n....E");
+/// let open = |c| board.get(&c).is_some_and(|&tile| tile != '#');
+///
+/// for method in [GridSearch::AStar, GridSearch::JumpPoint] {
+///     let (steps, path) = grid_path(Coord(0, 0), Coord(4, 4), method, open).unwrap();
+///     assert_eq!(steps, 16);
+///     assert_eq!(path.len(), 17);
+/// }
+/// ```
+pub fn grid_path<F>(
+    start: Coord,
+    goal: Coord,
+    method: GridSearch,
+    open: F,
+) -> Option<(u64, Vec<Coord>)>
+where
+    F: Fn(Coord) -> bool,
+{
+    let open = &open;
+    let heuristic = |c: &Coord| c.manhattan_distance(&goal) as u64;
+
+    match method {
+        GridSearch::AStar => astar(
+            [start],
+            |c| {
+                c.cardinal_neighbours()
+                    .into_iter()
+                    .filter(|&n| open(n))
+                    .map(|n| (n, 1))
+            },
+            heuristic,
+            |&c| c == goal,
+        ),
+        GridSearch::JumpPoint => {
+            // Jump points remember the direction they were reached from, which decides where
+            // the search can go next
+            let neighbours = |&(c, from): &(Coord, Option<Dir>)| {
+                let dirs = match from {
+                    None => Dir::cardinal().to_vec(),
+                    Some(dir) => vec![dir, dir.rotate_left(), dir.rotate_right()],
+                };
+                dirs.into_iter().filter_map(move |dir| {
+                    let (point, steps) = jump(c, dir, goal, open)?;
+                    Some(((point, Some(dir)), steps))
+                })
+            };
+
+            let (steps, points) = astar(
+                [(start, None)],
+                neighbours,
+                |(c, _)| heuristic(c),
+                |(c, _)| *c == goal,
+            )?;
+
+            let mut path = vec![start];
+            for (point, dir) in points.into_iter().skip(1) {
+                let dir = dir.unwrap();
+                let mut c = *path.last().unwrap();
+                while c != point {
+                    c = c + dir;
+                    path.push(c);
+                }
+            }
+            Some((steps, path))
+        }
+    }
+}
+
+/// Move from `from` in a cardinal direction until reaching a jump point, returning it and the
+/// number of steps taken, or None on hitting a wall first.
+///
+/// Paths are only allowed to turn from vertical to horizontal where a wall forces them to, so
+/// vertical runs stop there. Horizontal runs can turn anywhere, so they stop wherever a
+/// vertical run would find a jump point.
+fn jump<F>(from: Coord, dir: Dir, goal: Coord, open: &F) -> Option<(Coord, u64)>
+where
+    F: Fn(Coord) -> bool,
+{
+    let horizontal = matches!(dir, Dir::East | Dir::West);
+    let (left, right) = (dir.rotate_left(), dir.rotate_right());
+
+    let mut prev = from;
+    let mut steps = 0;
+    loop {
+        let c = prev + dir;
+        if !open(c) {
+            return None;
+        }
+        steps += 1;
+
+        let stop = if c == goal {
+            true
+        } else if horizontal {
+            jump(c, left, goal, open).is_some() || jump(c, right, goal, open).is_some()
+        } else {
+            let forced = |side: Dir| open(c + side) && !open(prev + side);
+            forced(left) || forced(right)
+        };
+        if stop {
+            return Some((c, steps));
+        }
+
+        prev = c;
+    }
+}
+
+/// Movement rules for [`turning_path`] and [`GridState::successors`]. The default is free turns
+/// with no limits on straight runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Movement {
+    /// Extra cost for each 90 degree turn, on top of the cost of the step after it
+    pub turn_cost: u64,
+    /// Steps that must be taken in a straight line before turning or stopping
+    pub min_straight: usize,
+    /// Most steps that can be taken in a straight line before having to turn
+    pub max_straight: Option<usize>,
+    /// Whether turning around (at the cost of two turns) is allowed
+    pub allow_reverse: bool,
+}
+
+/// A state for searching over a grid with [`Movement`] rules: where we are, which way we're
+/// facing, and how many steps we've taken in a straight line. Without a `max_straight`, the
+/// count stops at `min_straight`, since longer runs can all move the same way from there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridState {
+    pub pos: Coord,
+    pub dir: Dir,
+    pub steps_in_dir: u32,
+}
+
+impl GridState {
+    /// Standing at `pos` facing `dir`, before taking any steps
+    pub fn new(pos: Coord, dir: Dir) -> Self {
+        GridState {
+            pos,
+            dir,
+            steps_in_dir: 0,
+        }
+    }
+
+    /// Whether we've gone far enough in a straight line to turn or stop
+    pub fn can_stop(&self, movement: &Movement) -> bool {
+        self.steps_in_dir as usize >= movement.min_straight
+    }
+
+    /// States one step away and the cost of getting to them, for passing as the neighbours of
+    /// [`dijkstra`] and friends. `enter_cost` gives the cost of stepping into a cell, or None
+    /// for walls and cells off the grid, and any turn cost is added on top.
+    ///
+    /// # Panics
+    /// Panics if `min_straight` or `max_straight` don't fit in a `u32`, which `steps_in_dir`
+    /// couldn't count up to
+    ///
+    /// # Examples
+    /// ```
+    /// use aoc::grid_2d::{Coord, Dir};
+    /// use aoc::search::{GridState, Movement};
+    ///
+    /// let movement = Movement {
+    ///     turn_cost: 10,
+    ///     max_straight: Some(1),
+    ///     ..Default::default()
+    /// };
+    /// let state = GridState::new(Coord(0, 0), Dir::East);
+    ///
+    /// let next: Vec<_> = state.successors(&movement, |_| Some(1)).collect();
+    /// assert_eq!(next.len(), 3);
+    ///
+    /// // Having taken a step east, it has to turn
+    /// let (state, cost) = next[0];
+    /// assert_eq!((state.pos, state.steps_in_dir, cost), (Coord(0, 1), 1, 1));
+    /// assert!(state
+    ///     .successors(&movement, |_| Some(1))
+    ///     .all(|(next, cost)| next.dir != Dir::East && cost == 11));
+    /// ```
+    pub fn successors<F>(
+        self,
+        movement: &Movement,
+        enter_cost: F,
+    ) -> impl Iterator<Item = (GridState, u64)>
+    where
+        F: Fn(Coord) -> Option<u64>,
+    {
+        let fits = |limit: usize| u32::try_from(limit).is_ok();
+        assert!(
+            fits(movement.min_straight) && movement.max_straight.is_none_or(fits),
+            "straight run limits must fit in a u32"
+        );
+
+        let run = self.steps_in_dir as usize;
+        let can_continue = movement.max_straight.is_none_or(|max| run < max);
+
+        // Counting past min_straight with no max_straight would make a separate state for
+        // every length of run, multiplying the states by the length of each corridor
+        let count = |steps: u32| match movement.max_straight {
+            Some(_) => steps,
+            None => steps.min(movement.min_straight as u32),
+        };
+
+        let mut moves = Vec::with_capacity(4);
+        if can_continue {
+            moves.push((self.dir, count(self.steps_in_dir.saturating_add(1)), 0));
+        }
+        if self.can_stop(movement) {
+            let turned = count(1);
+            moves.push((self.dir.rotate_left(), turned, movement.turn_cost));
+            moves.push((self.dir.rotate_right(), turned, movement.turn_cost));
+            if movement.allow_reverse {
+                moves.push((self.dir.rotate_180(), turned, 2 * movement.turn_cost));
+            }
+        }
+
+        moves
+            .into_iter()
+            .filter_map(move |(dir, steps_in_dir, turn)| {
+                let pos = self.pos + dir;
+                let cost = enter_cost(pos)?;
+                Some((
+                    GridState {
+                        pos,
+                        dir,
+                        steps_in_dir,
+                    },
+                    turn + cost,
+                ))
+            })
+    }
+}
+
+/// Lowest cost path over a grid where the state is the position and facing direction, for
+/// puzzles with turn costs (reindeer mazes) or limits on straight runs (crucibles).
+///
+/// `enter_cost` gives the cost of stepping into a cell, or None for walls and cells off the
+/// grid. The search starts at `start` facing any of `facing`, moves between cardinal
+/// neighbours, and must end at `goal` having made at least `min_straight` steps since its last
+/// turn. Returns the cost and the cells along the path, including the start and goal.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Coord, Dir};
+/// use aoc::search::{turning_path, Movement};
+///
+/// let board = Board::from_str("S..\n##.\n..E");
+/// let movement = Movement {
+///     turn_cost: 1000,
+///     ..Default::default()
+/// };
+///
+/// let (cost, path) = turning_path(Coord(0, 0), &[Dir::East], Coord(2, 2), &movement, |c| {
+///     board.get(&c).filter(|&&tile| tile != '#').map(|_| 1)
+/// })
+/// .unwrap();
+///
+/// assert_eq!(cost, 1004);
+/// assert_eq!(path.len(), 5);
+/// ```
+pub fn turning_path<F>(
+    start: Coord,
+    facing: &[Dir],
+    goal: Coord,
+    movement: &Movement,
+    enter_cost: F,
+) -> Option<(u64, Vec<Coord>)>
+where
+    F: Fn(Coord) -> Option<u64>,
+{
+    let starts = facing.iter().map(|&dir| GridState::new(start, dir));
+
+    let enter_cost = &enter_cost;
+    let neighbours = |state: &GridState| state.successors(movement, enter_cost);
+    let is_goal = |state: &GridState| state.pos == goal && state.can_stop(movement);
+
+    dijkstra(starts, neighbours, is_goal)
+        .map(|(cost, path)| (cost, path.into_iter().map(|state| state.pos).collect()))
+}
+
+/// A set of poses, a cell of a board along with a direction, such as a beam or guard moving
+/// over a grid. Stored as one bit per direction for every cell, which is much faster than
+/// hashing for the millions of poses a simulation can visit.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Coord, Dir};
+/// use aoc::search::PoseSet;
+///
+/// let mut seen = PoseSet::new((3, 4));
+/// assert!(seen.insert(Coord(1, 2), Dir::East));
+/// assert!(!seen.insert(Coord(1, 2), Dir::East));
+/// assert!(seen.insert(Coord(1, 2), Dir::NorthWest));
+///
+/// assert!(seen.contains(Coord(1, 2), Dir::East));
+/// assert!(!seen.contains(Coord(1, 2), Dir::West));
+/// assert!(!seen.contains(Coord(5, 2), Dir::East));
+/// assert_eq!(seen.len(), 2);
+/// assert_eq!(seen.cells().collect::<Vec<_>>(), vec![Coord(1, 2)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoseSet {
+    rows: usize,
+    cols: usize,
+    /// The directions seen at each cell, as a bitmask indexed by `Dir as u8`
+    cells: Vec<u8>,
+    len: usize,
+}
+
+impl PoseSet {
+    /// An empty set for a board of the given (rows, cols) size
+    pub fn new(size: (usize, usize)) -> Self {
+        let (rows, cols) = size;
+        Self {
+            rows,
+            cols,
+            cells: vec![0; rows * cols],
+            len: 0,
+        }
+    }
+
+    fn index(&self, c: Coord) -> Option<usize> {
+        let (row, col) = (usize::try_from(c.0).ok()?, usize::try_from(c.1).ok()?);
+        (row < self.rows && col < self.cols).then_some(row * self.cols + col)
+    }
+
+    /// Whether a coordinate is on the board the set covers
+    pub fn in_bounds(&self, c: Coord) -> bool {
+        self.index(c).is_some()
+    }
+
+    /// Add a pose, returning whether it wasn't already in the set
+    ///
+    /// # Panics
+    /// Panics if the coordinate is off the board
+    pub fn insert(&mut self, c: Coord, dir: Dir) -> bool {
+        let i = self
+            .index(c)
+            .unwrap_or_else(|| panic!("{:?} is outside of the pose set", c));
+        let bit = 1 << dir as u8;
+        let new = self.cells[i] & bit == 0;
+        self.cells[i] |= bit;
+        self.len += new as usize;
+        new
+    }
+
+    /// Whether the set contains a pose. Poses off the board never are.
+    pub fn contains(&self, c: Coord, dir: Dir) -> bool {
+        self.index(c)
+            .is_some_and(|i| self.cells[i] & (1 << dir as u8) != 0)
+    }
+
+    /// Number of poses in the set
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Cells with at least one pose in the set, row by row
+    pub fn cells(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &dirs)| dirs != 0)
+            .map(|(i, _)| Coord((i / self.cols) as i32, (i % self.cols) as i32))
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+        self.len = 0;
+    }
+}
+
+/// Every pose reachable from `starts` on a board of the given (rows, cols) size, where `next`
+/// gives the poses that follow on from one. Poses that leave the board are dropped, and each
+/// pose is only followed once, so splitting beams and looping guards both terminate.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Coord, Dir};
+/// use aoc::search::reachable_poses;
+///
+/// // A guard walking forwards, turning right at the edges of a 3x3 room
+/// let inside = |c: Coord| (0..3).contains(&c.0) && (0..3).contains(&c.1);
+/// let poses = reachable_poses((3, 3), [(Coord(2, 0), Dir::North)], |c, dir| {
+///     let dir = if inside(c + dir) { dir } else { dir.rotate_right() };
+///     [(c + dir, dir)]
+/// });
+///
+/// // The start corner is visited facing north and west
+/// assert_eq!(poses.len(), 9);
+/// assert_eq!(poses.cells().count(), 8);
+/// ```
+pub fn reachable_poses<N, I>(
+    size: (usize, usize),
+    starts: impl IntoIterator<Item = (Coord, Dir)>,
+    mut next: N,
+) -> PoseSet
+where
+    N: FnMut(Coord, Dir) -> I,
+    I: IntoIterator<Item = (Coord, Dir)>,
+{
+    let mut seen = PoseSet::new(size);
+    let mut stack = Vec::new();
+
+    for (c, dir) in starts {
+        if seen.in_bounds(c) && seen.insert(c, dir) {
+            stack.push((c, dir));
+        }
+    }
+
+    while let Some((c, dir)) = stack.pop() {
+        for (c, dir) in next(c, dir) {
+            if seen.in_bounds(c) && seen.insert(c, dir) {
+                stack.push((c, dir));
+            }
+        }
+    }
+
+    seen
+}
+
+/// The point at position `i` of the cartesian product of `ranges`, with the last range varying
+/// fastest
+fn scan_point(ranges: &[Range<i64>], mut i: u64, point: &mut [i64]) {
+    for (range, x) in ranges.iter().zip(point.iter_mut()).rev() {
+        let len = range_len(range);
+        // Wrapping, as the offset can be past i64::MAX when the range starts below zero
+        *x = range.start.wrapping_add((i % len) as i64);
+        i /= len;
+    }
+}
+
+/// Number of values in a range, which can be up to `u64::MAX`
+fn range_len(range: &Range<i64>) -> u64 {
+    if range.end > range.start {
+        range.end.abs_diff(range.start)
+    } else {
+        0
+    }
+}
+
+/// Number of points in the cartesian product of `ranges`
+fn scan_len(ranges: &[Range<i64>]) -> u64 {
+    if ranges.iter().any(|range| range_len(range) == 0) {
+        return 0;
+    }
+    ranges
+        .iter()
+        .try_fold(1u64, |total, range| total.checked_mul(range_len(range)))
+        .expect("grid_scan over more than u64::MAX points")
+}
+
+/// Try every point in the cartesian product of `ranges`, in order with the last range varying
+/// fastest, and return the first where `f` finds something, along with what it found. For
+/// brute forcing the inputs that make a program produce some output.
+///
+/// # Panics
+/// Panics if there are more than `u64::MAX` points, even if one is found before then
+///
+/// # Examples
+/// ```
+/// use aoc::search::grid_scan;
+///
+/// let found = grid_scan(&[0..100, 0..100], |p| (p[0] * p[1] == 391).then_some(p[0] + p[1]));
+///
+/// assert_eq!(found, Some((vec![17, 23], 40)));
+/// ```
+pub fn grid_scan<F, T>(ranges: &[Range<i64>], mut f: F) -> Option<(Vec<i64>, T)>
+where
+    F: FnMut(&[i64]) -> Option<T>,
+{
+    let mut point = vec![0; ranges.len()];
+    for i in 0..scan_len(ranges) {
+        scan_point(ranges, i, &mut point);
+        if let Some(found) = f(&point) {
+            return Some((point, found));
+        }
+    }
+    None
+}
+
+/// [`grid_scan`] spread over every core. Finds the same point as [`grid_scan`] would, and
+/// stops as soon as no earlier point could still be found. Panics in the same way too.
+pub fn grid_scan_parallel<F, T>(ranges: &[Range<i64>], f: F) -> Option<(Vec<i64>, T)>
+where
+    F: Fn(&[i64]) -> Option<T> + Sync,
+    T: Send,
+{
+    let total = scan_len(ranges);
+    let workers = thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+    // Position of the earliest point found so far
+    let best = AtomicU64::new(u64::MAX);
+    let found = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for worker in 0..workers {
+            let (f, best, found) = (&f, &best, &found);
+            scope.spawn(move || {
+                let mut point = vec![0; ranges.len()];
+                let mut i = worker;
+                while i < total && i < best.load(Ordering::Relaxed) {
+                    scan_point(ranges, i, &mut point);
+                    if let Some(value) = f(&point) {
+                        let mut found = found.lock().unwrap();
+                        if i < best.fetch_min(i, Ordering::Relaxed) {
+                            *found = Some((point, value));
+                        }
+                        return;
+                    }
+                    i += workers;
+                }
+            });
+        }
+    });
+
+    found.into_inner().unwrap()
+}
+
+/// Smallest input for which `run` outputs exactly `target`, for machines that consume `bits`
+/// bits of their input for every value they output and stop once it's used up (like the
+/// self-replicating program of 2024 day 17).
+///
+/// Rather than trying every input, this builds it up from the most significant end: an input
+/// made of the top `k` chunks of the answer must already output the last `k` values of the
+/// target. Each chunk is tried in increasing order, backtracking when none work.
+///
+/// # Panics
+/// Panics if the input needs more than 64 bits, a chunk of `bits` for each value of `target`
+///
+/// # Examples
+/// ```
+/// use aoc::search::quine_search;
+///
+/// // a = a >> 3, output a % 8, loop until a is 0
+/// let run = |mut a: u64| {
+///     let mut out = Vec::new();
+///     loop {
+///         a >>= 3;
+///         out.push(a % 8);
+///         if a == 0 {
+///             return out;
+///         }
+///     }
+/// };
+///
+/// assert_eq!(quine_search(&[0, 3, 5, 4, 3, 0], 3, run), Some(117440));
+/// ```
+pub fn quine_search<R>(target: &[u64], bits: u32, mut run: R) -> Option<u64>
+where
+    R: FnMut(u64) -> Vec<u64>,
+{
+    assert!(
+        bits < 64 && bits as u128 * target.len() as u128 <= 64,
+        "{} bit chunks for a target of length {} don't fit in a u64",
+        bits,
+        target.len()
+    );
+
+    fn extend<R: FnMut(u64) -> Vec<u64>>(
+        prefix: u64,
+        matched: usize,
+        target: &[u64],
+        bits: u32,
+        run: &mut R,
+    ) -> Option<u64> {
+        if matched == target.len() {
+            return Some(prefix);
+        }
+
+        (0..1 << bits).find_map(|chunk| {
+            let input = (prefix << bits) | chunk;
+            if run(input) == target[target.len() - matched - 1..] {
+                extend(input, matched + 1, target, bits, run)
+            } else {
+                None
+            }
+        })
+    }
+
+    extend(0, 0, target, bits, &mut run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid_2d::Board;
+    use crate::rng::Rng;
+
+    #[test]
+    fn test_bfs_matches_dijkstra() {
+        let board = Board::from_str("S..#....\n.#.#.##.\n.#...#..\n.####.#.\n......#E");
+        let (start, goal) = (board.find(&'S')[0], board.find(&'E')[0]);
+        let open = |c: &Coord| board.get(c).is_some_and(|&tile| tile != '#');
+        let neighbours = |c: &Coord| c.cardinal_neighbours().into_iter().filter(open);
+
+        let (steps, path) = bfs([start], neighbours, |&c| c == goal).unwrap();
+        let expected = dijkstra([start], |c| neighbours(c).map(|n| (n, 1)), |&c| c == goal);
+        assert_eq!(Some(steps), expected.map(|(cost, _)| cost));
+        assert_eq!(path.len() as u64, steps + 1);
+
+        let distances = bfs_distances([start], neighbours);
+        assert_eq!(distances[&goal], steps);
+        assert_eq!(
+            distances.len(),
+            board.iter().filter(|&&tile| tile != '#').count()
+        );
+
+        assert_eq!(bfs([start], neighbours, |&c| c == Coord(0, 3)), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "heuristic isn't admissible")]
+    fn test_inadmissible_heuristic() {
+        astar(
+            [0i32],
+            |&n| [(n + 1, 1)],
+            |&n| (10 - n) as u64 * 2,
+            |&n| n == 10,
+        );
+    }
+
+    #[test]
+    fn test_tie_break_reading_order() {
+        // 2018 day 15, the elf moves towards the nearest square next to a goblin, taking the
+        // first in reading order when several are as near
+        let board = Board::from_str("#######\n#E..G.#\n#...#.#\n#.G.#G#\n#######");
+        let open = |c: &Coord| board.get(c) == Some(&'.');
+        let goblins = board.find(&'G');
+        let in_range = |c: &Coord| open(c) && goblins.iter().any(|g| g.manhattan_distance(c) == 1);
+
+        let (cost, path) = dijkstra_by(
+            board.find(&'E'),
+            |c| {
+                c.cardinal_neighbours()
+                    .into_iter()
+                    .filter(open)
+                    .map(|n| (n, 1))
+            },
+            in_range,
+            |c| (c.0, c.1),
+        )
+        .unwrap();
+        assert_eq!(cost, 2);
+        assert_eq!(path.last(), Some(&Coord(1, 3)));
+    }
+
+    #[test]
+    fn test_tie_break_fewest_turns() {
+        let board = Board::from_str("S.....\n.#.##.\n...#..\n.#....\n...#.E");
+        let (start, goal) = (board.find(&'S')[0], board.find(&'E')[0]);
+        let open = |c: &Coord| board.get(c).is_some_and(|&tile| tile != '#');
+        let turns = |path: &[Coord]| {
+            let dirs: Vec<Coord> = std::iter::once(Coord(0, 1))
+                .chain(path.windows(2).map(|w| w[1] - w[0]))
+                .collect();
+            dirs.windows(2).filter(|d| d[0] != d[1]).count() as u32
+        };
+
+        // Every shortest path, to find the fewest turns any of them take
+        let result = dijkstra_paths([start], |c| {
+            c.cardinal_neighbours()
+                .into_iter()
+                .filter(open)
+                .map(|n| (n, 1))
+        });
+        let paths = result.all_shortest_paths(&goal);
+        let fewest = paths.iter().map(|path| turns(path)).min().unwrap();
+        assert!(paths.iter().any(|path| turns(path) > fewest));
+
+        let (cost, path) = astar_by(
+            [(start, Dir::East, 0u32)],
+            |&(c, dir, turns)| {
+                Dir::cardinal()
+                    .into_iter()
+                    .map(move |d| ((c + d, d, turns + (d != dir) as u32), 1))
+                    .filter(|((c, _, _), _)| open(c))
+            },
+            |(c, _, _)| c.manhattan_distance(&goal) as u64,
+            |(c, _, _)| *c == goal,
+            |&(_, _, turns)| turns,
+        )
+        .unwrap();
+        assert_eq!(Some(cost), result.cost(&goal));
+        assert_eq!(path.last().unwrap().2, fewest);
+        let cells: Vec<Coord> = path.iter().map(|&(c, _, _)| c).collect();
+        assert_eq!(turns(&cells), fewest);
+    }
+
+    #[test]
+    fn test_dijkstra_paths_zero_cost() {
+        // a and b are free to move between, and so are c and d
+        let edges = [
+            ('a', 'b', 0),
+            ('b', 'a', 0),
+            ('a', 'c', 1),
+            ('b', 'c', 1),
+            ('c', 'd', 0),
+            ('d', 'c', 0),
+        ];
+        let result = dijkstra_paths(['a'], |&n| {
+            edges
+                .iter()
+                .filter(move |(from, _, _)| *from == n)
+                .map(|&(_, to, cost)| (to, cost))
+        });
+
+        assert_eq!(result.cost(&'b'), Some(0));
+        assert_eq!(result.cost(&'d'), Some(1));
+        assert_eq!(result.path_to(&'a'), Some(vec!['a']));
+        assert_eq!(result.path_to(&'b'), Some(vec!['a', 'b']));
+
+        let mut paths = result.all_shortest_paths(&'d');
+        paths.sort();
+        assert_eq!(paths, vec![vec!['a', 'b', 'c', 'd'], vec!['a', 'c', 'd']]);
+        assert_eq!(result.on_shortest_paths(&['d']).len(), 4);
+    }
+
+    #[test]
+    fn test_trace() {
+        // A diamond where the cheap way round is found second
+        let edges = [("a", "b", 1), ("a", "c", 3), ("b", "d", 5), ("c", "d", 1)];
+        let neighbours = |n: &&str| -> Vec<(&str, u64)> {
+            edges
+                .iter()
+                .filter(|(from, _, _)| from == n)
+                .map(|&(_, to, cost)| (to, cost))
+                .collect()
+        };
+
+        let mut trace = Trace::new();
+        let result = dijkstra_paths_traced(["a"], neighbours, &mut trace);
+        assert_eq!(result.cost(&"d"), Some(4));
+        assert_eq!(trace.states, vec!["a", "b", "c", "d"]);
+        assert_eq!(
+            trace.expanded().collect::<Vec<_>>(),
+            vec![(&"a", 0), (&"b", 1), (&"c", 3), (&"d", 4)]
+        );
+        // Every move looked at, including b to d which was later beaten
+        let edge_count = |trace: &Trace<_>| {
+            trace
+                .events
+                .iter()
+                .filter(|e| matches!(e, TraceEvent::Edge { .. }))
+                .count()
+        };
+        assert_eq!(edge_count(&trace), 4);
+
+        // Stopping at the goal leaves d unexpanded, and a reused trace starts afresh
+        astar_traced(["a"], neighbours, |_| 0, |&n| n == "d", |_| (), &mut trace);
+        assert_eq!(trace.expanded().count(), 4);
+        assert!(
+            astar_traced(["a"], neighbours, |_| 0, |&n| n == "c", |_| (), &mut trace).is_some()
+        );
+        assert_eq!(trace.expanded().count(), 3);
+        assert_eq!(trace.states.len(), 4);
+    }
+
+    #[test]
+    fn test_trace_text() {
+        let mut trace = Trace::new();
+        dijkstra_paths_traced(
+            [Coord(0, 0)],
+            |c| {
+                c.cardinal_neighbours()
+                    .into_iter()
+                    .filter(|n| (0..2).contains(&n.0) && (0..2).contains(&n.1))
+                    .map(|n| (n, 1))
+            },
+            &mut trace,
+        );
+
+        let text = trace.to_text();
+        assert!(text.starts_with("state\t0\tCoord(0, 0)\n"));
+        assert!(text.contains("expand\t0\t0\n"));
+
+        let parsed = Trace::parse(&text).unwrap();
+        let states: Vec<String> = trace.states.iter().map(|s| format!("{:?}", s)).collect();
+        assert_eq!(parsed.states, states);
+        assert_eq!(parsed.events, trace.events);
+
+        let err = Trace::parse("state\t0\ta\nedge\t0\t1\t1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse line 2: \"edge\\t0\\t1\\t1\" (unknown state)"
+        );
+        assert!(Trace::parse("state\t1\ta").is_err());
+        assert!(Trace::parse("visit\t0").is_err());
+    }
+
+    #[test]
+    fn test_grid_scan() {
+        // Every point is visited once, in order
+        let mut seen = Vec::new();
+        let found = grid_scan(&[-1..1, 3..6], |p| {
+            seen.push(p.to_vec());
+            None::<()>
+        });
+        assert_eq!(found, None);
+        assert_eq!(seen.len(), 6);
+        assert_eq!(seen[0], vec![-1, 3]);
+        assert_eq!(seen[5], vec![0, 5]);
+
+        assert_eq!(grid_scan(&[0..10, 5..5], |_| Some(())), None);
+    }
+
+    #[test]
+    fn test_grid_scan_wide_ranges() {
+        let full = i64::MIN..i64::MAX;
+        assert_eq!(scan_len(std::slice::from_ref(&full)), u64::MAX);
+        assert_eq!(scan_len(&[full.clone(), full.clone(), 0..0]), 0);
+
+        let found = grid_scan(&[0..1, full.clone()], |p| {
+            (p[1] == i64::MIN + 5).then_some(())
+        });
+        assert_eq!(found, Some((vec![0, i64::MIN + 5], ())));
+
+        // Past i64::MAX steps from the start
+        let mut point = [0];
+        scan_point(&[full], u64::MAX - 1, &mut point);
+        assert_eq!(point, [i64::MAX - 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid_scan over more than u64::MAX points")]
+    fn test_grid_scan_too_many_points() {
+        grid_scan(&[0..1 << 40, 0..1 << 40], |_| Some(()));
+    }
+
+    #[test]
+    fn test_grid_scan_parallel() {
+        let ranges = [0..50, 0..50, 0..50];
+        let f = |p: &[i64]| (p[0] + p[1] * p[2] == 60).then_some(p[0] * 10000 + p[1] * 100 + p[2]);
+
+        let expected = grid_scan(&ranges, f);
+        assert_eq!(expected, Some((vec![0, 2, 30], 230)));
+        assert_eq!(grid_scan_parallel(&ranges, f), expected);
+        assert_eq!(grid_scan_parallel(&ranges, |_| None::<()>), None);
+    }
+
+    #[test]
+    fn test_quine_search() {
+        // 2024 day 17: b = a % 8; b ^= 2; c = a >> b; b ^= c; b ^= 3; out b % 8; a >>= 3
+        let program = [2, 4, 1, 2, 7, 5, 4, 5, 1, 3, 5, 5, 0, 3, 3, 0];
+        let run = |mut a: u64| {
+            let mut out = Vec::new();
+            while a != 0 {
+                let b = (a % 8) ^ 2;
+                let b = b ^ (a >> b) ^ 3;
+                out.push(b % 8);
+                a >>= 3;
+            }
+            out
+        };
+
+        let a = quine_search(&program, 3, run).unwrap();
+        assert_eq!(run(a), program);
+
+        // Small enough to check against brute force
+        let tail = &program[program.len() - 5..];
+        let brute = (0..1 << 15).find(|&a| run(a) == tail);
+        assert_eq!(quine_search(tail, 3, run), brute);
+
+        assert_eq!(quine_search(&[9], 3, run), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "64 bit chunks for a target of length 1 don't fit in a u64")]
+    fn test_quine_search_too_wide() {
+        quine_search(&[0], 64, |a| vec![a]);
+    }
+
+    #[test]
+    fn test_dijkstra_unreachable() {
+        assert_eq!(
+            dijkstra(
+                [0i32],
+                |&n| [(n + 2, 1)].into_iter().filter(|(n, _)| *n < 100),
+                |&n| n == 7
+            ),
+            None
+        );
+    }
+
+    /// A random grid with roughly `density` of its cells walls
+    fn random_grid(rng: &mut Rng, rows: usize, cols: usize, density: f64) -> Board<bool> {
+        let mut board = Board::from_size((rows, cols), true);
+        for c in board.positions() {
+            board.set(&c, !rng.chance(density));
+        }
+        board
+    }
+
+    fn check_path(board: &Board<bool>, start: Coord, goal: Coord, path: &[Coord]) {
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for pair in path.windows(2) {
+            assert_eq!(pair[0].manhattan_distance(&pair[1]), 1);
+            assert_eq!(board.get(&pair[1]), Some(&true));
+        }
+    }
+
+    #[test]
+    fn test_jump_point_matches_astar() {
+        let mut rng = Rng::new(2025);
+        for case in 0..300 {
+            let (rows, cols) = (rng.range(1, 20) as usize, rng.range(1, 20) as usize);
+            let board = random_grid(&mut rng, rows, cols, [0.0, 0.1, 0.3, 0.45][case % 4]);
+            let open = |c| board.get(&c) == Some(&true);
+            let mut random_open = || {
+                let c = Coord(
+                    rng.range(0, rows as i64) as i32,
+                    rng.range(0, cols as i64) as i32,
+                );
+                open(c).then_some(c)
+            };
+            let (Some(start), Some(goal)) = (random_open(), random_open()) else {
+                continue;
+            };
+
+            let expected = grid_path(start, goal, GridSearch::AStar, open);
+            let actual = grid_path(start, goal, GridSearch::JumpPoint, open);
+            assert_eq!(
+                actual.as_ref().map(|(steps, _)| *steps),
+                expected.as_ref().map(|(steps, _)| *steps),
+                "{:?} to {:?} in case {}",
+                start,
+                goal,
+                case
+            );
+
+            for (steps, path) in [expected, actual].into_iter().flatten() {
+                assert_eq!(path.len() as u64, steps + 1);
+                check_path(&board, start, goal, &path);
+            }
+        }
+    }
+
+    // cargo test --release -p aoc bench_grid_path -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_grid_path() {
+        use std::time::Instant;
+
+        // Large rooms joined by gaps at alternating ends, which A* floods one by one
+        let mut rooms = Board::from_size((1000, 1000), true);
+        for (i, col) in [250, 500, 750].into_iter().enumerate() {
+            let gap = if i % 2 == 0 { 999 } else { 0 };
+            for row in (0..1000).filter(|&row| row != gap) {
+                rooms.set(&Coord(row, col), false);
+            }
+        }
+
+        let mut rng = Rng::new(8);
+        let grids = [
+            ("open", Board::from_size((1000, 1000), true)),
+            ("rooms", rooms),
+            ("10% walls", random_grid(&mut rng, 1000, 1000, 0.1)),
+        ];
+
+        for (name, mut board) in grids {
+            let (start, goal) = (Coord(0, 0), Coord(0, 999));
+            board.set(&start, true);
+            board.set(&goal, true);
+            let open = |c| board.get(&c) == Some(&true);
+
+            for method in [GridSearch::AStar, GridSearch::JumpPoint] {
+                let begin = Instant::now();
+                let steps = grid_path(start, goal, method, open).map(|(steps, _)| steps);
+                println!(
+                    "{} {:?}: {:?} in {:?}",
+                    name,
+                    method,
+                    steps,
+                    begin.elapsed()
+                );
+            }
+        }
+    }
+
+    fn crucible(input: &str, min_straight: usize, max_straight: usize) -> u64 {
+        let board = Board::from_str(input);
+        let (rows, cols) = board.size();
+        let goal = Coord(rows as i32 - 1, cols as i32 - 1);
+        let movement = Movement {
+            min_straight,
+            max_straight: Some(max_straight),
+            ..Default::default()
+        };
+
+        turning_path(
+            Coord(0, 0),
+            &[Dir::East, Dir::South],
+            goal,
+            &movement,
+            |c| board.get(&c).map(|d| d.to_digit(10).unwrap() as u64),
+        )
+        .unwrap()
+        .0
+    }
+
+    #[test]
+    fn test_crucible() {
+        // 2023 day 17
+        let input = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+        assert_eq!(crucible(input, 0, 3), 102);
+        assert_eq!(crucible(input, 4, 10), 94);
+
+        let input = "\
+111111111111
+999999999991
+999999999991
+999999999991
+999999999991";
+        assert_eq!(crucible(input, 4, 10), 71);
+    }
+
+    #[test]
+    fn test_long_straight_runs() {
+        let corridor =
+            |len: i32| move |c: Coord| (c.0 == 0 && (0..len).contains(&c.1)).then_some(1);
+
+        // Can't go 400 in a straight line with at most 300 at a time, and there's no room to turn
+        let limited = Movement {
+            max_straight: Some(300),
+            ..Default::default()
+        };
+        let path = turning_path(
+            Coord(0, 0),
+            &[Dir::East],
+            Coord(0, 399),
+            &limited,
+            corridor(400),
+        );
+        assert_eq!(path, None);
+        let path = turning_path(
+            Coord(0, 0),
+            &[Dir::East],
+            Coord(0, 299),
+            &limited,
+            corridor(400),
+        );
+        assert_eq!(path.map(|(cost, _)| cost), Some(299));
+
+        let at_least = Movement {
+            min_straight: 260,
+            ..Default::default()
+        };
+        let path = turning_path(
+            Coord(0, 0),
+            &[Dir::East],
+            Coord(0, 260),
+            &at_least,
+            corridor(300),
+        );
+        assert_eq!(path.map(|(cost, _)| cost), Some(260));
+        let path = turning_path(
+            Coord(0, 0),
+            &[Dir::East],
+            Coord(0, 259),
+            &at_least,
+            corridor(300),
+        );
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn test_grid_state_count() {
+        // Without a max_straight there's a state for each position and direction, and for each
+        // run shorter than min_straight
+        let size = 20;
+        let open = |c: Coord| ((0..size).contains(&c.0) && (0..size).contains(&c.1)).then_some(1);
+
+        for min_straight in [0, 3] {
+            let movement = Movement {
+                min_straight,
+                allow_reverse: true,
+                ..Default::default()
+            };
+            let mut expanded = HashSet::new();
+            let path = dijkstra(
+                [GridState::new(Coord(0, 0), Dir::East)],
+                |state: &GridState| {
+                    expanded.insert(*state);
+                    state.successors(&movement, open)
+                },
+                |_| false,
+            );
+            assert_eq!(path, None);
+
+            // Facing a way into a cell means having stepped in from the one behind it, apart
+            // from at the start
+            let poses = 4 * size as usize * (size as usize - 1) + 1;
+            let reached: HashSet<(Coord, Dir)> = expanded.iter().map(|s| (s.pos, s.dir)).collect();
+            assert_eq!(reached.len(), poses);
+            assert!(expanded.len() <= poses * (min_straight + 1));
+        }
+    }
+
+    #[test]
+    fn test_reindeer_maze() {
+        // 2024 day 16
+        let input = "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+        let board = Board::from_str(input);
+        let start = board.find(&'S')[0];
+        let goal = board.find(&'E')[0];
+        let movement = Movement {
+            turn_cost: 1000,
+            ..Default::default()
+        };
+
+        let (cost, path) = turning_path(start, &[Dir::East], goal, &movement, |c| {
+            board.get(&c).filter(|&&tile| tile != '#').map(|_| 1)
+        })
+        .unwrap();
+
+        assert_eq!(cost, 7036);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+
+        // Tiles on any of the best paths
+        let result = dijkstra_paths([(start, Dir::East)], |&(c, dir)| {
+            [
+                (dir, 1),
+                (dir.rotate_left(), 1001),
+                (dir.rotate_right(), 1001),
+            ]
+            .into_iter()
+            .map(move |(dir, cost)| ((c + dir, dir), cost))
+            .filter(|((c, _), _)| board.get(c).is_some_and(|&tile| tile != '#'))
+        });
+        let goals = Dir::cardinal().map(|dir| (goal, dir));
+        let tiles: HashSet<Coord> = result
+            .on_shortest_paths(&goals)
+            .into_iter()
+            .map(|(c, _)| c)
+            .collect();
+        assert_eq!(tiles.len(), 45);
+
+        let best = goals
+            .iter()
+            .min_by_key(|g| result.cost(g).unwrap_or(u64::MAX))
+            .unwrap();
+        let paths = result.all_shortest_paths(best);
+        assert_eq!(result.cost(best), Some(7036));
+        assert_eq!(paths.len(), 3);
+        assert!(paths.contains(&result.path_to(best).unwrap()));
+    }
+}
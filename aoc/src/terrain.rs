@@ -0,0 +1,165 @@
+use crate::grid_2d::{Board, Coord};
+
+/// Cells lower than all of their cardinal neighbours
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Coord};
+/// use aoc::terrain::low_points;
+///
+/// let heights = Board::from_str("1221\n2332").map(|c| c.to_digit(10).unwrap());
+///
+/// assert_eq!(low_points(&heights), vec![Coord(0, 0), Coord(0, 3)]);
+/// ```
+pub fn low_points<T>(board: &Board<T>) -> Vec<Coord>
+where
+    T: Clone + Ord,
+{
+    board
+        .enumerate_coords()
+        .filter(|(c, height)| board.cardinal_neighbours(c).all(|(_, n)| n > height))
+        .map(|(c, _)| c)
+        .collect()
+}
+
+/// Where a cell flows to: its lowest cardinal neighbour, if that's lower than it. Ties go to
+/// the first neighbour clockwise from the north.
+fn downhill<T>(board: &Board<T>, c: &Coord) -> Option<Coord>
+where
+    T: Clone + Ord,
+{
+    board
+        .cardinal_neighbours(c)
+        .filter(|(_, n)| *n < &board[*c])
+        .reduce(|lowest, next| if next.1 < lowest.1 { next } else { lowest })
+        .map(|(n, _)| n)
+}
+
+/// Which low point every cell drains to, see [`basins`]
+#[derive(Debug, Clone)]
+pub struct Basins {
+    pub low_points: Vec<Coord>,
+    /// Index into `low_points` of the basin each cell is in, if any
+    pub labels: Board<Option<usize>>,
+}
+
+impl Basins {
+    /// Number of cells in each basin, in the same order as `low_points`
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.low_points.len()];
+        for label in self.labels.iter().flatten() {
+            sizes[*label] += 1;
+        }
+        sizes
+    }
+
+    /// Sizes of the `n` largest basins, largest first
+    pub fn largest(&self, n: usize) -> Vec<usize> {
+        let mut sizes = self.sizes();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.truncate(n);
+        sizes
+    }
+}
+
+/// Split a height map into basins, by letting every cell flow to its lowest neighbour until it
+/// reaches a low point. Cells that are a `barrier`, or that end up on a flat area rather than a
+/// low point, aren't in any basin.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Coord};
+/// use aoc::terrain::basins;
+///
+/// let heights = Board::from_str("1239\n2399\n9921").map(|c| c.to_digit(10).unwrap());
+/// let basins = basins(&heights, |&h| h == 9);
+///
+/// assert_eq!(basins.low_points, vec![Coord(0, 0), Coord(2, 3)]);
+/// assert_eq!(basins.labels[Coord(1, 1)], Some(0));
+/// assert_eq!(basins.labels[Coord(0, 3)], None);
+/// assert_eq!(basins.sizes(), vec![5, 2]);
+/// ```
+pub fn basins<T, B>(board: &Board<T>, barrier: B) -> Basins
+where
+    T: Clone + Ord,
+    B: Fn(&T) -> bool,
+{
+    let low_points: Vec<Coord> = low_points(board)
+        .into_iter()
+        .filter(|c| !barrier(&board[*c]))
+        .collect();
+
+    // None until a cell's basin is known
+    let mut labels: Board<Option<Option<usize>>> = board.map(|_| None);
+    for (i, c) in low_points.iter().enumerate() {
+        labels[*c] = Some(Some(i));
+    }
+
+    for start in board.positions() {
+        // Walk downhill until reaching a cell we already know the basin of
+        let mut path = Vec::new();
+        let mut c = start;
+        let label = loop {
+            if let Some(label) = labels[c] {
+                break label;
+            }
+            if barrier(&board[c]) {
+                break None;
+            }
+            path.push(c);
+            match downhill(board, &c) {
+                Some(next) => c = next,
+                None => break None,
+            }
+        };
+
+        for c in path {
+            labels[c] = Some(label);
+        }
+    }
+
+    Basins {
+        low_points,
+        labels: labels.map(|label| label.flatten()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEIGHTS: &str = "\
+2199943210
+3987894921
+9856789892
+8767896789
+9899965678";
+
+    #[test]
+    fn test_smoke_basin() {
+        // 2021 day 9
+        let board = Board::from_str(HEIGHTS).map(|c| c.to_digit(10).unwrap());
+
+        let risk: u32 = low_points(&board).iter().map(|c| board[*c] + 1).sum();
+        assert_eq!(risk, 15);
+
+        let basins = basins(&board, |&h| h == 9);
+        assert_eq!(basins.sizes(), vec![3, 9, 14, 9]);
+        assert_eq!(basins.largest(3), vec![14, 9, 9]);
+
+        // Every cell other than the 9s drains somewhere
+        let nines = board.iter().filter(|&&h| h == 9).count();
+        assert_eq!(basins.labels.iter().flatten().count() + nines, 50);
+    }
+
+    #[test]
+    fn test_plateau() {
+        let board = Board::from_str("555\n515\n555").map(|c| c.to_digit(10).unwrap());
+        let basins = basins(&board, |_| false);
+
+        assert_eq!(basins.low_points, vec![Coord(1, 1)]);
+        // Corners only touch other 5s, so never flow anywhere
+        assert_eq!(basins.labels[Coord(0, 0)], None);
+        assert_eq!(basins.sizes(), vec![5]);
+    }
+}
@@ -0,0 +1,689 @@
+use crate::grid_2d::{Board, Coord};
+use crate::rng::Rng;
+use crate::runner::{Answer, IntoAnswer};
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::fs;
+use std::io::IsTerminal;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+/// Gap between the columns of a diff
+const GAP: &str = "   ";
+
+fn color() -> bool {
+    std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Assert that a board renders as `expected`, one character per cell. On failure, panics with
+/// the expected and actual boards side by side, with the mismatched cells highlighted.
+///
+/// Leading and trailing whitespace is ignored on every line of `expected`, so it can be an
+/// indented string literal.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::{Board, Coord};
+/// use aoc::testing::assert_board_eq;
+///
+/// let mut board = Board::from_str("...\n...");
+/// board.set(&Coord(1, 2), '#');
+///
+/// assert_board_eq(
+///     "
+///     ...
+///     ..#
+///     ",
+///     &board,
+/// );
+/// ```
+pub fn assert_board_eq<T>(expected: &str, board: &Board<T>)
+where
+    T: Clone + Display,
+{
+    if let Some(diff) = board_diff(expected, board, color()) {
+        panic!("boards differ\n{}", diff);
+    }
+}
+
+/// Side by side rendering of the expected and actual boards, plus a column marking each
+/// mismatched cell with `X`. Returns None if the boards match.
+fn board_diff<T>(expected: &str, board: &Board<T>, color: bool) -> Option<String>
+where
+    T: Clone + Display,
+{
+    let expected: Vec<Vec<String>> = expected
+        .trim()
+        .lines()
+        .map(|line| line.trim().chars().map(String::from).collect())
+        .collect();
+    let actual: Vec<Vec<String>> = board
+        .matrix
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+        .collect();
+
+    let rows = expected.len().max(actual.len());
+    let cols = expected
+        .iter()
+        .chain(actual.iter())
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+
+    let cell = |grid: &[Vec<String>], row: usize, col: usize| {
+        grid.get(row).and_then(|r| r.get(col)).cloned()
+    };
+
+    // In row-major order, so the first is the top-left most
+    let mut mismatches = BTreeSet::new();
+    for row in 0..rows {
+        for col in 0..cols {
+            if cell(&expected, row, col) != cell(&actual, row, col) {
+                mismatches.insert((row, col));
+            }
+        }
+    }
+
+    if mismatches.is_empty() {
+        return None;
+    }
+
+    // Each column of the diff as (visible width, lines), so escape codes don't break alignment
+    let render = |grid: &[Vec<String>], highlight: bool| -> Vec<(usize, String)> {
+        (0..rows)
+            .map(|row| {
+                let mut width = 0;
+                let mut line = String::new();
+                for (col, c) in grid.get(row).into_iter().flatten().enumerate() {
+                    width += c.chars().count();
+                    if highlight && mismatches.contains(&(row, col)) {
+                        line.push_str(&format!("\x1b[1;31m{}\x1b[0m", c));
+                    } else {
+                        line.push_str(c);
+                    }
+                }
+                (width, line)
+            })
+            .collect()
+    };
+
+    let marks: Vec<Vec<String>> = (0..rows)
+        .map(|row| {
+            (0..cols)
+                .map(|col| {
+                    let mismatch = mismatches.contains(&(row, col));
+                    if mismatch { "X" } else { " " }.to_string()
+                })
+                .collect()
+        })
+        .collect();
+
+    let columns = [
+        ("expected", render(&expected, false)),
+        ("actual", render(&actual, color)),
+        ("diff", render(&marks, color)),
+    ];
+
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|(title, lines)| {
+            lines
+                .iter()
+                .map(|(width, _)| *width)
+                .max()
+                .unwrap_or(0)
+                .max(title.len())
+        })
+        .collect();
+
+    let pad = |text: &str, visible: usize, width: usize| {
+        format!("{}{}", text, " ".repeat(width - visible))
+    };
+
+    let mut out = String::new();
+    let header: Vec<String> = columns
+        .iter()
+        .zip(&widths)
+        .map(|((title, _), width)| pad(title, title.len(), *width))
+        .collect();
+    out.push_str(header.join(GAP).trim_end());
+    out.push('\n');
+
+    let underline: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    out.push_str(&underline.join(GAP));
+    out.push('\n');
+
+    for row in 0..rows {
+        let line: Vec<String> = columns
+            .iter()
+            .zip(&widths)
+            .map(|((_, lines), width)| pad(&lines[row].1, lines[row].0, *width))
+            .collect();
+        out.push_str(line.join(GAP).trim_end());
+        out.push('\n');
+    }
+
+    out.push_str(&format!(
+        "{} mismatched cell(s), first at {:?}\n",
+        mismatches.len(),
+        Coord::from(*mismatches.first().unwrap())
+    ));
+
+    Some(out)
+}
+
+/// Where snapshots are stored: `tests/snapshots/<name>.txt` in the crate being tested
+fn snapshot_path(name: &str) -> PathBuf {
+    // Cargo sets this when running tests, pointing at the crate under test rather than `aoc`
+    let root = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    root.join("tests")
+        .join("snapshots")
+        .join(format!("{}.txt", name))
+}
+
+/// Whether snapshots should be overwritten rather than compared, with `AOC_UPDATE_SNAPSHOTS=1`
+fn update_snapshots() -> bool {
+    std::env::var("AOC_UPDATE_SNAPSHOTS").is_ok_and(|v| v != "0" && !v.is_empty())
+}
+
+/// Compare `rendered` to the snapshot at `path`. New snapshots are written rather than compared,
+/// as are all snapshots when `update` is set. Returns the stored snapshot if it differs.
+fn check_snapshot(path: &Path, rendered: &str, update: bool) -> Option<String> {
+    match fs::read_to_string(path) {
+        Ok(stored) if stored == rendered => None,
+        Ok(stored) if !update => Some(stored),
+        _ => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).expect("Failed to create snapshot directory");
+            }
+            fs::write(path, rendered)
+                .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+            eprintln!("Wrote snapshot {}", path.display());
+            None
+        }
+    }
+}
+
+/// Assert that `rendered` matches the snapshot `name` stored from a previous run, under
+/// `tests/snapshots/` of the crate being tested. Useful for locking down the intermediate states
+/// of a simulation, eg. the [render](crate::debug_view::DebugView::render) of each step.
+///
+/// The first run writes the snapshot. After an intentional change, re-run the tests with
+/// `AOC_UPDATE_SNAPSHOTS=1` to overwrite them.
+pub fn assert_snapshot(name: &str, rendered: &str) {
+    let path = snapshot_path(name);
+
+    if let Some(stored) = check_snapshot(&path, rendered, update_snapshots()) {
+        let (stored, rendered): (Vec<&str>, Vec<&str>) =
+            (stored.lines().collect(), rendered.lines().collect());
+
+        let mut diff = String::new();
+        for i in 0..stored.len().max(rendered.len()) {
+            match (stored.get(i), rendered.get(i)) {
+                (Some(a), Some(b)) if a == b => diff.push_str(&format!("  {}\n", a)),
+                (a, b) => {
+                    if let Some(a) = a {
+                        diff.push_str(&format!("- {}\n", a));
+                    }
+                    if let Some(b) = b {
+                        diff.push_str(&format!("+ {}\n", b));
+                    }
+                }
+            }
+        }
+
+        panic!(
+            "snapshot {} differs, re-run with AOC_UPDATE_SNAPSHOTS=1 if that's expected\n{}",
+            path.display(),
+            diff
+        );
+    }
+}
+
+/// Assert that a board matches the snapshot `name`, like [`assert_snapshot`] but showing the
+/// mismatched cells side by side on failure like [`assert_board_eq`].
+pub fn assert_board_snapshot<T>(name: &str, board: &Board<T>)
+where
+    T: Clone + Display,
+{
+    let rendered: String = board
+        .matrix
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect::<String>() + "\n")
+        .collect();
+    let path = snapshot_path(name);
+
+    if let Some(stored) = check_snapshot(&path, &rendered, update_snapshots()) {
+        let diff = board_diff(&stored, board, color()).unwrap_or_default();
+        panic!(
+            "snapshot {} differs, re-run with AOC_UPDATE_SNAPSHOTS=1 if that's expected\n{}",
+            path.display(),
+            diff
+        );
+    }
+}
+
+/// Settings for [`find_counterexample`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EquivConfig {
+    /// How many random inputs to try
+    pub cases: usize,
+    /// Inputs are generated with sizes cycling from 1 up to this, so small counterexamples
+    /// are found first
+    pub max_size: usize,
+}
+
+impl Default for EquivConfig {
+    fn default() -> Self {
+        Self {
+            cases: 256,
+            max_size: 32,
+        }
+    }
+}
+
+/// An input two solutions disagree on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    pub input: String,
+    /// The answer of each solution, or the error it returned or panicked with
+    pub expected: Result<Answer, String>,
+    pub actual: Result<Answer, String>,
+    /// How many lines the generated input had before shrinking
+    pub original_lines: usize,
+    pub seed: u64,
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |outcome: &Result<Answer, String>| match outcome {
+            Ok(answer) => answer.to_string(),
+            Err(failure) => format!("failed: {}", failure),
+        };
+
+        writeln!(
+            f,
+            "solutions disagree on this input (seed {}, shrunk from {} lines):",
+            self.seed, self.original_lines
+        )?;
+        write!(f, "{}", self.input)?;
+        if !self.input.ends_with('\n') {
+            writeln!(f)?;
+        }
+        writeln!(f, "reference: {}", show(&self.expected))?;
+        write!(f, "candidate: {}", show(&self.actual))
+    }
+}
+
+thread_local! {
+    /// Whether panics on this thread are being caught and reported some other way
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `f` without the panic hook printing anything for panics on this thread. Other threads,
+/// like tests running alongside, still get their panics printed.
+fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.get() {
+                previous(info);
+            }
+        }));
+    });
+
+    let was_quiet = QUIET.replace(true);
+    let result = f();
+    QUIET.set(was_quiet);
+    result
+}
+
+/// Run a solution, catching any panic or returned error as its message
+fn outcome<A: IntoAnswer>(solution: impl Fn(&str) -> A, input: &str) -> Result<Answer, String> {
+    quietly(|| panic::catch_unwind(AssertUnwindSafe(|| solution(input).into_answer())))
+        .map_err(|panic| {
+            panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string())
+        })
+        .and_then(|answer| answer.map_err(|e| e.to_string()))
+}
+
+/// Remove as many lines from a failing input as possible while it keeps failing, trying big
+/// chunks first and then smaller ones
+fn shrink_lines(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let trailing_newline = input.ends_with('\n');
+    let join = |lines: &[&str]| {
+        let mut joined = lines.join("\n");
+        if trailing_newline && !lines.is_empty() {
+            joined.push('\n');
+        }
+        joined
+    };
+
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<&str> = [&lines[..start], &lines[end..]].concat();
+
+            if !candidate.is_empty() && fails(&join(&candidate)) {
+                lines = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+
+    join(&lines)
+}
+
+/// Byte ranges of the runs of digits in `input`
+fn number_spans(input: &str) -> Vec<Range<usize>> {
+    let mut spans: Vec<Range<usize>> = Vec::new();
+    for (i, b) in input.bytes().enumerate() {
+        if !b.is_ascii_digit() {
+            continue;
+        }
+        match spans.last_mut() {
+            Some(span) if span.end == i => span.end += 1,
+            _ => spans.push(i..i + 1),
+        }
+    }
+    spans
+}
+
+/// Make each number in a failing input as small as possible while it keeps failing, by binary
+/// searching between 0 and its value. Numbers too big for a `u64` are left alone.
+fn shrink_numbers(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut input = input.to_string();
+
+    // Replacing digits with digits keeps the same number of spans
+    for i in 0..number_spans(&input).len() {
+        let span = number_spans(&input)[i].clone();
+        let Ok(value) = input[span.clone()].parse::<u64>() else {
+            continue;
+        };
+        let with = |n: u64| {
+            let mut candidate = input.clone();
+            candidate.replace_range(span.clone(), &n.to_string());
+            candidate
+        };
+
+        // `hi` always fails, and everything below `lo` that's been tried passes
+        let (mut lo, mut hi) = (0, value);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if fails(&with(mid)) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        input = with(hi);
+    }
+
+    input
+}
+
+/// Property test that two solutions to the same puzzle agree, eg. a brute force reference and
+/// an optimized candidate. Runs both on random inputs from `generate` (see
+/// [`GenInput`](crate::geninput::GenInput)), treating a panic as an answer of its own, and
+/// without printing it. The first input they disagree on is shrunk by removing lines and then
+/// making the numbers in it smaller, while they still disagree.
+pub fn find_counterexample<A, B>(
+    reference: impl Fn(&str) -> A,
+    candidate: impl Fn(&str) -> B,
+    generate: impl Fn(usize, &mut Rng) -> String,
+    config: EquivConfig,
+) -> Option<Counterexample>
+where
+    A: IntoAnswer,
+    B: IntoAnswer,
+{
+    let disagree = |input: &str| outcome(&reference, input) != outcome(&candidate, input);
+
+    (0..config.cases as u64).find_map(|seed| {
+        let size = seed as usize % config.max_size.max(1) + 1;
+        let input = generate(size, &mut Rng::new(seed));
+        if !disagree(&input) {
+            return None;
+        }
+
+        let original_lines = input.lines().count();
+        let input = shrink_lines(&input, disagree);
+        let input = shrink_numbers(&input, disagree);
+
+        Some(Counterexample {
+            expected: outcome(&reference, &input),
+            actual: outcome(&candidate, &input),
+            input,
+            original_lines,
+            seed,
+        })
+    })
+}
+
+/// Define a test that two solutions agree on random inputs from a day's
+/// [`GenInput`](crate::geninput::GenInput), failing with a shrunk counterexample if they don't.
+/// Attributes like `#[ignore]` are passed through to the test, and the number of cases and
+/// maximum input size can be set with an [`EquivConfig`].
+///
+/// ```ignore
+/// aoc::proptest_equiv!(
+///     test_smart_matches_brute_force,
+///     solution,
+///     solution_smart,
+///     crate::geninput::Rotations
+/// );
+/// ```
+#[macro_export]
+macro_rules! proptest_equiv {
+    ($(#[$attr:meta])* $name:ident, $reference:expr, $candidate:expr, $generator:ty) => {
+        $crate::proptest_equiv!(
+            $(#[$attr])*
+            $name,
+            $reference,
+            $candidate,
+            $generator,
+            $crate::testing::EquivConfig::default()
+        );
+    };
+    ($(#[$attr:meta])* $name:ident, $reference:expr, $candidate:expr, $generator:ty, $config:expr) => {
+        #[test]
+        $(#[$attr])*
+        fn $name() {
+            if let Some(counterexample) = $crate::testing::find_counterexample(
+                $reference,
+                $candidate,
+                <$generator as $crate::geninput::GenInput>::generate,
+                $config,
+            ) {
+                panic!("{}", counterexample);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_boards() {
+        let board = Board::from_str("#.\n.#");
+        assert_eq!(board_diff("#.\n.#", &board, false), None);
+        assert_eq!(board_diff("\n    #.\n    .#\n", &board, false), None);
+    }
+
+    #[test]
+    fn test_diff() {
+        let board = Board::from_str("#..\n.#.");
+
+        let expected = "\
+expected   actual   diff
+--------   ------   ----
+#..        #..
+..#        .#.       XX
+2 mismatched cell(s), first at Coord(1, 1)
+";
+        assert_eq!(board_diff("#..\n..#", &board, false).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_diff_different_sizes() {
+        let board = Board::from_str("#..\n.#.");
+
+        let diff = board_diff("#..\n.#.\n...", &board, false).unwrap();
+        assert!(diff.ends_with("3 mismatched cell(s), first at Coord(2, 0)\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "boards differ")]
+    fn test_assert_board_eq_panics() {
+        assert_board_eq("##", &Board::from_str("#."));
+    }
+
+    #[test]
+    fn test_check_snapshot() {
+        let path = std::env::temp_dir()
+            .join(format!("aoc-snapshot-test-{}", std::process::id()))
+            .join("board.txt");
+
+        // Written on the first run
+        assert_eq!(check_snapshot(&path, "#.\n.#\n", false), None);
+        assert_eq!(check_snapshot(&path, "#.\n.#\n", false), None);
+
+        assert_eq!(
+            check_snapshot(&path, "##\n.#\n", false),
+            Some("#.\n.#\n".to_string())
+        );
+
+        // Updating overwrites it
+        assert_eq!(check_snapshot(&path, "##\n.#\n", true), None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "##\n.#\n");
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    /// Every line as a number, one per line
+    fn numbers(size: usize, rng: &mut Rng) -> String {
+        (0..size)
+            .map(|_| format!("{}\n", rng.range(0, 99)))
+            .collect()
+    }
+
+    fn sum(input: &str) -> u32 {
+        input.lines().map(|l| l.parse::<u32>().unwrap()).sum()
+    }
+
+    #[test]
+    fn test_equivalent_solutions() {
+        let reversed_sum = |input: &str| {
+            input
+                .lines()
+                .rev()
+                .map(|l| l.parse::<u32>().unwrap())
+                .sum::<u32>()
+        };
+        assert_eq!(
+            find_counterexample(sum, reversed_sum, numbers, EquivConfig::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_counterexample_is_shrunk() {
+        // Wrong whenever there's a 7 in the ones place
+        let buggy = |input: &str| {
+            input
+                .lines()
+                .map(|l| l.parse::<u32>().unwrap())
+                .map(|n| if n % 10 == 7 { n + 1 } else { n })
+                .sum::<u32>()
+        };
+
+        let counterexample =
+            find_counterexample(sum, buggy, numbers, EquivConfig::default()).unwrap();
+
+        assert_eq!(counterexample.input.lines().count(), 1);
+        assert!(counterexample.input.trim().ends_with('7'));
+        assert_eq!(
+            counterexample.actual,
+            Ok(Answer::from(
+                counterexample.input.trim().parse::<u32>().unwrap() + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn test_numbers_are_shrunk() {
+        // Wrong whenever there's a number over 40
+        let buggy = |input: &str| {
+            input
+                .lines()
+                .map(|l| l.parse::<u32>().unwrap())
+                .map(|n| n.min(40))
+                .sum::<u32>()
+        };
+
+        let counterexample =
+            find_counterexample(sum, buggy, numbers, EquivConfig::default()).unwrap();
+
+        assert_eq!(counterexample.input, "41\n");
+        assert_eq!(counterexample.expected, Ok(Answer::from(41u32)));
+    }
+
+    #[test]
+    fn test_shrink_numbers() {
+        let fails = |input: &str| input.contains("y4");
+        assert_eq!(shrink_numbers("x123 y45 007", fails), "x0 y40 0");
+        assert_eq!(number_spans("a1 22b"), vec![1..2, 3..5]);
+    }
+
+    #[test]
+    fn test_panics_are_counterexamples() {
+        let panics = |input: &str| -> u32 {
+            assert!(input.lines().count() < 3, "too many lines");
+            sum(input)
+        };
+
+        let counterexample =
+            find_counterexample(sum, panics, numbers, EquivConfig::default()).unwrap();
+
+        assert_eq!(counterexample.input.lines().count(), 3);
+        assert_eq!(counterexample.actual, Err("too many lines".to_string()));
+    }
+
+    proptest_equiv!(
+        test_macro,
+        |input: &str| input.lines().count(),
+        |input: &str| input.matches('\n').count(),
+        Lines,
+        EquivConfig {
+            cases: 20,
+            max_size: 10
+        }
+    );
+
+    struct Lines;
+
+    impl crate::geninput::GenInput for Lines {
+        fn generate(size: usize, rng: &mut Rng) -> String {
+            numbers(size, rng)
+        }
+    }
+}
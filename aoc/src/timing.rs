@@ -0,0 +1,176 @@
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+/// Total time spent in one named phase of a solution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phase {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    /// How many times the phase was entered
+    pub calls: usize,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.3} ms",
+            self.name,
+            self.elapsed.as_secs_f64() * 1000.0
+        )?;
+        if self.calls > 1 {
+            write!(f, " ({} calls)", self.calls)?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static PHASES: RefCell<Vec<Phase>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(name: &'static str, elapsed: Duration, calls: usize) {
+    PHASES.with_borrow_mut(|phases| match phases.iter_mut().find(|p| p.name == name) {
+        Some(phase) => {
+            phase.elapsed += elapsed;
+            phase.calls += calls;
+        }
+        None => phases.push(Phase {
+            name,
+            elapsed,
+            calls,
+        }),
+    });
+}
+
+/// Times a phase until dropped, see [`phase`]
+#[must_use = "the phase ends as soon as the guard is dropped"]
+pub struct PhaseGuard {
+    name: &'static str,
+    start: Instant,
+}
+
+impl Drop for PhaseGuard {
+    fn drop(&mut self) {
+        record(self.name, self.start.elapsed(), 1);
+    }
+}
+
+/// Start timing a phase of a solution, which lasts until the returned guard is dropped. Phases
+/// with the same name add up, and are printed by the runner with `--time`. See also [`time!`]
+/// for timing a single expression.
+///
+/// [`time!`]: crate::time
+///
+/// # Examples
+/// ```
+/// use aoc::timing;
+///
+/// fn solution(input: &str) -> usize {
+///     let graph = {
+///         let _phase = timing::phase("build graph");
+///         input.lines().collect::<Vec<_>>()
+///     };
+///
+///     let _phase = timing::phase("search");
+///     graph.len()
+/// }
+///
+/// timing::take_report();
+/// solution("a\nb");
+/// let report = timing::take_report();
+/// assert_eq!(report[0].name, "build graph");
+/// assert_eq!(report[1].name, "search");
+/// ```
+pub fn phase(name: &'static str) -> PhaseGuard {
+    PhaseGuard {
+        name,
+        start: Instant::now(),
+    }
+}
+
+/// Phases recorded on this thread since the last call, in the order they first finished
+pub fn take_report() -> Vec<Phase> {
+    PHASES.take()
+}
+
+/// Add phases recorded elsewhere, eg. on a thread the solution ran on, to this thread's report
+pub fn extend_report(phases: Vec<Phase>) {
+    for phase in phases {
+        record(phase.name, phase.elapsed, phase.calls);
+    }
+}
+
+/// Time an expression as a named phase of the solution, see [`phase`]
+///
+/// ```
+/// let numbers: Vec<u32> = aoc::time!("parse", vec![1, 2, 3]);
+/// let total = aoc::time!("sum", numbers.iter().sum::<u32>());
+///
+/// assert_eq!(total, 6);
+/// assert_eq!(aoc::timing::take_report().len(), 2);
+/// ```
+#[macro_export]
+macro_rules! time {
+    ($name:expr, $e:expr) => {{
+        let _phase = $crate::timing::phase($name);
+        $e
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases_add_up() {
+        take_report();
+        for _ in 0..3 {
+            let _outer = phase("outer");
+            crate::time!("inner", std::thread::sleep(Duration::from_millis(1)));
+        }
+
+        let report = take_report();
+        let names: Vec<_> = report.iter().map(|p| (p.name, p.calls)).collect();
+        // Nested phases are reported separately, by when they first finished
+        assert_eq!(names, vec![("inner", 3), ("outer", 3)]);
+        assert!(report[0].elapsed >= Duration::from_millis(3));
+        assert!(report[1].elapsed >= report[0].elapsed);
+
+        assert!(take_report().is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let mut phase = Phase {
+            name: "search",
+            elapsed: Duration::from_micros(1522),
+            calls: 1,
+        };
+        assert_eq!(phase.to_string(), "search: 1.522 ms");
+
+        phase.calls = 4;
+        assert_eq!(phase.to_string(), "search: 1.522 ms (4 calls)");
+    }
+
+    #[test]
+    fn test_extend_report() {
+        take_report();
+        record("parse", Duration::from_millis(2), 1);
+
+        let other = std::thread::spawn(|| {
+            crate::time!("parse", ());
+            crate::time!("solve", ());
+            take_report()
+        })
+        .join()
+        .unwrap();
+        extend_report(other);
+
+        let report = take_report();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].calls, 2);
+        assert!(report[0].elapsed >= Duration::from_millis(2));
+    }
+}
@@ -1,4 +1,11 @@
-use std::io::stdin;
+use crate::config::config;
+use crate::debug_view::DebugView;
+use std::fs;
+use std::io::{stdin, stdout, IsTerminal};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::time::Duration;
 
 /// Prompt for user input
 pub fn prompt(text: &str) -> String {
@@ -14,3 +21,133 @@ pub fn prompt(text: &str) -> String {
 pub fn wait() {
     stdin().read_line(&mut String::new()).unwrap();
 }
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// How many steps have been written to the `frames_dir`
+static FRAMES_WRITTEN: AtomicUsize = AtomicUsize::new(0);
+
+/// The last frame drawn by [`step`] with `clear` set, to redraw only what changed
+static PREVIOUS_FRAME: Mutex<Option<String>> = Mutex::new(None);
+
+/// Turn on [`step`], eg. for a day run with `--visualize`
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Step through a simulation: print the labelled state, then wait for an enter press. The
+/// `[visualize]` config can make it advance on a timer and clear the screen between steps.
+/// When clearing, only the characters that changed since the previous step are redrawn, which
+/// assumes each step fits on the screen.
+///
+/// With a `frames_dir` configured, each step is also saved as `frame-00001.txt` and so on. When
+/// stdout isn't a terminal (eg. redirected to a file, or on a server) steps are printed plainly
+/// one after another, without escape codes or waiting.
+///
+/// Does nothing unless visualization is [enabled](enable), so solutions can leave their steps in.
+pub fn step<V: DebugView>(label: &str, state: &V) {
+    if !enabled() {
+        return;
+    }
+
+    let settings = &config().visualize;
+
+    let frame = format!("== {} ==\n{}", label, state.render());
+
+    if let Some(dir) = &settings.frames_dir {
+        let n = FRAMES_WRITTEN.fetch_add(1, Ordering::Relaxed) + 1;
+        let path = dir.join(format!("frame-{:05}.txt", n));
+        fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&path, &frame))
+            .unwrap_or_else(|e| panic!("Failed to write frame {}: {}", path.display(), e));
+    }
+
+    if !stdout().is_terminal() {
+        print!("{}", frame);
+        return;
+    }
+
+    if settings.clear {
+        let mut previous = PREVIOUS_FRAME.lock().unwrap();
+        match previous.as_deref() {
+            Some(previous) => print!("{}", redraw(previous, &frame)),
+            None => print!("\x1b[2J\x1b[H{}", frame),
+        }
+        *previous = Some(frame);
+    } else {
+        print!("{}", frame);
+    }
+
+    match settings.delay_ms {
+        Some(delay) => sleep(Duration::from_millis(delay)),
+        None => wait(),
+    }
+}
+
+/// Terminal output that turns `previous` into `next` on screen, assuming `previous` was drawn
+/// from the top left. Only runs of changed characters are rewritten, using cursor positioning,
+/// so large boards that change a little each step don't redraw everything. Leaves the cursor
+/// below `next`.
+fn redraw(previous: &str, next: &str) -> String {
+    let old: Vec<Vec<char>> = previous.lines().map(|l| l.chars().collect()).collect();
+    let new: Vec<Vec<char>> = next.lines().map(|l| l.chars().collect()).collect();
+    let mut out = String::new();
+
+    for (row, line) in new.iter().enumerate() {
+        let before = old.get(row).map_or(&[][..], |l| l.as_slice());
+
+        let mut col = 0;
+        while col < line.len() {
+            if before.get(col) == Some(&line[col]) {
+                col += 1;
+                continue;
+            }
+
+            let start = col;
+            while col < line.len() && before.get(col) != Some(&line[col]) {
+                col += 1;
+            }
+            // Terminal rows and columns are 1-based
+            out.push_str(&format!("\x1b[{};{}H", row + 1, start + 1));
+            out.extend(&line[start..col]);
+        }
+
+        if before.len() > line.len() {
+            out.push_str(&format!("\x1b[{};{}H\x1b[K", row + 1, line.len() + 1));
+        }
+    }
+
+    // Clear anything left over from a taller previous frame
+    out.push_str(&format!("\x1b[{};1H", new.len() + 1));
+    if old.len() > new.len() {
+        out.push_str("\x1b[J");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redraw_changed_cells() {
+        let previous = "== 1 ==\n#..\n...\n";
+        let next = "== 2 ==\n#..\n.##\n";
+
+        assert_eq!(redraw(previous, next), "\x1b[1;4H2\x1b[3;2H##\x1b[4;1H");
+        assert_eq!(redraw(next, next), "\x1b[4;1H");
+    }
+
+    #[test]
+    fn test_redraw_size_changes() {
+        // Shorter lines are cleared to the end, and leftover lines below are cleared
+        assert_eq!(
+            redraw("abcd\nxy\nz\n", "ab\nxyz\n"),
+            "\x1b[1;3H\x1b[K\x1b[2;3Hz\x1b[3;1H\x1b[J"
+        );
+    }
+}
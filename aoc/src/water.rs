@@ -0,0 +1,216 @@
+use crate::grid_2d::{Coord, Dir};
+use std::collections::HashSet;
+
+/// Where water ended up after being poured over some clay
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reservoir {
+    /// Tiles water passed through before flowing away
+    pub flowing: HashSet<Coord>,
+    /// Tiles where water came to rest in a basin
+    pub settled: HashSet<Coord>,
+}
+
+impl Reservoir {
+    /// Every tile that water reached
+    pub fn wet(&self) -> usize {
+        self.flowing.len() + self.settled.len()
+    }
+}
+
+/// Simulation state while pouring
+struct Pour<'a> {
+    clay: &'a HashSet<Coord>,
+    max_row: i32,
+    reservoir: Reservoir,
+}
+
+impl Pour<'_> {
+    fn blocked(&self, c: &Coord) -> bool {
+        self.clay.contains(c) || self.reservoir.settled.contains(c)
+    }
+
+    /// Let water fall into `c`. Returns whether it escapes, either off the bottom of the scan or
+    /// by joining water that already does, rather than being held up.
+    fn fall(&mut self, c: Coord) -> bool {
+        if c.0 > self.max_row {
+            return true;
+        }
+        if self.blocked(&c) {
+            return false;
+        }
+        if self.reservoir.flowing.contains(&c) {
+            return true;
+        }
+
+        self.reservoir.flowing.insert(c);
+        if self.fall(c + Dir::South) {
+            return true;
+        }
+
+        // Held up from below, so spread out to either side
+        let (left_escapes, left) = self.spread(c, Dir::West);
+        let (right_escapes, right) = self.spread(c, Dir::East);
+        if left_escapes || right_escapes {
+            return true;
+        }
+
+        // Walled in on both sides, so the row fills up
+        for col in left.1..=right.1 {
+            let tile = Coord(c.0, col);
+            self.reservoir.flowing.remove(&tile);
+            self.reservoir.settled.insert(tile);
+        }
+        false
+    }
+
+    /// Spread sideways from `c` until hitting a wall or falling off an edge. Returns whether the
+    /// water escapes, and the last tile it reached.
+    fn spread(&mut self, c: Coord, dir: Dir) -> (bool, Coord) {
+        let mut tile = c;
+        loop {
+            let next = tile + dir;
+            if self.blocked(&next) {
+                return (false, tile);
+            }
+
+            tile = next;
+            self.reservoir.flowing.insert(tile);
+            if self.fall(tile + Dir::South) {
+                return (true, tile);
+            }
+        }
+    }
+}
+
+/// Pour water from a spring at `source` onto a scan of `clay` tiles, with rows increasing
+/// downwards. Water falls until held up by clay or settled water, spreads sideways, settles in
+/// basins, and overflows their sides.
+///
+/// Only tiles between the highest and lowest rows of clay are reported, since everything else
+/// extends forever. The simulation recurses once per tile of falling water, so very tall scans
+/// may need a larger stack.
+///
+/// # Examples
+/// ```
+/// use aoc::grid_2d::Coord;
+/// use aoc::water::pour;
+/// use std::collections::HashSet;
+///
+/// // A U-shaped bucket, 3 tiles wide inside
+/// let mut clay = HashSet::new();
+/// for row in 1..=3 {
+///     clay.insert(Coord(row, 0));
+///     clay.insert(Coord(row, 4));
+/// }
+/// for col in 0..=4 {
+///     clay.insert(Coord(4, col));
+/// }
+///
+/// let reservoir = pour(&clay, Coord(0, 2));
+///
+/// assert_eq!(reservoir.settled.len(), 9);
+/// assert_eq!(reservoir.flowing.len(), 8);
+/// ```
+pub fn pour(clay: &HashSet<Coord>, source: Coord) -> Reservoir {
+    let (Some(min_row), Some(max_row)) = (
+        clay.iter().map(|c| c.0).min(),
+        clay.iter().map(|c| c.0).max(),
+    ) else {
+        return Reservoir::default();
+    };
+
+    let mut pour = Pour {
+        clay,
+        max_row,
+        reservoir: Reservoir::default(),
+    };
+    pour.fall(source);
+
+    let mut reservoir = pour.reservoir;
+    reservoir.flowing.retain(|c| c.0 >= min_row);
+    reservoir.settled.retain(|c| c.0 >= min_row);
+    reservoir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse scan lines like `x=495, y=2..7`
+    fn parse_scan(input: &str) -> HashSet<Coord> {
+        let range = |s: &str| -> (i32, i32) {
+            match s.split_once("..") {
+                Some((a, b)) => (a.parse().unwrap(), b.parse().unwrap()),
+                None => (s.parse().unwrap(), s.parse().unwrap()),
+            }
+        };
+
+        let mut clay = HashSet::new();
+        for line in input.lines() {
+            let (first, second) = line.split_once(", ").unwrap();
+            let (a, b) = (range(&first[2..]), range(&second[2..]));
+            let (xs, ys) = if first.starts_with('x') {
+                (a, b)
+            } else {
+                (b, a)
+            };
+
+            for x in xs.0..=xs.1 {
+                for y in ys.0..=ys.1 {
+                    clay.insert(Coord(y, x));
+                }
+            }
+        }
+        clay
+    }
+
+    #[test]
+    fn test_example() {
+        // 2018 day 17
+        let clay = parse_scan(
+            "\
+x=495, y=2..7
+y=7, x=495..501
+x=501, y=3..7
+x=498, y=2..4
+x=506, y=1..2
+x=498, y=10..13
+x=504, y=10..13
+y=13, x=498..504",
+        );
+
+        let reservoir = pour(&clay, Coord(0, 500));
+
+        assert_eq!(reservoir.wet(), 57);
+        assert_eq!(reservoir.settled.len(), 29);
+    }
+
+    #[test]
+    fn test_overflow_into_shared_basin() {
+        // A shelf that overflows on both sides into one wide basin below
+        let mut clay = HashSet::new();
+        for col in 4..=6 {
+            clay.insert(Coord(2, col));
+        }
+        for row in 4..=7 {
+            clay.insert(Coord(row, 0));
+            clay.insert(Coord(row, 10));
+        }
+        for col in 0..=10 {
+            clay.insert(Coord(8, col));
+        }
+
+        let reservoir = pour(&clay, Coord(0, 5));
+
+        // The basin fills all 4 rows of its 9 wide interior, then overflows both walls
+        assert_eq!(reservoir.settled.len(), 36);
+        assert!(reservoir.flowing.contains(&Coord(3, -1)));
+        assert!(reservoir.flowing.contains(&Coord(8, 11)));
+        assert!(!reservoir.flowing.contains(&Coord(9, 11)));
+    }
+
+    #[test]
+    fn test_no_clay() {
+        assert_eq!(pour(&HashSet::new(), Coord(0, 0)), Reservoir::default());
+    }
+}
@@ -0,0 +1,16 @@
+use aoc::geninput::GenInput;
+use aoc::rng::Rng;
+
+/// `size` random rotations of the dial, eg. `L68`
+pub struct Rotations;
+
+impl GenInput for Rotations {
+    fn generate(size: usize, rng: &mut Rng) -> String {
+        (0..size)
+            .map(|_| {
+                let dir = if rng.chance(0.5) { 'L' } else { 'R' };
+                format!("{}{}\n", dir, rng.range(1, 999))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,6 @@
+pub mod geninput;
+pub mod part_1;
+pub mod part_2;
+
+aoc::register_solution!(day = 1);
+aoc::register_generator!(day = 1, geninput::Rotations);
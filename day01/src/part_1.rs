@@ -1,22 +1,25 @@
-pub fn solution(input: &str) -> usize {
-    input
-        .lines()
-        .map(|line| {
-            let (dir, num) = line.split_at(1);
-            let num = num.parse::<i32>().unwrap();
-
-            (dir, num)
-        })
-        .scan(50, |cur, (dir, num)| {
-            *cur = match dir {
-                "L" => (*cur - num).rem_euclid(100),
-                "R" => (*cur + num).rem_euclid(100),
-                _ => panic!("Unrecognized direction {}", dir),
-            };
-            Some(*cur)
-        })
-        .filter(|&e| e == 0)
-        .count()
+use aoc::Result;
+use aoc::modular::ModularCounter;
+use aoc::parse::prefixed_ints_with;
+
+pub fn solution(input: &str) -> Result<usize> {
+    let mut dial = ModularCounter::new(100, 50);
+    let mut zeros = 0;
+
+    for instruction in prefixed_ints_with(input, &['L', 'R']) {
+        let delta = match instruction? {
+            ('L', num) => -num,
+            ('R', num) => num,
+            _ => unreachable!("only L and R are parsed"),
+        };
+
+        dial.advance(delta);
+        if dial.position() == 0 {
+            zeros += 1;
+        }
+    }
+
+    Ok(zeros)
 }
 
 #[cfg(test)]
@@ -26,7 +29,7 @@ mod tests {
     #[test]
     fn test_example() {
         let input = include_str!("../example.txt");
-        let res = solution(input);
+        let res = solution(input).unwrap();
 
         assert_eq!(res, 3);
     }
@@ -34,7 +37,7 @@ mod tests {
     #[test]
     fn test_input() {
         let input = include_str!("../input.txt");
-        let res = solution(input);
+        let res = solution(input).unwrap();
 
         assert_eq!(res, 1011);
     }
@@ -1,68 +1,49 @@
-use std::iter;
-
-/// Brute force solution, same as part 1 just expanding out into individual inputs
-pub fn solution(input: &str) -> usize {
-    input
-        .lines()
-        .map(|line| {
-            let (dir, num) = line.split_at(1);
-            let num = num.parse::<usize>().unwrap();
-
-            // This and the .flatten() are the only thing that changed
-            iter::repeat((dir, 1i32)).take(num)
-        })
-        .flatten()
-        .scan(50, |cur, (dir, num)| {
-            *cur = match dir {
-                "L" => (*cur - num).rem_euclid(100),
-                "R" => (*cur + num).rem_euclid(100),
-                _ => panic!("Unrecognized direction {}", dir),
-            };
-            Some(*cur)
-        })
-        .filter(|&e| e == 0)
-        .count()
-}
+use aoc::Result;
+use aoc::modular::ModularCounter;
+use aoc::parse::prefixed_ints_with;
 
-/// Smarter solution that doesn't involve brute force. This doesn't quite work,
-/// there are some more edge cases.
-pub fn solution_smart(input: &str) -> usize {
-    input
-        .lines()
-        .map(|line| {
-            let (dir, num) = line.split_at(1);
-            let num = num.parse::<i32>().unwrap();
-
-            (dir, num)
-        })
-        .scan(50, |cur, (dir, num)| {
-            let next = match dir {
-                "L" => *cur - num,
-                "R" => *cur + num,
-                _ => panic!("Unrecognized direction {}", dir),
-            };
-
-            // Quotient gives us roughly how many times we passed zero as a result of this operation
-            let mut times_passed_zero = next.div_euclid(100).abs();
-            // There are some edge cases. TODO: There are more edge cases to fix!
-            if *cur == 0 && times_passed_zero > 0 {
-                times_passed_zero -= 1;
-            } else if (next == 0 && times_passed_zero == 0) {
-                times_passed_zero += 1;
-            }
+/// Count how many times the dial points at 0, including while passing it during a rotation.
+/// [`ModularCounter`] counts the zeros of each rotation directly, rather than stepping through
+/// every click.
+pub fn solution(input: &str) -> Result<usize> {
+    let mut dial = ModularCounter::new(100, 50);
+    let mut zeros = 0;
+
+    for instruction in prefixed_ints_with(input, &['L', 'R']) {
+        let delta = match instruction? {
+            ('L', num) => -num,
+            ('R', num) => num,
+            _ => unreachable!("only L and R are parsed"),
+        };
+
+        zeros += dial.advance(delta) as usize;
+    }
+
+    Ok(zeros)
+}
 
-            // Remainder - what number we ended up on
-            let rem = next.rem_euclid(100);
+/// Brute force solution, same as part 1 just expanding out into individual clicks. Kept as a
+/// reference to check [`solution`] against.
+pub fn solution_brute_force(input: &str) -> Result<usize> {
+    let mut dial = 50;
+    let mut zeros = 0;
 
-            // println!(
-            //     "{dir}{num}\tcur: {cur:4}\tnext: {next:4}\trem: {rem:02}\ttpz: {times_passed_zero}"
-            // );
+    for instruction in prefixed_ints_with(input, &['L', 'R']) {
+        let (step, num) = match instruction? {
+            ('L', num) => (-1i32, num),
+            ('R', num) => (1, num),
+            _ => unreachable!("only L and R are parsed"),
+        };
 
-            *cur = rem;
+        for _ in 0..num {
+            dial = (dial + step).rem_euclid(100);
+            if dial == 0 {
+                zeros += 1;
+            }
+        }
+    }
 
-            Some(times_passed_zero as usize)
-        })
-        .sum()
+    Ok(zeros)
 }
 
 #[cfg(test)]
@@ -72,7 +53,7 @@ mod tests {
     #[test]
     fn test_example() {
         let input = include_str!("../example.txt");
-        let res = solution(input);
+        let res = solution(input).unwrap();
 
         assert_eq!(res, 6);
     }
@@ -80,8 +61,65 @@ mod tests {
     #[test]
     fn test_input() {
         let input = include_str!("../input.txt");
-        let res = solution(input);
+        let res = solution(input).unwrap();
 
         assert_eq!(res, 5937);
     }
+
+    fn check(input: &str, expected: usize) {
+        assert_eq!(solution(input).unwrap(), expected, "{:?}", input);
+        assert_eq!(
+            solution_brute_force(input).unwrap(),
+            expected,
+            "{:?}",
+            input
+        );
+    }
+
+    #[test]
+    fn test_end_at_zero() {
+        check("L50", 1);
+        check("R50", 1);
+    }
+
+    #[test]
+    fn test_start_at_zero() {
+        check("L50\nL5", 1);
+        check("L50\nR5", 1);
+        check("L50\nL100", 2);
+        check("R50\nR100", 2);
+    }
+
+    #[test]
+    fn test_multiple_wraps() {
+        check("R1000", 10);
+        check("L1050", 11);
+        check("L49\nL201", 3);
+        check("R49\nR201", 3);
+    }
+
+    #[test]
+    fn test_zero_length_moves() {
+        check("R0", 0);
+        check("L50\nR0\nL0", 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            solution("L5\nX12").unwrap_err().to_string(),
+            "failed to parse line 2: \"X12\" (expected one of L, R)"
+        );
+        assert_eq!(
+            solution("L5\nR").unwrap_err().to_string(),
+            "failed to parse line 2: \"R\" (expected a number after R: cannot parse integer from empty string)"
+        );
+    }
+
+    aoc::proptest_equiv!(
+        test_matches_brute_force,
+        solution_brute_force,
+        solution,
+        crate::geninput::Rotations
+    );
 }
@@ -0,0 +1,4 @@
+pub mod part_1;
+pub mod part_2;
+
+aoc::register_solution!(day = 0);
@@ -1,5 +1,7 @@
-pub fn solution(_input: &str) -> usize {
-    0
+use aoc::Result;
+
+pub fn solution(_input: &str) -> Result<usize> {
+    Ok(0)
 }
 
 #[cfg(test)]
@@ -9,7 +11,7 @@ mod tests {
     #[test]
     fn test_example() {
         let input = include_str!("../example.txt");
-        let res = solution(input);
+        let res = solution(input).unwrap();
 
         assert_eq!(res, 0);
     }
@@ -17,7 +19,7 @@ mod tests {
     #[test]
     fn test_input() {
         let input = include_str!("../input.txt");
-        let res = solution(input);
+        let res = solution(input).unwrap();
 
         assert_eq!(res, 0);
     }